@@ -0,0 +1,14 @@
+#![no_main]
+//! Pasa lineas cualquiera por el parseo de los comandos DCC, directo y dentro de un CTCP.
+use irc_super_main::{to_dcc_command, upgrade_dcc_command};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let line = match std::str::from_utf8(data) {
+        Ok(line) => line,
+        Err(_) => return,
+    };
+
+    let _ = upgrade_dcc_command(line);
+    let _ = to_dcc_command(&format!("alice:\u{1}CTCP DCC {}\u{1}", line));
+});
@@ -0,0 +1,19 @@
+#![no_main]
+//! Parsea lineas cualquiera y las pasa por el mensaje de su comando: no tiene que haber panics
+//! y lo que se serializa se tiene que volver a parsear igual.
+use irc_super_main::message::GenericMessage;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let line = match std::str::from_utf8(data) {
+        Ok(line) => line,
+        Err(_) => return,
+    };
+
+    let canonical = match GenericMessage::parse(line).and_then(|m| m.canonical()) {
+        Ok(canonical) => canonical,
+        Err(_) => return,
+    };
+    let again = GenericMessage::parse(&canonical).and_then(|m| m.canonical());
+    assert_eq!(again.as_ref(), Ok(&canonical), "from {:?}", line);
+});
@@ -0,0 +1,121 @@
+//! Modulo que se centra en las funcionalidades referentes a correr el server o el cliente desde la linea de comandos.
+#[cfg(feature = "gui")]
+use crate::gui::components::irc::ircwindow::run_app;
+#[cfg(feature = "gui")]
+use crate::gui::plugins::{PluginHost, PLUGINS_DIR};
+use crate::irc::model::client::Client;
+use crate::irc::model::server::Server;
+use crate::irc::model::{ConnectionError, WHAT_TO_RUN_POS};
+use std::io::stdin;
+#[cfg(feature = "gui")]
+use std::{
+    path::Path,
+    sync::{mpsc::channel, Arc, Mutex},
+    thread,
+};
+
+/// Corre el server o el cliente segun el primer argumento, como el binario irc-super-main.
+pub fn run(argv: Vec<String>) -> Result<(), ConnectionError> {
+    if argv[WHAT_TO_RUN_POS] == "server" {
+        // run as server
+        let mut s = Server::create(&argv)?;
+
+        if let Err(reason) = s.load_file() {
+            println!("[SERVER - PERSISTENCY] Can't load datafile {}", reason)
+        }
+
+        match Server::server_run(&s, None) {
+            Ok(_) => {}
+            Err(_) => return Err(ConnectionError::InternalServerError),
+        }
+        if s.is_restarting() {
+            Server::relaunch(&argv);
+        }
+    } else if argv[WHAT_TO_RUN_POS] == "server-connect" {
+        let mut s = Server::create(&argv)?;
+
+        if let Err(reason) = s.load_file() {
+            println!("[SERVER - PERSISTENCY] Can't load datafile {}", reason)
+        }
+
+        let uplink = Server::server_connect(&s, &argv)?;
+
+        match Server::server_run(&s, Some(uplink)) {
+            Ok(_) => {}
+            Err(_) => return Err(ConnectionError::InternalServerError),
+        }
+        if s.is_restarting() {
+            Server::relaunch(&argv);
+        }
+    } else if argv[WHAT_TO_RUN_POS] == "client" {
+        // run as client
+        run_gui_client(argv)?;
+    } else if argv[WHAT_TO_RUN_POS] == "client-no-gui" {
+        let c = match Client::create_fromargs(argv) {
+            Ok(v) => v,
+            Err(_) => {
+                println!("[CLIENT] Can't properly create client");
+                return Err(ConnectionError::InternalServerError);
+            }
+        };
+
+        if let Err(e) = c.client_run(&mut stdin()) {
+            println!("{:?}", e);
+        };
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "gui")]
+fn run_gui_client(argv: Vec<String>) -> Result<(), ConnectionError> {
+    let c = match Client::create_fromargs(argv) {
+        Ok(v) => v,
+        Err(_) => {
+            println!("[CLIENT] Can't properly create client");
+            return Err(ConnectionError::InternalServerError);
+        }
+    };
+
+    let mut plugins = PluginHost::new();
+    for error in plugins.load_dir(Path::new(PLUGINS_DIR)) {
+        println!("[CLIENT] Can't load plugin {}", error);
+    }
+    for name in plugins.names() {
+        println!("[CLIENT] Loaded plugin {}", name);
+    }
+    let plugins = Arc::new(Mutex::new(plugins));
+
+    // GTK-CLIENT to SERVER
+    let (tx_uitosv, rx_uitosv) = channel();
+
+    // SERVER to CLIENT-GTK
+    let (tx_svtoui, rx_svtoui) = channel();
+
+    let client_plugins = plugins.clone();
+    let client_thread = thread::spawn(move || {
+        if let Err(e) = c.run_gui_comms(rx_uitosv, tx_svtoui, client_plugins) {
+            println!("[CLIENT] Can't run from GUI {:?}", e);
+        };
+    });
+
+    run_app(tx_uitosv, rx_svtoui, plugins);
+
+    let tid = client_thread.thread().id();
+
+    match client_thread.join() {
+        Ok(_) => println!("[CLIENT - THREAD MANAGEMENT]: Cleaning thread {:?}", tid),
+        Err(e) => println!(
+            "[SERVER - THREAD MANAGEMENT]: Couldn't clean thread {:?}, {:?}",
+            tid, e
+        ),
+    };
+
+    Ok(())
+}
+
+#[cfg(not(feature = "gui"))]
+fn run_gui_client(_argv: Vec<String>) -> Result<(), ConnectionError> {
+    println!("[CLIENT] Built without the gui feature, run client-no-gui instead");
+    Err(ConnectionError::InvalidArguments)
+}
@@ -1,8 +1,8 @@
 use crate::gui::actors::chat_actor::ChatActor;
-use crate::gui::utils::build;
+use crate::gui::utils::{build, get_selected_nick};
 use crate::irc::ctcp::constants::{CHAT_PROTOCOL, DCC_CHAT};
 use crate::irc::ctcp::dcc_relay::DccRelay;
-use crate::irc::ctcp::utils::{get_selected_nick, to_notice_command};
+use crate::irc::ctcp::utils::to_notice_command;
 
 use gtk::prelude::GtkWindowExt;
 use gtk::traits::ButtonExt;
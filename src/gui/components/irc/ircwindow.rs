@@ -12,6 +12,7 @@ use std::{
 };
 
 use crate::irc::{ctcp::dcc_relay::DccRelay, message::Command};
+use crate::try_lock;
 
 use crate::gui::actors::chat_actor::ChatActor;
 use crate::gui::actors::send_actor::SendActor;
@@ -22,6 +23,7 @@ use crate::gui::{
     },
     constants::{COMMANDS, IRC_WELCOME},
     message_hub::MessageHub,
+    plugins::{PluginReactor, SharedPlugins},
     utils::{send_message, to_server_message},
     GuiMessage, IncomingMessage,
 };
@@ -31,7 +33,11 @@ use super::user_sidebar::login_watcher::LoginWatcher;
 use super::user_sidebar::model::nick_storage::NickStorage;
 use super::user_sidebar::view::display::UserSidebar;
 
-fn show_ui(tx_uitosv: Sender<GuiMessage>, rx_svtoui: Receiver<IncomingMessage>) {
+fn show_ui(
+    tx_uitosv: Sender<GuiMessage>,
+    rx_svtoui: Receiver<IncomingMessage>,
+    plugins: SharedPlugins,
+) {
     gtk::init().expect("Couldn't open IrcWindow");
 
     let glade_src = include_str!("../../templates/ircwindow.glade");
@@ -49,6 +55,7 @@ fn show_ui(tx_uitosv: Sender<GuiMessage>, rx_svtoui: Receiver<IncomingMessage>)
         "button_message",
         tx_uitosv.clone(),
     )
+    .with_commands(glib::clone!(@strong plugins => move |text| try_lock!(plugins).command(text)))
     .hook(
         builder.object("entry_message").expect("Couldn't get entry"),
         &to_server_message,
@@ -89,6 +96,8 @@ fn show_ui(tx_uitosv: Sender<GuiMessage>, rx_svtoui: Receiver<IncomingMessage>)
     let login_watcher = LoginWatcher::new(tx_uitosv.clone());
     message_hub.add_reactor(login_watcher);
 
+    message_hub.add_reactor(PluginReactor::new(plugins, tx_uitosv.clone()));
+
     let w = irc_window.clone();
     let tx = tx_uitosv.clone();
 
@@ -145,6 +154,10 @@ fn build_dcc(
     DCCButton::start(builder).hook(dcc_relay, user_storage);
 }
 
-pub fn run_app(tx_uitosv: Sender<GuiMessage>, rx_svtoui: Receiver<IncomingMessage>) {
-    show_ui(tx_uitosv, rx_svtoui);
+pub fn run_app(
+    tx_uitosv: Sender<GuiMessage>,
+    rx_svtoui: Receiver<IncomingMessage>,
+    plugins: SharedPlugins,
+) {
+    show_ui(tx_uitosv, rx_svtoui, plugins);
 }
@@ -13,6 +13,9 @@ use crate::{
 };
 
 type F<T> = &'static dyn Fn(String) -> T;
+/// Comandos propios de la entrada (ej los de los plugins): devuelve las lineas a mandar
+/// en lugar del texto, o None si el texto no es uno de ellos.
+type Commands = Box<dyn Fn(&str) -> Option<Vec<String>>>;
 
 pub struct UserEntry<T> {
     gtk_window: ApplicationWindow,
@@ -20,6 +23,7 @@ pub struct UserEntry<T> {
     gtk_scrolled: ScrolledWindow,
     gtk_entrybtn: Button,
     tx: Sender<T>,
+    commands: Option<Commands>,
 }
 
 impl<T> UserEntry<T> {
@@ -43,9 +47,18 @@ impl<T> UserEntry<T> {
                 .object(entrybtn_id)
                 .expect("Couldn't get scrolled window"),
             tx,
+            commands: None,
         }
     }
 
+    pub fn with_commands<C>(mut self, commands: C) -> Self
+    where
+        C: Fn(&str) -> Option<Vec<String>> + 'static,
+    {
+        self.commands = Some(Box::new(commands));
+        self
+    }
+
     fn adjust_scrolled(&self) {
         let adj = self.gtk_scrolled.vadjustment();
         adj.set_value(adj.lower());
@@ -62,9 +75,15 @@ impl<T> UserEntry<T> {
         if msg.is_empty() {
             return;
         }
-        let r = transformer(msg);
-        if send_message(&self.tx, r).is_err() {
-            self.gtk_window.close();
+        let lines = match self.commands.as_ref().and_then(|c| c(&msg)) {
+            Some(lines) => lines,
+            None => vec![msg],
+        };
+        for line in lines {
+            if send_message(&self.tx, transformer(line)).is_err() {
+                self.gtk_window.close();
+                return;
+            }
         }
     }
 
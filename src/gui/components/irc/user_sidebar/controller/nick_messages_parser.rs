@@ -14,6 +14,12 @@ pub struct NickParser {
     storage: Rc<RefCell<NickStorage>>,
 }
 
+impl Default for NickParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NickParser {
     pub fn new() -> Self {
         Self {
@@ -7,6 +7,12 @@ pub struct NickStorage {
     current_nick: Option<String>,
 }
 
+impl Default for NickStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NickStorage {
     pub fn new() -> Self {
         Self {
@@ -1,9 +1,17 @@
 use crate::irc::ctcp::message::FileTransferStatus;
 
+#[cfg(feature = "gui")]
 pub mod actors;
+#[cfg(feature = "gui")]
 pub mod components;
+#[cfg(feature = "gui")]
 pub mod constants;
+#[cfg(feature = "gui")]
 pub mod message_hub;
+// Only the GTK client loads plugins
+#[cfg(all(feature = "gui", feature = "plugins"))]
+pub mod plugins;
+#[cfg(feature = "gui")]
 pub mod utils;
 
 type Message = String;
@@ -11,6 +19,7 @@ type RawMessage = Vec<u8>;
 type UcID = usize;
 type TotalSize = u64;
 type Transferred = u64;
+#[cfg(feature = "gui")]
 pub enum GuiMessage {
     Close,
     MessageIRC(Message),
@@ -32,6 +41,7 @@ pub enum DccCommands {
     FileTransfer(FileTransferStatus),
 }
 
+#[cfg(feature = "gui")]
 pub trait Reactor {
     fn react(&mut self, messages: &[IncomingMessage]) {
         for message in messages {
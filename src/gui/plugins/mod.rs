@@ -0,0 +1,332 @@
+//! Modulo que se centra en las funcionalidades referentes a los plugins del cliente.
+use std::{
+    fs,
+    path::Path,
+    sync::{mpsc::Sender, Arc, Mutex},
+};
+
+use rhai::{CallFnOptions, Dynamic, Engine, Scope, AST};
+
+use crate::try_lock;
+
+use super::{GuiMessage, IncomingMessage, Reactor};
+
+pub const PLUGINS_DIR: &str = "./plugins";
+const PLUGIN_EXTENSION: &str = "rhai";
+const ON_LINE: &str = "on_line";
+const ON_SEND: &str = "on_send";
+const COMMAND_PREFIX: &str = "command_";
+/// Corta los scripts que se cuelgan, para que no frenen la interfaz.
+const MAX_OPERATIONS: u64 = 100_000;
+
+pub type SharedPlugins = Arc<Mutex<PluginHost>>;
+
+/// Script de Rhai cargado, con los ganchos que define.
+struct Plugin {
+    name: String,
+    ast: AST,
+    reacts: bool,
+    filters: bool,
+    commands: Vec<String>,
+}
+
+/// Plugins del cliente, escritos en Rhai. Cada script puede definir:
+/// - `fn on_line(line)`: recibe cada linea del server; lo que devuelve (un texto o un array
+///   de textos) se le manda al server.
+/// - `fn command_<nombre>(args)`: comando `/<nombre> args` para la entrada del usuario,
+///   devuelve lo que se manda al server.
+/// - `fn on_send(line)`: filtro de lo que se manda al server; devuelve la linea (cambiada o no),
+///   o () para no mandarla.
+///
+/// Los scripts tienen `privmsg(target, text)` y `notice(target, text)` para armar los mensajes.
+pub struct PluginHost {
+    engine: Engine,
+    plugins: Vec<Plugin>,
+}
+
+impl Default for PluginHost {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PluginHost {
+    pub fn new() -> Self {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.register_fn("privmsg", |target: &str, text: &str| {
+            format!("PRIVMSG {} :{}", target, text)
+        });
+        engine.register_fn("notice", |target: &str, text: &str| {
+            format!("NOTICE {} :{}", target, text)
+        });
+
+        Self {
+            engine,
+            plugins: vec![],
+        }
+    }
+
+    /// Carga los .rhai del directorio en orden alfabetico. Devuelve los errores de los que no
+    /// se pudieron cargar; que no exista el directorio no es un error.
+    pub fn load_dir(&mut self, dir: &Path) -> Vec<String> {
+        let mut paths: Vec<_> = match fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|e| e == PLUGIN_EXTENSION))
+                .collect(),
+            Err(_) => return vec![],
+        };
+        paths.sort();
+
+        let mut errors = vec![];
+        for path in paths {
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let loaded = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|source| self.load(&name, &source));
+            if let Err(e) = loaded {
+                errors.push(format!("{}: {}", path.display(), e));
+            }
+        }
+        errors
+    }
+
+    pub fn load(&mut self, name: &str, source: &str) -> Result<(), String> {
+        let ast = self.engine.compile(source).map_err(|e| e.to_string())?;
+
+        let hooks: Vec<&str> = ast
+            .iter_functions()
+            .filter(|f| f.params.len() == 1)
+            .map(|f| f.name)
+            .collect();
+        let commands = hooks
+            .iter()
+            .filter_map(|f| f.strip_prefix(COMMAND_PREFIX))
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+
+        self.plugins.push(Plugin {
+            name: name.to_owned(),
+            reacts: hooks.contains(&ON_LINE),
+            filters: hooks.contains(&ON_SEND),
+            commands,
+            ast,
+        });
+        Ok(())
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.plugins.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    /// Lineas que los plugins quieren mandar al server al recibir esta.
+    pub fn on_line(&self, line: &str) -> Vec<String> {
+        self.plugins
+            .iter()
+            .filter(|p| p.reacts)
+            .filter_map(|p| self.call(p, ON_LINE, line))
+            .flat_map(to_lines)
+            .collect()
+    }
+
+    /// Si el texto es un comando de algun plugin (ej "/greet Tomi"), las lineas a mandar.
+    pub fn command(&self, text: &str) -> Option<Vec<String>> {
+        let (name, args) = text
+            .strip_prefix('/')?
+            .split_once(' ')
+            .unwrap_or((&text[1..], ""));
+        let name = name.to_ascii_lowercase();
+        let plugin = self.plugins.iter().find(|p| p.commands.contains(&name))?;
+
+        let function = format!("{}{}", COMMAND_PREFIX, name);
+        Some(
+            self.call(plugin, &function, args.trim())
+                .map(to_lines)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Pasa la linea por los filtros en orden. None si algun plugin la descarto.
+    pub fn filter(&self, line: &str) -> Option<String> {
+        let mut line = line.to_owned();
+        for plugin in self.plugins.iter().filter(|p| p.filters) {
+            let result = self.call(plugin, ON_SEND, &line)?;
+            if result.is_unit() {
+                return None;
+            }
+            line = result.to_string();
+        }
+        Some(line)
+    }
+
+    /// Los errores de los scripts se informan y no cortan al cliente; en un filtro,
+    /// un error deja pasar la linea sin cambios.
+    fn call(&self, plugin: &Plugin, function: &str, argument: &str) -> Option<Dynamic> {
+        let options = CallFnOptions::new().eval_ast(false);
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            &plugin.ast,
+            function,
+            (argument.to_owned(),),
+        );
+
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                println!("[PLUGIN {}] {} failed: {}", plugin.name, function, e);
+                (function == ON_SEND).then(|| Dynamic::from(argument.to_owned()))
+            }
+        }
+    }
+}
+
+/// Lo que devolvio un script como lineas para el server: () no manda nada, un array manda
+/// cada elemento y cualquier otro valor se manda como texto.
+fn to_lines(value: Dynamic) -> Vec<String> {
+    if value.is_unit() {
+        return vec![];
+    }
+    if value.is_array() {
+        return value
+            .cast::<rhai::Array>()
+            .into_iter()
+            .flat_map(to_lines)
+            .collect();
+    }
+    vec![value.to_string()]
+}
+
+/// Reactor que le pasa a los plugins las lineas del server y manda lo que responden.
+pub struct PluginReactor {
+    plugins: SharedPlugins,
+    tx: Sender<GuiMessage>,
+}
+
+impl PluginReactor {
+    pub fn new(plugins: SharedPlugins, tx: Sender<GuiMessage>) -> Self {
+        Self { plugins, tx }
+    }
+}
+
+impl Reactor for PluginReactor {
+    fn react_single(&mut self, message: &IncomingMessage) {
+        if let IncomingMessage::Server(line) = message {
+            for reply in try_lock!(self.plugins).on_line(line) {
+                let _ = self.tx.send(GuiMessage::MessageIRC(reply));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod plugin_tests {
+    use std::sync::mpsc::channel;
+
+    use super::*;
+
+    fn host(sources: &[(&str, &str)]) -> PluginHost {
+        let mut host = PluginHost::new();
+        for (name, source) in sources {
+            host.load(name, source).unwrap();
+        }
+        host
+    }
+
+    #[test]
+    fn test_reactors_answer_incoming_lines() {
+        let host = host(&[
+            (
+                "pong",
+                r##"fn on_line(line) { if line.starts_with("#rust tomi: ping") { privmsg("#rust", "pong") } }"##,
+            ),
+            (
+                "welcome",
+                r##"fn on_line(line) { if line.starts_with("353: ") { ["NOTICE a :hi", "NOTICE b :hi"] } }"##,
+            ),
+        ]);
+
+        assert_eq!(
+            host.on_line("#rust tomi: ping"),
+            vec!["PRIVMSG #rust :pong"]
+        );
+        assert_eq!(
+            host.on_line("353: #rust valen"),
+            vec!["NOTICE a :hi", "NOTICE b :hi"]
+        );
+        assert!(host.on_line("#rust valen: hello").is_empty());
+        assert_eq!(host.names(), vec!["pong", "welcome"]);
+    }
+
+    #[test]
+    fn test_slash_commands() {
+        let host = host(&[(
+            "greet",
+            r##"fn command_greet(args) { privmsg(args, "hello " + args) }"##,
+        )]);
+
+        assert_eq!(
+            host.command("/GREET valen"),
+            Some(vec!["PRIVMSG valen :hello valen".to_owned()])
+        );
+        assert_eq!(host.command("/part #rust"), None);
+        assert_eq!(host.command("greet valen"), None);
+    }
+
+    #[test]
+    fn test_filters_change_or_drop_outgoing_lines() {
+        let host = host(&[
+            ("shout", r##"fn on_send(line) { line.to_upper() }"##),
+            (
+                "quiet",
+                r##"fn on_send(line) { if line.contains("SECRET") { () } else { line } }"##,
+            ),
+        ]);
+
+        assert_eq!(
+            host.filter("privmsg valen :hi").as_deref(),
+            Some("PRIVMSG VALEN :HI")
+        );
+        assert_eq!(host.filter("privmsg valen :secret"), None);
+    }
+
+    #[test]
+    fn test_broken_plugins_do_not_stop_the_client() {
+        let mut host = host(&[
+            ("loop", r##"fn on_line(line) { loop { } }"##),
+            ("fails", r##"fn on_send(line) { line.nope() }"##),
+        ]);
+        assert!(host.load("syntax", "fn on_line(line) {").is_err());
+
+        assert!(host.on_line("#rust tomi: ping").is_empty());
+        assert_eq!(host.filter("PING x").as_deref(), Some("PING x"));
+
+        let (tx, rx) = channel();
+        let plugins = Arc::new(Mutex::new(host));
+        let mut reactor = PluginReactor::new(plugins, tx);
+        reactor.react_single(&IncomingMessage::Server("PING".to_owned()));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_load_dir_reads_rhai_files_in_order() {
+        let dir = std::env::temp_dir().join(format!("irc-plugins-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("b.rhai"), "fn command_b(args) { args }").unwrap();
+        fs::write(dir.join("a.rhai"), "fn command_a(args) { args }").unwrap();
+        fs::write(dir.join("broken.rhai"), "fn on_line(").unwrap();
+        fs::write(dir.join("notes.txt"), "not a plugin").unwrap();
+
+        let mut host = PluginHost::new();
+        let errors = host.load_dir(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(host.names(), vec!["a", "b"]);
+        assert_eq!(errors.len(), 1);
+        assert!(host.load_dir(&dir).is_empty());
+    }
+}
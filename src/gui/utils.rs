@@ -1,7 +1,11 @@
 use std::sync::mpsc::{SendError, Sender};
 
-use glib::{IsA, Object};
-use gtk::{prelude::BuilderExtManual, traits::TextBufferExt, Builder, TextBuffer};
+use glib::{Cast, IsA, Object};
+use gtk::{
+    prelude::BuilderExtManual,
+    traits::{ButtonExt, ContainerExt, TextBufferExt, ToggleButtonExt},
+    Builder, ButtonBox, TextBuffer, Widget,
+};
 
 use crate::irc::constants::RPL_NICKSET;
 
@@ -64,3 +68,17 @@ where
 {
     builder.object(id).expect(&format!("Couldn't get {}", id))
 }
+
+pub fn get_selected_nick(user_container: &ButtonBox) -> Option<String> {
+    user_container
+        .children()
+        .iter()
+        .find_map(|w: &Widget| {
+            let b = w.clone().downcast::<gtk::RadioButton>().unwrap();
+            match b.is_active() {
+                true => b.label(),
+                false => None,
+            }
+        })
+        .map(|v| v.to_string())
+}
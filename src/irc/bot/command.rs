@@ -0,0 +1,304 @@
+//! Modulo que se centra en las funcionalidades referentes a los comandos de un bot.
+use std::{collections::HashMap, fmt::Display, time::Duration};
+
+/// Argumento de un comando: <nombre> es obligatorio, [nombre] opcional y con "..." al final
+/// (solo el ultimo) toma el resto de la linea.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArgSpec {
+    pub name: String,
+    pub required: bool,
+    pub rest: bool,
+}
+
+impl ArgSpec {
+    fn parse(token: &str) -> Result<Self, String> {
+        let (name, required) = if let Some(n) = token.strip_prefix('<') {
+            (n.strip_suffix('>'), true)
+        } else if let Some(n) = token.strip_prefix('[') {
+            (n.strip_suffix(']'), false)
+        } else {
+            (None, false)
+        };
+
+        let name = match name {
+            Some(n) => n,
+            None => return Err(format!("{} is not <argument> or [argument]", token)),
+        };
+        let (name, rest) = match name.strip_suffix("...") {
+            Some(n) => (n, true),
+            None => (name, false),
+        };
+        if name.is_empty() {
+            return Err(format!("{} has no name", token));
+        }
+
+        Ok(Self {
+            name: name.to_owned(),
+            required,
+            rest,
+        })
+    }
+}
+
+impl Display for ArgSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rest = if self.rest { "..." } else { "" };
+        match self.required {
+            true => write!(f, "<{}{}>", self.name, rest),
+            false => write!(f, "[{}{}]", self.name, rest),
+        }
+    }
+}
+
+/// Quien puede usar un comando.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Permission {
+    Anyone,
+    /// Operadores del canal en el que se usa el comando, en privado nadie.
+    ChannelOperator,
+    /// Clientes registrados con alguna de estas cuentas.
+    Accounts(Vec<String>),
+    /// Operadores del canal o clientes con alguna de las cuentas.
+    OperatorOrAccounts(Vec<String>),
+}
+
+impl Permission {
+    /// Si hay que preguntarle al server por el que manda el comando antes de ejecutarlo.
+    pub fn needs_lookup(&self) -> bool {
+        *self != Permission::Anyone
+    }
+
+    /// Decide con lo que respondio el server: los flags de WHO en el canal y la cuenta ("0" sin cuenta).
+    pub fn allows(&self, flags: &str, account: &str) -> bool {
+        let has_account = |accounts: &Vec<String>| {
+            account != "0" && accounts.iter().any(|a| a.eq_ignore_ascii_case(account))
+        };
+        match self {
+            Permission::Anyone => true,
+            Permission::ChannelOperator => flags.contains('@'),
+            Permission::Accounts(accounts) => has_account(accounts),
+            Permission::OperatorOrAccounts(accounts) => {
+                flags.contains('@') || has_account(accounts)
+            }
+        }
+    }
+}
+
+/// Argumentos con los que se invoco un comando, por nombre.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Arguments {
+    values: HashMap<String, String>,
+}
+
+impl Arguments {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|v| v.as_str())
+    }
+}
+
+/// Uso de un comando que ya paso los controles, lo que recibe su handler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    pub sender: String,
+    /// Canal en el que se uso, None si fue por privado.
+    pub channel: Option<String>,
+    pub arguments: Arguments,
+}
+
+impl Invocation {
+    pub fn arg(&self, name: &str) -> Option<&str> {
+        self.arguments.get(name)
+    }
+}
+
+/// Lo que hace el comando, devuelve las lineas con las que responde.
+pub type Handler = Box<dyn FnMut(&Invocation) -> Vec<String>>;
+
+/// Comando de un bot armado desde su forma de uso, por ejemplo "deploy <service> [env]".
+pub struct BotCommand {
+    pub name: String,
+    pub arguments: Vec<ArgSpec>,
+    pub description: String,
+    pub permission: Permission,
+    /// Permisos distintos al general en algunos canales, indexados en minusculas.
+    pub channel_permissions: HashMap<String, Permission>,
+    /// Tiempo que tiene que esperar cada cliente entre dos usos.
+    pub cooldown: Duration,
+    pub handler: Handler,
+}
+
+impl BotCommand {
+    pub fn new<F>(usage: &str, description: &str, handler: F) -> Result<Self, String>
+    where
+        F: FnMut(&Invocation) -> Vec<String> + 'static,
+    {
+        let mut tokens = usage.split_whitespace();
+        let name = match tokens.next() {
+            Some(n) => n.to_ascii_lowercase(),
+            None => return Err("empty usage".to_owned()),
+        };
+
+        let mut arguments: Vec<ArgSpec> = vec![];
+        for token in tokens {
+            let arg = ArgSpec::parse(token)?;
+            if let Some(last) = arguments.last() {
+                if last.rest {
+                    return Err(format!(
+                        "{} comes after {}, which takes the rest",
+                        arg, last
+                    ));
+                }
+                if arg.required && !last.required {
+                    return Err(format!("{} is required after an optional argument", arg));
+                }
+            }
+            arguments.push(arg);
+        }
+
+        Ok(Self {
+            name,
+            arguments,
+            description: description.to_owned(),
+            permission: Permission::Anyone,
+            channel_permissions: HashMap::new(),
+            cooldown: Duration::ZERO,
+            handler: Box::new(handler),
+        })
+    }
+
+    pub fn permission(mut self, permission: Permission) -> Self {
+        self.permission = permission;
+        self
+    }
+
+    /// Permiso para el canal, reemplaza al general ahi.
+    pub fn channel_permission(mut self, channel: &str, permission: Permission) -> Self {
+        self.channel_permissions
+            .insert(channel.to_ascii_lowercase(), permission);
+        self
+    }
+
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Permiso que aplica en el canal, o el general si es por privado.
+    pub fn permission_for(&self, channel: Option<&str>) -> &Permission {
+        channel
+            .and_then(|c| self.channel_permissions.get(&c.to_ascii_lowercase()))
+            .unwrap_or(&self.permission)
+    }
+
+    pub fn usage(&self, prefix: &str) -> String {
+        let mut usage = format!("{}{}", prefix, self.name);
+        for arg in &self.arguments {
+            usage.push(' ');
+            usage.push_str(&arg.to_string());
+        }
+        usage
+    }
+
+    /// Asigna el texto despues del nombre a los argumentos, o None si no respeta la forma de uso.
+    pub fn parse_arguments(&self, text: &str) -> Option<Arguments> {
+        let mut values = HashMap::new();
+        let mut rest = text.trim();
+
+        for arg in &self.arguments {
+            if rest.is_empty() {
+                if arg.required {
+                    return None;
+                }
+                break;
+            }
+
+            let value = match arg.rest {
+                true => std::mem::take(&mut rest),
+                false => match rest.split_once(char::is_whitespace) {
+                    Some((v, r)) => {
+                        rest = r.trim_start();
+                        v
+                    }
+                    None => std::mem::take(&mut rest),
+                },
+            };
+            values.insert(arg.name.to_owned(), value.to_owned());
+        }
+
+        if !rest.is_empty() {
+            return None;
+        }
+        Some(Arguments { values })
+    }
+}
+
+impl std::fmt::Debug for BotCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BotCommand")
+            .field("name", &self.name)
+            .field("arguments", &self.arguments)
+            .field("permission", &self.permission)
+            .field("channel_permissions", &self.channel_permissions)
+            .field("cooldown", &self.cooldown)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod bot_command_tests {
+    use super::*;
+
+    fn command(usage: &str) -> Result<BotCommand, String> {
+        BotCommand::new(usage, "test", |_| vec![])
+    }
+
+    #[test]
+    fn test_usage_with_required_optional_and_rest_arguments() {
+        let deploy = command("Deploy <service> [env]").unwrap();
+        let say = command("say <channel> <text...>").unwrap();
+
+        assert_eq!(deploy.name, "deploy");
+        assert_eq!(deploy.usage("!"), "!deploy <service> [env]");
+        assert_eq!(say.usage("!"), "!say <channel> <text...>");
+    }
+
+    #[test]
+    fn test_invalid_usages_error() {
+        assert!(command("").is_err());
+        assert!(command("deploy service").is_err());
+        assert!(command("deploy [env] <service>").is_err());
+        assert!(command("say <text...> <channel>").is_err());
+    }
+
+    #[test]
+    fn test_arguments_follow_the_usage() {
+        let deploy = command("deploy <service> [env]").unwrap();
+        let say = command("say <channel> <text...>").unwrap();
+
+        let args = deploy.parse_arguments("api  prod").unwrap();
+        assert_eq!(args.get("service"), Some("api"));
+        assert_eq!(args.get("env"), Some("prod"));
+        assert_eq!(deploy.parse_arguments("api").unwrap().get("env"), None);
+        assert!(deploy.parse_arguments("").is_none());
+        assert!(deploy.parse_arguments("api prod extra").is_none());
+
+        let args = say.parse_arguments("#ops hello  there").unwrap();
+        assert_eq!(args.get("text"), Some("hello  there"));
+    }
+
+    #[test]
+    fn test_channel_permissions_override_the_default() {
+        let deploy = command("deploy <service>")
+            .unwrap()
+            .permission(Permission::ChannelOperator)
+            .channel_permission("#Prod", Permission::Accounts(vec!["alice".to_owned()]));
+
+        assert_eq!(
+            deploy.permission_for(Some("#dev")),
+            &Permission::ChannelOperator
+        );
+        assert!(deploy.permission_for(Some("#prod")).allows("H", "Alice"));
+        assert!(!deploy.permission_for(Some("#prod")).allows("H@", "0"));
+        assert!(!deploy.permission_for(None).allows("H", "alice"));
+    }
+}
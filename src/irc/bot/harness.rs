@@ -0,0 +1,75 @@
+//! Modulo que se centra en las funcionalidades referentes a probar un bot sin conectarlo.
+use std::{collections::VecDeque, time::Duration, time::Instant};
+
+use crate::irc::model::session::event::Event;
+
+use super::{Bot, BotAction};
+
+/// Prueba un bot pasandole lineas del server escritas a mano, como las recibiria una sesion,
+/// y revisando en orden lo que responde. El reloj solo avanza con advance, para los cooldowns.
+///
+/// Los comandos con permisos piden un WHO: expect_lookup devuelve el token, y la respuesta se
+/// pasa con feed, por ejemplo "354: <token> #canal nick H@ cuenta" y "315: #canal :End of /WHO list".
+#[derive(Debug)]
+pub struct BotHarness {
+    bot: Bot,
+    now: Instant,
+    actions: VecDeque<BotAction>,
+}
+
+impl BotHarness {
+    pub fn new(bot: Bot) -> Self {
+        Self {
+            bot,
+            now: Instant::now(),
+            actions: VecDeque::new(),
+        }
+    }
+
+    pub fn bot(&self) -> &Bot {
+        &self.bot
+    }
+
+    /// Le pasa la linea al bot y guarda lo que responde.
+    pub fn feed(&mut self, line: &str) -> &mut Self {
+        let actions = self.bot.handle(&Event::parse(line), self.now);
+        self.actions.extend(actions);
+        self
+    }
+
+    pub fn advance(&mut self, duration: Duration) -> &mut Self {
+        self.now += duration;
+        self
+    }
+
+    /// Proxima accion del bot que todavia no se reviso.
+    pub fn next_action(&mut self) -> Option<BotAction> {
+        self.actions.pop_front()
+    }
+
+    /// Lo proximo que hace el bot es mandar el texto al destino.
+    pub fn expect_say(&mut self, target: &str, text: &str) -> &mut Self {
+        let expected = BotAction::Say {
+            target: target.to_owned(),
+            text: text.to_owned(),
+        };
+        let action = self.next_action();
+        assert_eq!(action.as_ref(), Some(&expected), "unexpected bot action");
+        self
+    }
+
+    /// Lo proximo que hace el bot es pedir un WHO de la mascara. Devuelve el token del pedido.
+    pub fn expect_lookup(&mut self, mask: &str) -> String {
+        match self.next_action() {
+            Some(BotAction::Lookup { mask: m, token }) if m.eq_ignore_ascii_case(mask) => token,
+            other => panic!("expected a lookup of {}, got {:?}", mask, other),
+        }
+    }
+
+    /// El bot no hizo nada mas.
+    pub fn expect_silence(&mut self) -> &mut Self {
+        let action = self.next_action();
+        assert_eq!(action, None, "unexpected bot action");
+        self
+    }
+}
@@ -0,0 +1,472 @@
+//! Modulo que se centra en las funcionalidades referentes a los bots de comandos sobre una sesion.
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    time::Instant,
+};
+
+use crate::irc::{
+    constants::{RPL_ENDOFWHO, RPL_WHOSPCRPL},
+    model::session::{event::Event, Session},
+};
+
+use self::command::{BotCommand, Invocation};
+
+pub mod command;
+pub mod harness;
+
+const HELP_COMMAND: &str = "help";
+/// Los tokens de WHOX son de hasta 3 digitos.
+const MAX_LOOKUP_TOKEN: usize = 1000;
+
+/// Lo que el bot le pide a la sesion que mande.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BotAction {
+    /// Mensaje a un canal o a un nick.
+    Say { target: String, text: String },
+    /// WHO con WHOX ("%tcnfa,<token>") para saber los flags y la cuenta de quien uso un comando.
+    Lookup { mask: String, token: String },
+}
+
+/// Comando que espera la respuesta del WHO para saber si el que lo mando tiene permiso.
+#[derive(Debug)]
+struct PendingCommand {
+    token: String,
+    mask: String,
+    command: usize,
+    invocation: Invocation,
+    /// Flags y cuenta del que lo mando, si aparecio en la respuesta.
+    found: Option<(String, String)>,
+}
+
+/// Bot que responde a comandos con un prefijo (ej "!deploy api prod") en canales o por privado.
+/// No depende de la conexion: transforma eventos en acciones, que run manda por una sesion
+/// y BotHarness permite revisar en los tests.
+#[derive(Debug)]
+pub struct Bot {
+    prefix: String,
+    commands: Vec<BotCommand>,
+    pending: VecDeque<PendingCommand>,
+    /// Ultimo uso de cada comando por cada nick, en minusculas, para los cooldowns.
+    last_used: HashMap<(String, String), Instant>,
+    next_token: usize,
+}
+
+impl Bot {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_owned(),
+            commands: vec![],
+            pending: VecDeque::new(),
+            last_used: HashMap::new(),
+            next_token: 0,
+        }
+    }
+
+    /// Agrega el comando. Falla si ya hay uno con el mismo nombre o si se llama help,
+    /// que ya viene con el bot.
+    pub fn register(&mut self, command: BotCommand) -> Result<(), String> {
+        if command.name == HELP_COMMAND || self.find(&command.name).is_some() {
+            return Err(format!("{}{} already exists", self.prefix, command.name));
+        }
+        self.commands.push(command);
+        Ok(())
+    }
+
+    /// Forma de uso y descripcion de todos los comandos, o del indicado.
+    pub fn help(&self, command: Option<&str>) -> Vec<String> {
+        let describe = |c: &BotCommand| format!("{} - {}", c.usage(&self.prefix), c.description);
+
+        match command {
+            Some(name) => {
+                let name = name.trim_start_matches(&self.prefix[..]);
+                match self.find(name) {
+                    Some(i) => vec![describe(&self.commands[i])],
+                    None => vec![format!("No command {}{}", self.prefix, name)],
+                }
+            }
+            None => {
+                let mut lines: Vec<String> = self.commands.iter().map(describe).collect();
+                lines.push(format!(
+                    "{}{} [command] - Shows how to use the commands",
+                    self.prefix, HELP_COMMAND
+                ));
+                lines
+            }
+        }
+    }
+
+    /// Lee eventos de la sesion y manda las acciones hasta que se cierra la conexion.
+    pub fn run(&mut self, session: &mut Session) -> io::Result<()> {
+        while let Some(event) = session.next_event()? {
+            for action in self.handle(&event, Instant::now()) {
+                match action {
+                    BotAction::Say { target, text } => session.privmsg(&target, &text)?,
+                    BotAction::Lookup { mask, token } => {
+                        session.who(Some(&mask), Some(&format!("%tcnfa,{}", token)))?
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Acciones con las que el bot responde al evento, recibido en el instante indicado.
+    pub fn handle(&mut self, event: &Event, now: Instant) -> Vec<BotAction> {
+        match event {
+            Event::Message {
+                channel,
+                sender,
+                text,
+            } => self.handle_message(channel.as_deref(), sender, text, now),
+            Event::Numeric { code, content } if *code == RPL_WHOSPCRPL => {
+                self.handle_who_reply(content);
+                vec![]
+            }
+            Event::Numeric { code, content } if *code == RPL_ENDOFWHO => {
+                self.handle_end_of_who(content, now)
+            }
+            _ => vec![],
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.commands
+            .iter()
+            .position(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    fn handle_message(
+        &mut self,
+        channel: Option<&str>,
+        sender: &str,
+        text: &str,
+        now: Instant,
+    ) -> Vec<BotAction> {
+        let text = match text.trim().strip_prefix(&self.prefix[..]) {
+            Some(t) => t,
+            None => return vec![],
+        };
+        let (name, rest) = text.split_once(' ').unwrap_or((text, ""));
+        let reply_to = channel.unwrap_or(sender).to_owned();
+        let say = |lines: Vec<String>| -> Vec<BotAction> {
+            lines
+                .into_iter()
+                .map(|text| BotAction::Say {
+                    target: reply_to.to_owned(),
+                    text,
+                })
+                .collect()
+        };
+
+        if name.eq_ignore_ascii_case(HELP_COMMAND) {
+            let rest = rest.trim();
+            return say(self.help((!rest.is_empty()).then_some(rest)));
+        }
+        let index = match self.find(name) {
+            Some(i) => i,
+            None => return vec![],
+        };
+        let command = &self.commands[index];
+
+        let arguments = match command.parse_arguments(rest) {
+            Some(a) => a,
+            None => return say(vec![format!("Usage: {}", command.usage(&self.prefix))]),
+        };
+        if let Some(remaining) = self.cooldown_left(index, sender, now) {
+            return say(vec![format!(
+                "{}: {}{} is on cooldown for {}s",
+                sender,
+                self.prefix,
+                command.name,
+                remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0)
+            )]);
+        }
+
+        let invocation = Invocation {
+            sender: sender.to_owned(),
+            channel: channel.map(|c| c.to_owned()),
+            arguments,
+        };
+        if !command.permission_for(channel).needs_lookup() {
+            return self.execute(index, invocation, now);
+        }
+
+        // Ops are per channel and accounts are only known by the server, so ask before running
+        let token = self.next_token.to_string();
+        self.next_token = (self.next_token + 1) % MAX_LOOKUP_TOKEN;
+        let mask = channel.unwrap_or(sender).to_owned();
+        self.pending.push_back(PendingCommand {
+            token: token.to_owned(),
+            mask: mask.to_owned(),
+            command: index,
+            invocation,
+            found: None,
+        });
+
+        vec![BotAction::Lookup { mask, token }]
+    }
+
+    fn cooldown_left(
+        &self,
+        index: usize,
+        sender: &str,
+        now: Instant,
+    ) -> Option<std::time::Duration> {
+        let command = &self.commands[index];
+        let last = self
+            .last_used
+            .get(&(command.name.to_owned(), sender.to_ascii_lowercase()))?;
+        command
+            .cooldown
+            .checked_sub(now.saturating_duration_since(*last))
+            .filter(|left| !left.is_zero())
+    }
+
+    /// Linea de WHOX: "<token> <canal> <nick> <flags> <cuenta>".
+    fn handle_who_reply(&mut self, content: &str) {
+        let fields: Vec<&str> = content.split_whitespace().collect();
+        let (token, nickname, flags, account) = match fields[..] {
+            [token, _, nickname, flags, account] => (token, nickname, flags, account),
+            _ => return,
+        };
+
+        if let Some(pending) = self.pending.iter_mut().find(|p| p.token == token) {
+            if pending.invocation.sender.eq_ignore_ascii_case(nickname) {
+                pending.found = Some((flags.to_owned(), account.to_owned()));
+            }
+        }
+    }
+
+    fn handle_end_of_who(&mut self, content: &str, now: Instant) -> Vec<BotAction> {
+        let mask = content.split(' ').next().unwrap_or_default();
+        let position = self
+            .pending
+            .iter()
+            .position(|p| p.mask.eq_ignore_ascii_case(mask));
+        let pending = match position.and_then(|i| self.pending.remove(i)) {
+            Some(p) => p,
+            None => return vec![],
+        };
+
+        let command = &self.commands[pending.command];
+        let channel = pending.invocation.channel.as_deref();
+        let (flags, account) = pending.found.unwrap_or_default();
+        if !command.permission_for(channel).allows(&flags, &account) {
+            return vec![BotAction::Say {
+                target: channel.unwrap_or(&pending.invocation.sender).to_owned(),
+                text: format!(
+                    "{}: you are not allowed to use {}{}",
+                    pending.invocation.sender, self.prefix, command.name
+                ),
+            }];
+        }
+
+        self.execute(pending.command, pending.invocation, now)
+    }
+
+    fn execute(&mut self, index: usize, invocation: Invocation, now: Instant) -> Vec<BotAction> {
+        let command = &mut self.commands[index];
+        self.last_used.insert(
+            (
+                command.name.to_owned(),
+                invocation.sender.to_ascii_lowercase(),
+            ),
+            now,
+        );
+
+        let target = invocation
+            .channel
+            .to_owned()
+            .unwrap_or_else(|| invocation.sender.to_owned());
+        // A PRIVMSG can't carry line breaks, each line of the reply goes in its own message
+        (command.handler)(&invocation)
+            .iter()
+            .flat_map(|text| text.lines())
+            .map(|line| BotAction::Say {
+                target: target.to_owned(),
+                text: line.to_owned(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod bot_tests {
+    use std::{thread, time::Duration};
+
+    use super::{
+        command::{BotCommand, Permission},
+        harness::BotHarness,
+        *,
+    };
+    use crate::irc::model::server::builder::ServerBuilder;
+
+    fn deploy(permission: Permission) -> BotCommand {
+        BotCommand::new("deploy <service> [env]", "Deploys a service", |i| {
+            vec![format!(
+                "{} deploys {} to {}",
+                i.sender,
+                i.arg("service").unwrap_or_default(),
+                i.arg("env").unwrap_or("staging")
+            )]
+        })
+        .unwrap()
+        .permission(permission)
+    }
+
+    fn harness(command: BotCommand) -> BotHarness {
+        let mut bot = Bot::new("!");
+        bot.register(command).unwrap();
+        BotHarness::new(bot)
+    }
+
+    #[test]
+    fn test_commands_reply_where_they_were_used() {
+        let mut h = harness(deploy(Permission::Anyone));
+
+        h.feed("#ops alice: !deploy api prod")
+            .expect_say("#ops", "alice deploys api to prod")
+            .feed("alice: !DEPLOY web")
+            .expect_say("alice", "alice deploys web to staging")
+            .feed("#ops alice: !deploy")
+            .expect_say("#ops", "Usage: !deploy <service> [env]")
+            .feed("#ops alice: !unknown")
+            .feed("#ops alice: deploy api")
+            .feed("353: #ops alice")
+            .expect_silence();
+        assert!(h.bot().help(Some("!deploy"))[0].contains("Deploys a service"));
+    }
+
+    #[test]
+    fn test_replies_with_line_breaks_are_sent_line_by_line() {
+        let status = BotCommand::new("status", "Shows the status", |_| {
+            vec!["api: up\r\nweb: down".to_owned()]
+        })
+        .unwrap();
+        let mut h = harness(status);
+
+        h.feed("#ops alice: !status")
+            .expect_say("#ops", "api: up")
+            .expect_say("#ops", "web: down")
+            .expect_silence();
+    }
+
+    #[test]
+    fn test_operator_commands_wait_for_the_who_reply() {
+        let mut h = harness(deploy(Permission::ChannelOperator));
+
+        h.feed("#ops alice: !deploy api");
+        let token = h.expect_lookup("#ops");
+        h.feed("#ops bob: !deploy web");
+        let other = h.expect_lookup("#ops");
+        assert_ne!(token, other);
+
+        h.feed(&format!("354: {} #ops alice H@ 0", token))
+            .feed(&format!("354: {} #ops bob H 0", token))
+            .feed("315: #ops :End of /WHO list")
+            .expect_say("#ops", "alice deploys api to staging")
+            .feed(&format!("354: {} #ops alice H@ 0", other))
+            .feed(&format!("354: {} #ops bob H 0", other))
+            .feed("315: #ops :End of /WHO list")
+            .expect_say("#ops", "bob: you are not allowed to use !deploy")
+            .expect_silence();
+    }
+
+    #[test]
+    fn test_channel_permissions_use_accounts() {
+        let mut h = harness(
+            deploy(Permission::Anyone)
+                .channel_permission("#prod", Permission::Accounts(vec!["carol".to_owned()])),
+        );
+
+        h.feed("#dev dave: !deploy api")
+            .expect_say("#dev", "dave deploys api to staging");
+
+        h.feed("#prod carol: !deploy api prod");
+        let token = h.expect_lookup("#prod");
+        h.feed(&format!("354: {} #prod carol H carol", token))
+            .feed("315: #prod :End of /WHO list")
+            .expect_say("#prod", "carol deploys api to prod");
+
+        h.feed("#prod dave: !deploy api prod");
+        let token = h.expect_lookup("#prod");
+        h.feed(&format!("354: {} #prod dave H@ 0", token))
+            .feed("315: #prod :End of /WHO list")
+            .expect_say("#prod", "dave: you are not allowed to use !deploy")
+            .expect_silence();
+    }
+
+    #[test]
+    fn test_help_and_cooldowns() {
+        let mut h = harness(deploy(Permission::Anyone).cooldown(Duration::from_secs(30)));
+
+        h.feed("#ops alice: !help")
+            .expect_say("#ops", "!deploy <service> [env] - Deploys a service")
+            .expect_say("#ops", "!help [command] - Shows how to use the commands")
+            .feed("#ops alice: !help nope")
+            .expect_say("#ops", "No command !nope");
+
+        h.feed("#ops alice: !deploy api")
+            .expect_say("#ops", "alice deploys api to staging")
+            .advance(Duration::from_secs(10))
+            .feed("#ops alice: !deploy api")
+            .expect_say("#ops", "alice: !deploy is on cooldown for 20s")
+            .feed("#ops bob: !deploy api")
+            .expect_say("#ops", "bob deploys api to staging")
+            .advance(Duration::from_secs(20))
+            .feed("#ops alice: !deploy api")
+            .expect_say("#ops", "alice deploys api to staging")
+            .expect_silence();
+
+        let mut bot = Bot::new("!");
+        bot.register(deploy(Permission::Anyone)).unwrap();
+        assert!(bot.register(deploy(Permission::Anyone)).is_err());
+    }
+
+    #[test]
+    fn test_bot_checks_channel_operators_on_a_server() {
+        let server = ServerBuilder::new().spawn().unwrap();
+        let address = server.address();
+        let connect = |nickname: &str| {
+            let session = Session::connect(address, "pass", nickname, nickname, "Bot").unwrap();
+            session
+                .set_read_timeout(Some(Duration::from_secs(10)))
+                .unwrap();
+            session
+        };
+        let wait_for = |session: &mut Session, text: &str| {
+            session.events().any(|event| match event {
+                Event::Message { text: t, .. } => t == text,
+                _ => false,
+            })
+        };
+
+        let mut alice = connect("botalice");
+        alice.join(&["#botops"], &[]).unwrap();
+        let mut bob = connect("botbob");
+        bob.join(&["#botops"], &[]).unwrap();
+
+        let mut session = connect("deploybot");
+        session.join(&["#botops"], &[]).unwrap();
+        let mut bot_sender = session.try_clone().unwrap();
+        let bot = thread::spawn(move || {
+            let mut bot = Bot::new("!");
+            bot.register(deploy(Permission::ChannelOperator)).unwrap();
+            bot.run(&mut session)
+        });
+        thread::sleep(Duration::from_millis(200));
+
+        alice.privmsg("#botops", "!deploy api").unwrap();
+        assert!(wait_for(&mut bob, "botalice deploys api to staging"));
+        bob.privmsg("#botops", "!deploy api").unwrap();
+        assert!(wait_for(
+            &mut alice,
+            "botbob: you are not allowed to use !deploy"
+        ));
+
+        bot_sender.quit(None).unwrap();
+        assert!(bot.join().unwrap().is_ok());
+        server.shutdown().unwrap();
+    }
+}
@@ -12,29 +12,58 @@ pub const ERR_NOTONCHANNEL: usize = 442;
 pub const ERR_NOSUCHSERVER: usize = 402;
 pub const ERR_USERONCHANNEL: usize = 443;
 pub const ERR_INVITEONLYCHAN: usize = 473;
+pub const ERR_BANNEDFROMCHAN: usize = 474;
 pub const ERR_CHANOPRIVSNEEDED: usize = 482;
 pub const ERR_USERNOTINCHANNEL: usize = 441;
 pub const ERR_CANNOTSENDTOCHAN: usize = 404;
 pub const ERR_UNKNOWNMODE: usize = 472;
+pub const ERR_NOPRIVILEGES: usize = 481;
+pub const ERR_INVALIDCAPCMD: usize = 410;
+pub const ERR_YOUREBANNEDCREEP: usize = 465;
+pub const ERR_NOMOTD: usize = 422;
+pub const ERR_NOADMININFO: usize = 423;
 pub const ERR_SAMEUSER: usize = 1401;
 pub const ERR_SERVERERR: usize = 999;
 pub const ERR_CHANLIMIT: usize = 1403;
 pub const RPL_PWDSET: usize = 1201;
 pub const RPL_NICKSET: usize = 1202;
 pub const RPL_REGISTERED: usize = 1203;
+pub const RPL_FILTERMATCH: usize = 1204;
+pub const RPL_ENDOFFILTERMATCH: usize = 1205;
 pub const ERR_REGMISSING: usize = 1405;
+pub const ERR_TOOMANYCOMMANDS: usize = 1406;
+pub const ERR_MSGFILTERED: usize = 1407;
 pub const ERR_ALREADYREGISTRED: usize = 462;
 pub const RPL_YOUREOPER: usize = 381;
+pub const RPL_REHASHING: usize = 382;
 pub const RPL_LIST: usize = 322;
 pub const RPL_LISTSTART: usize = 321;
 pub const RPL_LISTEND: usize = 323;
 pub const RPL_INVITING: usize = 341;
 pub const RPL_WHOREPLY: usize = 352;
 pub const RPL_ENDOFWHO: usize = 315;
+pub const RPL_WHOSPCRPL: usize = 354;
 pub const RPL_WHOISUSER: usize = 311;
 pub const RPL_ENDOFWHOIS: usize = 318;
 pub const RPL_WHOISOPERATOR: usize = 313;
 pub const RPL_WHOISCHANNELS: usize = 319;
+pub const RPL_WHOISSERVER: usize = 312;
+pub const RPL_WHOWASUSER: usize = 314;
+pub const RPL_USERHOST: usize = 302;
+pub const RPL_ISON: usize = 303;
+pub const RPL_MONONLINE: usize = 730;
+pub const RPL_MONOFFLINE: usize = 731;
+pub const RPL_MONLIST: usize = 732;
+pub const RPL_ENDOFMONLIST: usize = 733;
+pub const ERR_MONLISTFULL: usize = 734;
+pub const RPL_KNOCK: usize = 710;
+pub const RPL_KNOCKDLVR: usize = 711;
+pub const ERR_TOOMANYKNOCK: usize = 712;
+pub const ERR_CHANOPEN: usize = 713;
+pub const ERR_KNOCKONCHAN: usize = 714;
+pub const ERR_CANNOTKNOCK: usize = 480;
+pub const RPL_ENDOFWHOWAS: usize = 369;
+pub const ERR_WASNOSUCHNICK: usize = 406;
 pub const RPL_TOPIC: usize = 332;
 pub const RPL_NOTOPIC: usize = 331;
 pub const RPL_UMODEIS: usize = 221;
@@ -43,6 +72,45 @@ pub const RPL_AWAY: usize = 301;
 pub const RPL_NOAWAY: usize = 305;
 pub const RPL_UNAWAY: usize = 306;
 pub const RPL_PART: usize = 307;
+pub const RPL_LINKS: usize = 364;
+pub const RPL_ENDOFLINKS: usize = 365;
+pub const RPL_ISUPPORT: usize = 5;
+pub const RPL_MAP: usize = 15;
+pub const RPL_MAPEND: usize = 17;
+pub const RPL_BANLIST: usize = 367;
+pub const RPL_ENDOFBANLIST: usize = 368;
+pub const RPL_EXCEPTLIST: usize = 348;
+pub const RPL_ENDOFEXCEPTLIST: usize = 349;
+pub const RPL_INVITELIST: usize = 346;
+pub const RPL_ENDOFINVITELIST: usize = 347;
+pub const RPL_QUIETLIST: usize = 728;
+pub const RPL_ENDOFQUIETLIST: usize = 729;
+pub const RPL_STATSKLINE: usize = 216;
+pub const RPL_ENDOFSTATS: usize = 219;
+pub const RPL_STATSLINKINFO: usize = 211;
+pub const RPL_STATSCOMMANDS: usize = 212;
+pub const RPL_STATSUPTIME: usize = 242;
+pub const RPL_STATSOLINE: usize = 243;
+pub const RPL_TRACELINK: usize = 200;
+pub const RPL_TRACEOPERATOR: usize = 204;
+pub const RPL_TRACEUSER: usize = 205;
+pub const RPL_TRACESERVER: usize = 206;
+pub const RPL_TRACEEND: usize = 262;
+pub const RPL_MOTDSTART: usize = 375;
+pub const RPL_MOTD: usize = 372;
+pub const RPL_ENDOFMOTD: usize = 376;
+pub const RPL_VERSION: usize = 351;
+pub const RPL_TIME: usize = 391;
+pub const RPL_ADMINME: usize = 256;
+pub const RPL_ADMINLOC1: usize = 257;
+pub const RPL_ADMINLOC2: usize = 258;
+pub const RPL_ADMINEMAIL: usize = 259;
+pub const RPL_INFO: usize = 371;
+pub const RPL_ENDOFINFO: usize = 374;
+pub const RPL_LUSERCLIENT: usize = 251;
+pub const RPL_LUSEROP: usize = 252;
+pub const RPL_LUSERCHANNELS: usize = 254;
+pub const RPL_LUSERME: usize = 255;
 
 /*
  * Constants related to channel user information
@@ -67,3 +135,30 @@ pub const RPL_SUCLOGIN: usize = 3000;
 
 pub const INFO_PASSWORD: &str = "123";
 pub const DEFAULT_SERVERNAME: &str = "Unknown";
+
+/*
+ * Client capabilities negotiated through CAP
+ */
+pub const BATCH_CAPABILITY: &str = "batch";
+pub const SUPPORTED_CAPABILITIES: [&str; 1] = [BATCH_CAPABILITY];
+
+/*
+ * Server information
+ */
+pub const SERVER_VERSION: &str = concat!(env!("CARGO_PKG_NAME"), "-", env!("CARGO_PKG_VERSION"));
+pub const MOTD_FILE: &str = "./motd.txt";
+pub const ADMIN_FILE: &str = "./admin.txt";
+pub const CONFIG_FILE: &str = "./server.conf";
+pub const FILTER_FILE: &str = "./filters.conf";
+pub const AUDIT_FILE_PREFIX: &str = "audit";
+pub const PERSIST_DIR: &str = "./persist";
+/// Cantidad de entradas que guarda cada server para responder a WHOWAS.
+pub const WHOWAS_HISTORY_LEN: usize = 200;
+/// Casemapping con el que se comparan nicknames y canales, se anuncia en ISUPPORT.
+pub const CASEMAPPING: &str = "rfc1459";
+/// Cantidad maxima de nicknames que puede seguir cada cliente con MONITOR.
+pub const MONITOR_LIMIT: usize = 100;
+/// Cantidad maxima de nicknames por pedido de USERHOST.
+pub const USERHOST_MAX_TARGETS: usize = 5;
+/// Segundos que tiene que esperar un cliente para volver a hacer KNOCK al mismo canal.
+pub const KNOCK_DELAY: u64 = 60;
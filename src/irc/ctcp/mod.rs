@@ -6,6 +6,7 @@ use crate::gui::{DccCommands, IncomingMessage};
 use super::model::workers::dcc_handler::ExecutedAction;
 
 pub mod constants;
+#[cfg(feature = "gui")]
 pub mod dcc_relay;
 pub mod message;
 pub mod utils;
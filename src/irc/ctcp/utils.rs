@@ -1,16 +1,14 @@
-use glib::Cast;
-use gtk::traits::{ButtonExt, ContainerExt, ToggleButtonExt};
-use gtk::{ButtonBox, Widget};
-
 use crate::irc::ctcp::constants::{
     CHAT_PROTOCOL, ERR_INVALIDIP, ERR_INVALIDPORT, ERR_INVALIDPROTOCOL, ERR_INVALIDSIZE, IP_MAX_LEN,
 };
 use crate::irc::ctcp::message::send::DccSend;
-use crate::irc::message::generic_message::GenericMessage;
-use crate::irc::message::notice::Notice;
-use crate::irc::message::utils::{generate_string_from_vec, try_parse_number};
+use crate::irc::message::utils::try_parse_number;
 use crate::irc::message::MessageError::DCCDefined;
-use crate::irc::message::{utils, FromGeneric, MessageError, DOT};
+#[cfg(feature = "gui")]
+use crate::irc::message::{
+    generic_message::GenericMessage, notice::Notice, utils::generate_string_from_vec, FromGeneric,
+};
+use crate::irc::message::{utils, MessageError, DOT};
 use std::collections::vec_deque::VecDeque;
 
 use super::constants::{MAX_PORT, MIN_PORT};
@@ -22,6 +20,7 @@ use super::DccMessage;
 pub const PATH_FILES_UPLOAD: &str = "../uploads";
 pub const PATH_FILES_DOWNLOAD: &str = "../downloads";
 
+#[cfg(feature = "gui")]
 pub fn get_ctcp_message_from_notice(incoming: &str) -> Option<String> {
     let notice: Notice<'_> = Notice::from_generic(GenericMessage::parse(incoming).ok()?).ok()?;
 
@@ -76,7 +75,8 @@ fn evaluate_first_token<'a>(incoming: &'a str, to: &'static str) -> Option<&'a s
         return None;
     }
 
-    let next = incoming.find(first)? + to.len();
+    // The token may be longer than the command once uppercased, as in "ſend"
+    let next = incoming.find(first)? + first.len();
     Some(&incoming[next..])
 }
 
@@ -159,6 +159,7 @@ pub fn form_ctcp_cmd(cmd: &str) -> String {
     format!("\u{1}CTCP {}\u{1}", cmd)
 }
 
+#[cfg(any(feature = "gui", feature = "fuzzing", test))]
 pub fn to_dcc_command(incoming: &str) -> Option<Box<dyn DccMessage>> {
     let complete = get_ctcp_message(incoming)?;
     let ctcp = get_ctcp_command(complete)?;
@@ -167,6 +168,7 @@ pub fn to_dcc_command(incoming: &str) -> Option<Box<dyn DccMessage>> {
     upgrade_dcc_command(dcc)
 }
 
+#[cfg(feature = "gui")]
 pub fn to_dcc_command_from_notice(incoming: &str) -> Option<Box<dyn DccMessage>> {
     let complete = get_ctcp_message_from_notice(incoming)?;
     let ctcp = get_ctcp_command(&complete)?;
@@ -179,20 +181,6 @@ pub fn to_notice_command(nickname: String, dcc_command: String) -> String {
     format!("NOTICE {} :{}", nickname, form_ctcp_cmd(&dcc_command))
 }
 
-pub fn get_selected_nick(user_container: &ButtonBox) -> Option<String> {
-    user_container
-        .children()
-        .iter()
-        .find_map(|w: &Widget| {
-            let b = w.clone().downcast::<gtk::RadioButton>().unwrap();
-            match b.is_active() {
-                true => b.label(),
-                false => None,
-            }
-        })
-        .map(|v| v.to_string())
-}
-
 #[cfg(test)]
 mod test {
 
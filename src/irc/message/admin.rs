@@ -0,0 +1,155 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de admin.
+use crate::irc::constants::{
+    ERR_NEEDMOREPARAMS, ERR_NOADMININFO, RPL_ADMINEMAIL, RPL_ADMINLOC1, RPL_ADMINLOC2, RPL_ADMINME,
+};
+use crate::irc::message::utils::{validate_command, validate_irc_params_len};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError};
+use crate::irc::model::server::Server;
+use crate::irc::model::{MTClient, MTServerConnection};
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+
+use super::serializer::MessageSerializer;
+use super::server_query::ServerQuery;
+use super::{Executable, Serializable, ServerExecutable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a admin
+/// Contiene un server opcional al que se le piden los datos de su administrador.
+pub struct Admin<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub target: Option<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Admin<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Admin)?;
+        validate_irc_params_len(&generic.parameters, 1, 0, ERR_NEEDMOREPARAMS)?;
+
+        Ok(Self {
+            prefix: generic.prefix,
+            target: generic.parameters.pop_front(),
+        })
+    }
+}
+
+impl Serializable for Admin<'_> {
+    fn serialize(&self) -> String {
+        let mut s = MessageSerializer::new(self.prefix, Command::Admin);
+
+        if let Some(v) = self.target {
+            s = s.add_parameter(v);
+        }
+
+        s.serialize()
+    }
+}
+
+impl ServerQuery for Admin<'_> {
+    fn prefix(&self) -> Option<&[u8]> {
+        self.prefix
+    }
+
+    fn target(&self) -> Option<&[u8]> {
+        self.target
+    }
+
+    fn command(&self) -> Command {
+        Command::Admin
+    }
+
+    fn replies(&self, server: &Server) -> Vec<ResponseType> {
+        let lines = match server.admin_info() {
+            Some(v) => v,
+            None => {
+                return ResponseBuilder::new()
+                    .add_content_for_response(
+                        ERR_NOADMININFO,
+                        format!("{} :No administrative info available", server.host),
+                    )
+                    .build()
+            }
+        };
+
+        ResponseBuilder::new()
+            .add_content_for_response(RPL_ADMINME, format!("{} :Administrative info", server.host))
+            .add_content_for_response(RPL_ADMINLOC1, format!(":{}", lines[0]))
+            .add_content_for_response(RPL_ADMINLOC2, format!(":{}", lines[1]))
+            .add_content_for_response(RPL_ADMINEMAIL, format!(":{}", lines[2]))
+            .build()
+    }
+}
+
+impl Executable for Admin<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        self.query(server, client)
+    }
+}
+
+impl ServerExecutable for Admin<'_> {
+    fn _execute_for_server(&self, server: &Server) -> Vec<ResponseType> {
+        self.query_for_server(server);
+        ResponseBuilder::new().build()
+    }
+
+    fn execute_for_server(&self, server: &Server, _: MTServerConnection) -> Vec<ResponseType> {
+        self._execute_for_server(server)
+    }
+}
+
+#[cfg(test)]
+mod admin_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{InvalidCommand, TooManyParams};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_admin_with_different_command_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Links,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Admin::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_admin_with_too_many_params_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"127.0.0.1:8080");
+        parameters.push_back(b"other");
+
+        let generic = GenericMessage {
+            command: Command::Admin,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Admin::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, TooManyParams);
+    }
+
+    #[test]
+    fn test_admin_with_target() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"127.0.0.1:8080");
+
+        let generic = GenericMessage {
+            command: Command::Admin,
+            prefix: Some(b"nick"),
+            parameters,
+        };
+
+        let admin = Admin::from_generic(generic).unwrap();
+
+        assert_eq!(admin.target.unwrap(), b"127.0.0.1:8080");
+        assert_eq!(admin.serialize(), ":nick ADMIN 127.0.0.1:8080".to_owned());
+    }
+}
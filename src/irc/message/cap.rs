@@ -0,0 +1,204 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de cap.
+use crate::irc::constants::{ERR_INVALIDCAPCMD, ERR_NEEDMOREPARAMS, SUPPORTED_CAPABILITIES};
+use crate::irc::message::utils::{generate_string, validate_command, validate_irc_params_len};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError, UNLIMITED_MAX_LEN};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+use crate::try_lock;
+
+use super::serializer::MessageSerializer;
+use super::utils::split_trailing_list;
+use super::{Executable, Serializable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a cap
+/// Contiene el subcomando (LS, LIST, REQ o END)
+/// y las capacidades pedidas, si las hay.
+pub struct Cap<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub subcommand: &'a [u8],
+    pub capabilities: Vec<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Cap<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Cap)?;
+        validate_irc_params_len(
+            &generic.parameters,
+            UNLIMITED_MAX_LEN,
+            1,
+            ERR_NEEDMOREPARAMS,
+        )?;
+
+        let subcommand = match generic.parameters.pop_front() {
+            Some(v) => v,
+            None => return Err(MessageError::IRCDefined(ERR_NEEDMOREPARAMS)),
+        };
+
+        let capabilities = split_trailing_list(generic.parameters);
+
+        Ok(Self {
+            prefix: generic.prefix,
+            subcommand,
+            capabilities,
+        })
+    }
+}
+
+impl Serializable for Cap<'_> {
+    fn serialize(&self) -> String {
+        MessageSerializer::new(self.prefix, Command::Cap)
+            .add_parameter(self.subcommand)
+            .add_trailing_params(&self.capabilities)
+            .serialize()
+    }
+}
+
+impl Cap<'_> {
+    fn list(capabilities: &[String]) -> String {
+        capabilities.join(" ")
+    }
+
+    /// Aplica los pedidos de capacidades. Si alguna no esta soportada se rechaza el pedido entero.
+    fn request(&self, client: &MTClient) -> bool {
+        let requested: Vec<String> = self
+            .capabilities
+            .iter()
+            .map(|c| generate_string(c))
+            .collect();
+        let supported = requested
+            .iter()
+            .all(|c| SUPPORTED_CAPABILITIES.contains(&c.trim_start_matches('-')));
+        if !supported {
+            return false;
+        }
+
+        let mut c = try_lock!(client);
+        for capability in requested {
+            match capability.strip_prefix('-') {
+                Some(v) => c.remove_capability(v),
+                None => c.add_capability(&capability),
+            }
+        }
+        true
+    }
+}
+
+impl Executable for Cap<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        let nickname = { try_lock!(client).nickname.to_owned() };
+        let requested = self
+            .capabilities
+            .iter()
+            .map(|c| generate_string(c))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let reply = match generate_string(self.subcommand).to_uppercase().as_str() {
+            "LS" => {
+                let supported: Vec<String> = SUPPORTED_CAPABILITIES
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect();
+                format!("CAP {} LS :{}", nickname, Self::list(&supported))
+            }
+            "LIST" => {
+                let enabled = { try_lock!(client).capabilities.clone() };
+                format!("CAP {} LIST :{}", nickname, Self::list(&enabled))
+            }
+            "REQ" => match self.request(&client) {
+                true => format!("CAP {} ACK :{}", nickname, requested),
+                false => format!("CAP {} NAK :{}", nickname, requested),
+            },
+            "END" => return ResponseBuilder::new().build(),
+            _ => {
+                return ResponseBuilder::new()
+                    .add_content_for_response(
+                        ERR_INVALIDCAPCMD,
+                        format!("{} :Invalid CAP command", generate_string(self.subcommand)),
+                    )
+                    .build()
+            }
+        };
+
+        server.send_message_to_local_client(&mut *try_lock!(client), &reply);
+        ResponseBuilder::new().build()
+    }
+}
+
+#[cfg(test)]
+mod cap_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{IRCDefined, InvalidCommand};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_cap_with_different_command_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Away,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Cap::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_cap_without_subcommand_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Cap,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Cap::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, IRCDefined(ERR_NEEDMOREPARAMS));
+    }
+
+    #[test]
+    fn test_cap_ls() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"LS");
+
+        let generic = GenericMessage {
+            command: Command::Cap,
+            prefix: None,
+            parameters,
+        };
+
+        let cap = Cap::from_generic(generic).unwrap();
+
+        assert_eq!(cap.subcommand, b"LS");
+        assert!(cap.capabilities.is_empty());
+    }
+
+    #[test]
+    fn test_cap_req_with_trailing_capabilities() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"REQ");
+        parameters.push_back(b":batch");
+        parameters.push_back(b"-other");
+
+        let generic = GenericMessage {
+            command: Command::Cap,
+            prefix: None,
+            parameters,
+        };
+
+        let cap = Cap::from_generic(generic).unwrap();
+
+        assert_eq!(cap.subcommand, b"REQ");
+        assert_eq!(cap.capabilities, vec![b"batch" as &[u8], b"-other"]);
+        assert_eq!(cap.serialize(), "CAP REQ :batch -other".to_owned());
+    }
+}
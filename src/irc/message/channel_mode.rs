@@ -4,9 +4,10 @@ use std::collections::VecDeque;
 use crate::irc::{
     constants::{
         ERR_CHANOPRIVSNEEDED, ERR_NEEDMOREPARAMS, ERR_NOSUCHCHANNEL, ERR_NOSUCHNICK,
-        ERR_UNKNOWNMODE, RPL_CHANNELMODEIS,
+        ERR_UNKNOWNMODE, RPL_BANLIST, RPL_CHANNELMODEIS, RPL_ENDOFBANLIST, RPL_ENDOFEXCEPTLIST,
+        RPL_ENDOFINVITELIST, RPL_ENDOFQUIETLIST, RPL_EXCEPTLIST, RPL_INVITELIST, RPL_QUIETLIST,
     },
-    model::{server::Server, MTChannel, MTClient, ServerError},
+    model::{channel::ChannelList, server::Server, MTChannel, MTClient, ServerError},
     responses::{builder::ResponseBuilder, ResponseType},
 };
 
@@ -14,8 +15,9 @@ use super::{
     generic_message::GenericMessage,
     serializer::MessageSerializer,
     utils::{
-        generate_string, no_such_nick, try_parse_number, validate_channel, validate_channel_modes,
-        validate_command, validate_irc_params_len, validate_name_invalid_none, validate_user,
+        generate_string, no_such_nick, normalize_ban_mask, try_parse_number, validate_channel,
+        validate_channel_mask, validate_channel_modes, validate_command, validate_irc_params_len,
+        validate_name_invalid_none, validate_user,
     },
     ChannelModes, Command, FromGeneric, MessageError, ModesAction, Replicable, Serializable,
 };
@@ -56,6 +58,10 @@ impl Serializable for ChannelMode<'_> {
                 ChannelModes::Limit(Some(n)) => s = s.add_number(n),
                 ChannelModes::SpeakInModeratedChannel(v) => s = s.add_parameter(v),
                 ChannelModes::ChannelKey(Some(k)) => s = s.add_parameter(k),
+                ChannelModes::Ban(Some(m)) => s = s.add_parameter(m),
+                ChannelModes::BanException(Some(m)) => s = s.add_parameter(m),
+                ChannelModes::InviteException(Some(m)) => s = s.add_parameter(m),
+                ChannelModes::Quiet(Some(m)) => s = s.add_parameter(m),
                 _ => {}
             };
         }
@@ -65,6 +71,72 @@ impl Serializable for ChannelMode<'_> {
 }
 
 impl ChannelMode<'_> {
+    /// Un +b, +e, +I o +q sin mascara es un pedido del listado correspondiente.
+    fn list_request(&self) -> Option<ChannelList> {
+        let action = match self.mode.as_ref()? {
+            ModesAction::Add(action) => action,
+            ModesAction::Remove(action) => action,
+        };
+
+        match action {
+            ChannelModes::Ban(None) => Some(ChannelList::Bans),
+            ChannelModes::BanException(None) => Some(ChannelList::BanExceptions),
+            ChannelModes::InviteException(None) => Some(ChannelList::InviteExceptions),
+            ChannelModes::Quiet(None) => Some(ChannelList::Quiets),
+            _ => None,
+        }
+    }
+
+    fn describe_list(
+        server: &Server,
+        channel: MTChannel,
+        channel_name: &str,
+        list: ChannelList,
+    ) -> Vec<ResponseType> {
+        let (item, end, end_msg) = match list {
+            ChannelList::Bans => (RPL_BANLIST, RPL_ENDOFBANLIST, "End of channel ban list"),
+            ChannelList::BanExceptions => (
+                RPL_EXCEPTLIST,
+                RPL_ENDOFEXCEPTLIST,
+                "End of channel exception list",
+            ),
+            ChannelList::InviteExceptions => (
+                RPL_INVITELIST,
+                RPL_ENDOFINVITELIST,
+                "End of channel invite list",
+            ),
+            ChannelList::Quiets => (
+                RPL_QUIETLIST,
+                RPL_ENDOFQUIETLIST,
+                "End of channel quiet list",
+            ),
+        };
+
+        let mut response = ResponseBuilder::new();
+        for mask in server.get_channel_masks(channel, list) {
+            response =
+                response.add_content_for_response(item, format!("{} {}", channel_name, mask));
+        }
+
+        response
+            .add_content_for_response(end, format!("{} :{}", channel_name, end_msg))
+            .build()
+    }
+
+    fn set_mask(
+        server: &Server,
+        channel: MTChannel,
+        list: ChannelList,
+        mask: &Option<&[u8]>,
+        to: bool,
+    ) -> Result<(), ServerError> {
+        if let Some(m) = mask {
+            let mask = normalize_ban_mask(&generate_string(m));
+            server.set_channel_mask(channel, list, &mask, to);
+        }
+        Ok(())
+    }
+
     pub fn dispatch(&self, server: &Server, channel: MTChannel) -> Result<(), ServerError> {
         if let Some(mode) = &self.mode {
             let (to, action) = match mode {
@@ -157,6 +229,18 @@ impl ChannelMode<'_> {
                     };
                     Ok(())
                 }
+                ChannelModes::Ban(mask) => {
+                    Self::set_mask(server, channel, ChannelList::Bans, mask, to)
+                }
+                ChannelModes::BanException(mask) => {
+                    Self::set_mask(server, channel, ChannelList::BanExceptions, mask, to)
+                }
+                ChannelModes::InviteException(mask) => {
+                    Self::set_mask(server, channel, ChannelList::InviteExceptions, mask, to)
+                }
+                ChannelModes::Quiet(mask) => {
+                    Self::set_mask(server, channel, ChannelList::Quiets, mask, to)
+                }
             };
 
             res?;
@@ -185,6 +269,10 @@ impl Replicable for ChannelMode<'_> {
                     );
                 }
 
+                if let Some(list) = self.list_request() {
+                    return (Self::describe_list(server, ch, &channel_name, list), false);
+                }
+
                 let channel_op = server.is_channel_operator(client, channel_name.as_str());
                 if !channel_op {
                     return (
@@ -260,6 +348,18 @@ impl<'a> ChannelModes<'a> {
                 ))
             }
             b'k' => Ok(ChannelModes::ChannelKey(params.pop_front())),
+            b'b' => Ok(ChannelModes::Ban(validate_channel_mask(
+                params.pop_front(),
+            )?)),
+            b'e' => Ok(ChannelModes::BanException(validate_channel_mask(
+                params.pop_front(),
+            )?)),
+            b'I' => Ok(ChannelModes::InviteException(validate_channel_mask(
+                params.pop_front(),
+            )?)),
+            b'q' => Ok(ChannelModes::Quiet(validate_channel_mask(
+                params.pop_front(),
+            )?)),
             _ => Err(MessageError::IRCDefined(ERR_UNKNOWNMODE)),
         }
     }
@@ -276,6 +376,123 @@ impl<'a> ChannelModes<'a> {
             ChannelModes::Limit(_) => b'l',
             ChannelModes::SpeakInModeratedChannel(_) => b'v',
             ChannelModes::ChannelKey(_) => b'k',
+            ChannelModes::Ban(_) => b'b',
+            ChannelModes::BanException(_) => b'e',
+            ChannelModes::InviteException(_) => b'I',
+            ChannelModes::Quiet(_) => b'q',
         }
     }
 }
+
+#[cfg(test)]
+mod channel_mode_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::InvalidFormat;
+
+    #[test]
+    fn test_ban_with_mask() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"#canal");
+        parameters.push_back(b"+b");
+        parameters.push_back(b"*!*@127.0.0.*");
+
+        let generic = GenericMessage {
+            command: Command::Mode,
+            prefix: None,
+            parameters,
+        };
+
+        let mode = ChannelMode::from_generic(generic).unwrap();
+
+        assert!(mode.list_request().is_none());
+        assert_eq!(mode.serialize(), "MODE #canal +b *!*@127.0.0.*".to_owned());
+    }
+
+    #[test]
+    fn test_ban_list_request() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"#canal");
+        parameters.push_back(b"+b");
+
+        let generic = GenericMessage {
+            command: Command::Mode,
+            prefix: None,
+            parameters,
+        };
+
+        let mode = ChannelMode::from_generic(generic).unwrap();
+
+        assert!(matches!(mode.list_request(), Some(ChannelList::Bans)));
+        assert_eq!(mode.serialize(), "MODE #canal +b".to_owned());
+    }
+
+    #[test]
+    fn test_exception_lists() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"#canal");
+        parameters.push_back(b"-e");
+        parameters.push_back(b"nick");
+
+        let generic = GenericMessage {
+            command: Command::Mode,
+            prefix: None,
+            parameters,
+        };
+
+        let mode = ChannelMode::from_generic(generic).unwrap();
+        assert_eq!(mode.serialize(), "MODE #canal -e nick".to_owned());
+
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"#canal");
+        parameters.push_back(b"+I");
+
+        let generic = GenericMessage {
+            command: Command::Mode,
+            prefix: None,
+            parameters,
+        };
+
+        let mode = ChannelMode::from_generic(generic).unwrap();
+        assert!(matches!(
+            mode.list_request(),
+            Some(ChannelList::InviteExceptions)
+        ));
+    }
+
+    #[test]
+    fn test_ban_with_invalid_mask_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"#canal");
+        parameters.push_back(b"+b");
+        parameters.push_back(b"nick;other");
+
+        let generic = GenericMessage {
+            command: Command::Mode,
+            prefix: None,
+            parameters,
+        };
+
+        let err = ChannelMode::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidFormat);
+    }
+
+    #[test]
+    fn test_quiet_with_extended_ban() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"#canal");
+        parameters.push_back(b"+q");
+        parameters.push_back(b"$~a");
+
+        let generic = GenericMessage {
+            command: Command::Mode,
+            prefix: None,
+            parameters,
+        };
+
+        let mode = ChannelMode::from_generic(generic).unwrap();
+
+        assert!(mode.list_request().is_none());
+        assert_eq!(mode.serialize(), "MODE #canal +q $~a".to_owned());
+    }
+}
@@ -0,0 +1,110 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de fin de burst entre servers.
+use super::serializer::MessageSerializer;
+use super::utils::generate_string;
+use super::{generic_message::GenericMessage, Command, FromGeneric, MessageError};
+use super::{Serializable, ServerExecutable};
+use crate::irc::constants::ERR_NEEDMOREPARAMS;
+use crate::irc::message::utils::{validate_command, validate_irc_params_len};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTServerConnection;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+use crate::try_lock;
+
+#[derive(Debug)]
+/// Struct del mensaje que envia un server al terminar de mandar su estado a un link nuevo.
+/// El prefijo es el server cuyo burst termino.
+pub struct EndOfBurst<'a> {
+    pub prefix: Option<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for EndOfBurst<'a> {
+    fn from_generic(generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::EndOfBurst)?;
+        validate_irc_params_len(&generic.parameters, 0, 0, ERR_NEEDMOREPARAMS)?;
+
+        Ok(Self {
+            prefix: generic.prefix,
+        })
+    }
+}
+
+impl Serializable for EndOfBurst<'_> {
+    fn serialize(&self) -> String {
+        MessageSerializer::new(self.prefix, Command::EndOfBurst).serialize()
+    }
+}
+
+impl ServerExecutable for EndOfBurst<'_> {
+    fn _execute_for_server(&self, _: &Server) -> Vec<ResponseType> {
+        // Implements for semantic purposes
+        ResponseBuilder::new().build()
+    }
+
+    fn execute_for_server(&self, server: &Server, origin: MTServerConnection) -> Vec<ResponseType> {
+        let link = { try_lock!(origin).servername.to_owned() };
+        let servername = match self.prefix {
+            Some(v) => generate_string(v),
+            None => link.to_owned(),
+        };
+
+        server.finish_netjoin(&link, &servername);
+
+        self.replicate(server, origin);
+        ResponseBuilder::new().build()
+    }
+}
+
+#[cfg(test)]
+mod end_of_burst_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{InvalidCommand, TooManyParams};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_end_of_burst_with_different_command_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Server,
+            prefix: None,
+            parameters,
+        };
+
+        let err = EndOfBurst::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_end_of_burst_with_params_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"extra");
+
+        let generic = GenericMessage {
+            command: Command::EndOfBurst,
+            prefix: Some(b"127.0.0.1:8080"),
+            parameters,
+        };
+
+        let err = EndOfBurst::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, TooManyParams);
+    }
+
+    #[test]
+    fn test_end_of_burst_with_prefix() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::EndOfBurst,
+            prefix: Some(b"127.0.0.1:8080"),
+            parameters,
+        };
+
+        let eob = EndOfBurst::from_generic(generic).unwrap();
+
+        assert_eq!(eob.prefix.unwrap(), b"127.0.0.1:8080");
+        assert_eq!(eob.serialize(), ":127.0.0.1:8080 EOB".to_owned());
+    }
+}
@@ -1,11 +1,24 @@
 //! Modulo que se centra en las funcionalidades referentes al parseo
 //! de los mensajes.
+use crate::irc::message::admin::Admin;
 use crate::irc::message::away::Away;
+use crate::irc::message::cap::Cap;
+use crate::irc::message::end_of_burst::EndOfBurst;
 use crate::irc::message::generic_mode::Mode;
+use crate::irc::message::info::Info;
 use crate::irc::message::invite::Invite;
+use crate::irc::message::ison::Ison;
 use crate::irc::message::join::Join;
 use crate::irc::message::kick::Kick;
+use crate::irc::message::kill::Kill;
+use crate::irc::message::kline::Kline;
+use crate::irc::message::knock::Knock;
+use crate::irc::message::links::Links;
 use crate::irc::message::list::List;
+use crate::irc::message::lusers::Lusers;
+use crate::irc::message::map::Map;
+use crate::irc::message::monitor::Monitor;
+use crate::irc::message::motd::Motd;
 use crate::irc::message::names::Names;
 use crate::irc::message::nickname::Nickname;
 use crate::irc::message::notice::Notice;
@@ -14,17 +27,30 @@ use crate::irc::message::part::Part;
 use crate::irc::message::password::Password;
 use crate::irc::message::private::Private;
 use crate::irc::message::quit::Quit;
+use crate::irc::message::rehash::Rehash;
+use crate::irc::message::reply::Reply;
+use crate::irc::message::restart::Restart;
 use crate::irc::message::server::Sv;
 use crate::irc::message::server_quit::ServerQuit;
+use crate::irc::message::stats::Stats;
+use crate::irc::message::testfilter::TestFilter;
+use crate::irc::message::time::Time;
 use crate::irc::message::topic::Topic;
+use crate::irc::message::trace::Trace;
+use crate::irc::message::unkline::Unkline;
 use crate::irc::message::user::User;
+use crate::irc::message::userhost::Userhost;
 use crate::irc::message::utils::*;
+use crate::irc::message::version::Version;
+use crate::irc::message::wallops::Wallops;
 use crate::irc::message::who::Who;
 use crate::irc::message::whois::Whois;
+use crate::irc::message::whowas::Whowas;
 use crate::irc::message::Executable;
 use crate::irc::message::MessageError;
 use crate::irc::message::MessageError::*;
 use crate::irc::message::Replicable;
+use crate::irc::message::Serializable;
 use crate::irc::message::ServerExecutable;
 use crate::irc::message::{Command, FromGeneric};
 use crate::irc::model::connection::Connection;
@@ -109,6 +135,30 @@ impl<'a> GenericMessage<'a> {
             Command::Mode => execute!(Mode::from_generic(self)),
             Command::Kick => execute!(Kick::from_generic(self)),
             Command::Away => execute!(Away::from_generic(self)),
+            Command::Knock => execute!(Knock::from_generic(self)),
+            Command::Cap => execute!(Cap::from_generic(self)),
+            Command::ServerQuit => execute!(ServerQuit::from_generic(self)),
+            Command::Links => execute!(Links::from_generic(self)),
+            Command::Map => execute!(Map::from_generic(self)),
+            Command::Kline | Command::Gline => execute!(Kline::from_generic(self)),
+            Command::Unkline | Command::Ungline => execute!(Unkline::from_generic(self)),
+            Command::Motd => execute!(Motd::from_generic(self)),
+            Command::Version => execute!(Version::from_generic(self)),
+            Command::Time => execute!(Time::from_generic(self)),
+            Command::Admin => execute!(Admin::from_generic(self)),
+            Command::Info => execute!(Info::from_generic(self)),
+            Command::Lusers => execute!(Lusers::from_generic(self)),
+            Command::Stats => execute!(Stats::from_generic(self)),
+            Command::Trace => execute!(Trace::from_generic(self)),
+            Command::Kill => execute!(Kill::from_generic(self)),
+            Command::Wallops => execute!(Wallops::from_generic(self)),
+            Command::Whowas => execute!(Whowas::from_generic(self)),
+            Command::Ison => execute!(Ison::from_generic(self)),
+            Command::Userhost => execute!(Userhost::from_generic(self)),
+            Command::Monitor => execute!(Monitor::from_generic(self)),
+            Command::Rehash => execute!(Rehash::from_generic(self)),
+            Command::TestFilter => execute!(TestFilter::from_generic(self)),
+            Command::Restart | Command::Die => execute!(Restart::from_generic(self)),
             _ => ResponseBuilder::new()
                 .add_from_error(MessageError::InvalidFormat)
                 .build(),
@@ -164,14 +214,88 @@ impl<'a> GenericMessage<'a> {
             Command::Kick => execute!(Kick::from_generic(self)),
             Command::User => execute!(User::from_generic(self)),
             Command::Away => execute!(Away::from_generic(self)),
+            Command::Knock => execute!(Knock::from_generic(self)),
             Command::Server => execute!(Sv::from_generic(self)),
             Command::ServerQuit => execute!(ServerQuit::from_generic(self)),
+            Command::EndOfBurst => execute!(EndOfBurst::from_generic(self)),
+            Command::Gline => execute!(Kline::from_generic(self)),
+            Command::Ungline => execute!(Unkline::from_generic(self)),
+            Command::Motd => execute!(Motd::from_generic(self)),
+            Command::Version => execute!(Version::from_generic(self)),
+            Command::Time => execute!(Time::from_generic(self)),
+            Command::Admin => execute!(Admin::from_generic(self)),
+            Command::Info => execute!(Info::from_generic(self)),
+            Command::Lusers => execute!(Lusers::from_generic(self)),
+            Command::Stats => execute!(Stats::from_generic(self)),
+            Command::Trace => execute!(Trace::from_generic(self)),
+            Command::Kill => execute!(Kill::from_generic(self)),
+            Command::Wallops => execute!(Wallops::from_generic(self)),
+            Command::Whowas => execute!(Whowas::from_generic(self)),
+            Command::Reply => execute!(Reply::from_generic(self)),
             _ => ResponseBuilder::new()
                 .add_from_error(MessageError::InvalidFormat)
                 .build(),
         }
     }
 
+    /// Arma el mensaje propio del comando y lo vuelve a serializar, en su forma canonica.
+    /// Volver a parsear lo que devuelve tiene que dar el mismo mensaje.
+    pub fn canonical(self) -> Result<String, MessageError> {
+        macro_rules! serialize {
+            ($message: expr) => {
+                $message.map(|msg| msg.serialize())
+            };
+        }
+
+        match self.command {
+            Command::Password => serialize!(Password::from_generic(self)),
+            Command::Nick => serialize!(Nickname::from_generic(self)),
+            Command::User => serialize!(User::from_generic(self)),
+            Command::Server => serialize!(Sv::from_generic(self)),
+            Command::Oper => serialize!(Oper::from_generic(self)),
+            Command::Quit => serialize!(Quit::from_generic(self)),
+            Command::ServerQuit => serialize!(ServerQuit::from_generic(self)),
+            Command::Join => serialize!(Join::from_generic(self)),
+            Command::Part => serialize!(Part::from_generic(self)),
+            Command::Mode => serialize!(Mode::from_generic(self)),
+            Command::Topic => serialize!(Topic::from_generic(self)),
+            Command::Names => serialize!(Names::from_generic(self)),
+            Command::List => serialize!(List::from_generic(self)),
+            Command::Invite => serialize!(Invite::from_generic(self)),
+            Command::Kick => serialize!(Kick::from_generic(self)),
+            Command::PrivateMessage => serialize!(Private::from_generic(self)),
+            Command::Notice => serialize!(Notice::from_generic(self)),
+            Command::Who => serialize!(Who::from_generic(self)),
+            Command::WhoIs => serialize!(Whois::from_generic(self)),
+            Command::Away => serialize!(Away::from_generic(self)),
+            Command::Cap => serialize!(Cap::from_generic(self)),
+            Command::EndOfBurst => serialize!(EndOfBurst::from_generic(self)),
+            Command::Links => serialize!(Links::from_generic(self)),
+            Command::Map => serialize!(Map::from_generic(self)),
+            Command::Kline | Command::Gline => serialize!(Kline::from_generic(self)),
+            Command::Unkline | Command::Ungline => serialize!(Unkline::from_generic(self)),
+            Command::Motd => serialize!(Motd::from_generic(self)),
+            Command::Version => serialize!(Version::from_generic(self)),
+            Command::Time => serialize!(Time::from_generic(self)),
+            Command::Admin => serialize!(Admin::from_generic(self)),
+            Command::Info => serialize!(Info::from_generic(self)),
+            Command::Lusers => serialize!(Lusers::from_generic(self)),
+            Command::Reply => serialize!(Reply::from_generic(self)),
+            Command::Stats => serialize!(Stats::from_generic(self)),
+            Command::Trace => serialize!(Trace::from_generic(self)),
+            Command::Kill => serialize!(Kill::from_generic(self)),
+            Command::Wallops => serialize!(Wallops::from_generic(self)),
+            Command::Rehash => serialize!(Rehash::from_generic(self)),
+            Command::Restart | Command::Die => serialize!(Restart::from_generic(self)),
+            Command::Whowas => serialize!(Whowas::from_generic(self)),
+            Command::Ison => serialize!(Ison::from_generic(self)),
+            Command::Userhost => serialize!(Userhost::from_generic(self)),
+            Command::Monitor => serialize!(Monitor::from_generic(self)),
+            Command::Knock => serialize!(Knock::from_generic(self)),
+            Command::TestFilter => serialize!(TestFilter::from_generic(self)),
+        }
+    }
+
     /// funcion encargada de desenvolver la logica de devolver el prefijo
     fn retrieve_prefix(tokens: &mut VecDeque<&'a [u8]>) -> Option<&'a [u8]> {
         let first_token = tokens.front();
@@ -0,0 +1,153 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de info.
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, RPL_ENDOFINFO, RPL_INFO, SERVER_VERSION};
+use crate::irc::message::utils::{validate_command, validate_irc_params_len};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError};
+use crate::irc::model::server::Server;
+use crate::irc::model::utils::format_unix_time;
+use crate::irc::model::{MTClient, MTServerConnection};
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+
+use super::serializer::MessageSerializer;
+use super::server_query::ServerQuery;
+use super::{Executable, Serializable, ServerExecutable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a info
+/// Contiene un server opcional al que se le pide informacion general.
+pub struct Info<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub target: Option<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Info<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Info)?;
+        validate_irc_params_len(&generic.parameters, 1, 0, ERR_NEEDMOREPARAMS)?;
+
+        Ok(Self {
+            prefix: generic.prefix,
+            target: generic.parameters.pop_front(),
+        })
+    }
+}
+
+impl Serializable for Info<'_> {
+    fn serialize(&self) -> String {
+        let mut s = MessageSerializer::new(self.prefix, Command::Info);
+
+        if let Some(v) = self.target {
+            s = s.add_parameter(v);
+        }
+
+        s.serialize()
+    }
+}
+
+impl ServerQuery for Info<'_> {
+    fn prefix(&self) -> Option<&[u8]> {
+        self.prefix
+    }
+
+    fn target(&self) -> Option<&[u8]> {
+        self.target
+    }
+
+    fn command(&self) -> Command {
+        Command::Info
+    }
+
+    fn replies(&self, server: &Server) -> Vec<ResponseType> {
+        let lines = [
+            format!("{} - Rust IRC server", SERVER_VERSION),
+            "Taller de Programacion I - FIUBA".to_owned(),
+            "Impl Display For Group:".to_owned(),
+            "  Agustin Ariel Andrade, Carolina Di Matteo,".to_owned(),
+            "  Tomas Apaldetti, Valentina Laura Correa".to_owned(),
+            format!("On-line since {}", format_unix_time(server.started_at)),
+        ];
+
+        let mut response = ResponseBuilder::new();
+        for line in lines {
+            response = response.add_content_for_response(RPL_INFO, format!(":{}", line));
+        }
+
+        response
+            .add_content_for_response(RPL_ENDOFINFO, "End of /INFO list".to_owned())
+            .build()
+    }
+}
+
+impl Executable for Info<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        self.query(server, client)
+    }
+}
+
+impl ServerExecutable for Info<'_> {
+    fn _execute_for_server(&self, server: &Server) -> Vec<ResponseType> {
+        self.query_for_server(server);
+        ResponseBuilder::new().build()
+    }
+
+    fn execute_for_server(&self, server: &Server, _: MTServerConnection) -> Vec<ResponseType> {
+        self._execute_for_server(server)
+    }
+}
+
+#[cfg(test)]
+mod info_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{InvalidCommand, TooManyParams};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_info_with_different_command_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Links,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Info::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_info_with_too_many_params_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"127.0.0.1:8080");
+        parameters.push_back(b"other");
+
+        let generic = GenericMessage {
+            command: Command::Info,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Info::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, TooManyParams);
+    }
+
+    #[test]
+    fn test_info_with_target() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"127.0.0.1:8080");
+
+        let generic = GenericMessage {
+            command: Command::Info,
+            prefix: Some(b"nick"),
+            parameters,
+        };
+
+        let info = Info::from_generic(generic).unwrap();
+
+        assert_eq!(info.target.unwrap(), b"127.0.0.1:8080");
+        assert_eq!(info.serialize(), ":nick INFO 127.0.0.1:8080".to_owned());
+    }
+}
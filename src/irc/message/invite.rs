@@ -2,7 +2,7 @@
 use crate::irc::constants::{ERR_NEEDMOREPARAMS, RPL_INVITING};
 use crate::irc::message::utils::{
     generate_string, validate_channel, validate_command, validate_irc_params_len,
-    validate_name_invalid_none, validate_realname_valid_none,
+    validate_name_invalid_none, validate_prefix,
 };
 use crate::irc::message::{Command, Executable, FromGeneric, GenericMessage, MessageError};
 use crate::irc::model::server::Server;
@@ -28,7 +28,7 @@ impl<'a> FromGeneric<'a> for Invite<'a> {
 
         let nickname = validate_name_invalid_none(generic.parameters.pop_front())?;
         let channel = validate_channel(generic.parameters.pop_front())?;
-        let prefix = validate_realname_valid_none(generic.prefix)?;
+        let prefix = validate_prefix(generic.prefix)?;
 
         Ok(Self {
             prefix,
@@ -0,0 +1,116 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de ison.
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, RPL_ISON};
+use crate::irc::message::utils::{validate_command, validate_irc_params_len};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError, UNLIMITED_MAX_LEN};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+
+use super::serializer::MessageSerializer;
+use super::utils::{generate_string, split_trailing_list};
+use super::{Executable, Serializable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a ison.
+/// Contiene los nicknames a consultar, separados por espacios.
+pub struct Ison<'a> {
+    pub nicknames: Vec<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Ison<'a> {
+    fn from_generic(generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Ison)?;
+        validate_irc_params_len(
+            &generic.parameters,
+            UNLIMITED_MAX_LEN,
+            1,
+            ERR_NEEDMOREPARAMS,
+        )?;
+
+        // The list may come as trailing parameter, only its first token has the colon
+        let nicknames = split_trailing_list(generic.parameters);
+        if nicknames.is_empty() {
+            return Err(MessageError::IRCDefined(ERR_NEEDMOREPARAMS));
+        }
+
+        Ok(Self { nicknames })
+    }
+}
+
+impl Serializable for Ison<'_> {
+    fn serialize(&self) -> String {
+        MessageSerializer::new(None, Command::Ison)
+            .add_trailing_params(&self.nicknames)
+            .serialize()
+    }
+}
+
+impl Executable for Ison<'_> {
+    fn _execute(&self, server: &Server, _client: MTClient) -> Vec<ResponseType> {
+        let nicknames: Vec<String> = self.nicknames.iter().map(|n| generate_string(n)).collect();
+        let online = server.online_nicknames(&nicknames);
+
+        ResponseBuilder::new()
+            .add_content_for_response(RPL_ISON, format!(":{}", online.join(" ")))
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod ison_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{IRCDefined, InvalidCommand};
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test_ison_with_different_command_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick");
+
+        let generic = GenericMessage {
+            command: Command::Who,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Ison::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_ison_without_nicknames_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Ison,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Ison::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, IRCDefined(ERR_NEEDMOREPARAMS));
+    }
+
+    #[test]
+    fn test_ison_with_trailing_nicknames() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"alice");
+        parameters.push_back(b":bob");
+        parameters.push_back(b"carol");
+
+        let generic = GenericMessage {
+            command: Command::Ison,
+            prefix: None,
+            parameters,
+        };
+
+        let ison = Ison::from_generic(generic).unwrap();
+
+        assert_eq!(ison.nicknames, vec![b"alice".as_ref(), b"bob", b"carol"]);
+        assert_eq!(ison.serialize(), "ISON :alice bob carol".to_owned());
+    }
+}
@@ -1,6 +1,6 @@
 //! Modulo que se centra en las funcionalidades referentes al mensaje de join.
 use crate::irc::constants::{RPL_CHANNELMODEIS, RPL_ENDOFNAMES};
-use crate::irc::message::utils::validate_realname_valid_none;
+use crate::irc::message::utils::validate_prefix;
 use crate::{
     irc::{
         constants::{ERR_NEEDMOREPARAMS, RPL_NAMREPLY},
@@ -39,7 +39,7 @@ impl<'a> FromGeneric<'a> for Join<'a> {
         validate_command(generic.command, Command::Join)?;
         validate_irc_params_len(&generic.parameters, 2, 1, ERR_NEEDMOREPARAMS)?;
 
-        let prefix = validate_realname_valid_none(generic.prefix)?;
+        let prefix = validate_prefix(generic.prefix)?;
         let channels = validate_channels(generic.parameters.pop_front())?;
         let keys = split_csl_none(generic.parameters.pop_front());
 
@@ -141,12 +141,22 @@ impl Join<'_> {
         vec![(0, "a".to_owned())] //ignore
     }
 
+    fn joined_channels(&self, client: &MTClient) -> Vec<usize> {
+        let c = try_lock!(client);
+        self.channels
+            .iter()
+            .enumerate()
+            .filter(|(_, ch)| c.is_in_channel(&generate_string(ch)))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
     fn notify(&self, server: &Server, channel_name: &str, joiner: MTClient) {
         let joiner_nick = { try_lock!(joiner).nickname.to_owned() };
-        server.server_action_notify(&format!(
-            "{}: {} {}",
-            RPL_NAMREPLY, channel_name, joiner_nick
-        ))
+        server.server_action_notify_for(
+            &joiner_nick,
+            &format!("{}: {} {}", RPL_NAMREPLY, channel_name, joiner_nick),
+        )
     }
 }
 
@@ -154,17 +164,28 @@ impl Replicable for Join<'_> {
     fn _execute(&self, server: &Server, client: MTClient) -> (Vec<ResponseType>, bool) {
         let mut responseb = ResponseBuilder::new();
 
-        let responses = self.iterate_channels(true, server, client);
+        let responses = self.iterate_channels(true, server, client.clone());
 
         for response in responses {
             responseb = responseb.add_content_for_response(response.0, response.1);
         }
 
-        (responseb.build(), true)
+        let joined = !self.joined_channels(&client).is_empty();
+        (responseb.build(), joined)
     }
 
     fn forward(&mut self, client: MTClient) -> String {
         let nick = { try_lock!(client).nickname.to_owned() };
+        // Solo se replican los canales a los que el cliente realmente entro.
+        let joined = self.joined_channels(&client);
+        if let Some(keys) = &self.keys {
+            let keys: Vec<&[u8]> = joined
+                .iter()
+                .filter_map(|index| keys.get(*index).copied())
+                .collect();
+            self.keys = (!keys.is_empty()).then_some(keys);
+        }
+        self.channels = joined.iter().map(|index| self.channels[*index]).collect();
         self.prefix = None;
         format!(":{} {}", nick, self.serialize())
     }
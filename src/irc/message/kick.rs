@@ -7,6 +7,7 @@ use crate::irc::message::utils::{
     validate_name_invalid_none, validate_name_valid_none, validate_text,
 };
 use crate::irc::message::{Command, FromGeneric, GenericMessage, MessageError, UNLIMITED_MAX_LEN};
+use crate::irc::model::irc_key::IrcKey;
 use crate::irc::model::server::Server;
 use crate::irc::model::MTClient;
 use crate::irc::responses::builder::ResponseBuilder;
@@ -80,7 +81,7 @@ impl Replicable for Kick<'_> {
         let kicker_name = { try_lock!(client).nickname.to_owned() };
 
         {
-            if kicker_name == user_name {
+            if IrcKey::from(&kicker_name) == IrcKey::from(&user_name) {
                 response = response.add_content_for_response(
                     ERR_SAMEUSER,
                     "You can't kick yourself off a channel, use PART".to_owned(),
@@ -117,15 +118,17 @@ impl Replicable for Kick<'_> {
             None => "No reason given".to_owned(),
         };
 
-        let mut kicked = try_lock!(kicked_user);
-
-        server.send_message_to_local_client(
-            &mut kicked,
-            &format!(
-                "{} :Kicked from channel\n{} {} :{}",
-                channel_name, channel_name, kicker_name, reason
-            ),
-        );
+        {
+            // Released before notifying, the notification locks every client
+            let mut kicked = try_lock!(kicked_user);
+            server.send_message_to_local_client(
+                &mut kicked,
+                &format!(
+                    "{} :Kicked from channel\n{} {} :{}",
+                    channel_name, channel_name, kicker_name, reason
+                ),
+            );
+        }
 
         self.notify(server, &channel_name, &user_name);
 
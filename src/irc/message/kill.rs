@@ -0,0 +1,193 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de kill.
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, ERR_NOPRIVILEGES, ERR_NOSUCHNICK};
+use crate::irc::message::utils::{
+    generate_string, generate_string_from_vec, no_such_nick, validate_command,
+    validate_irc_params_len, validate_name_invalid_none, validate_text,
+};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError, UNLIMITED_MAX_LEN};
+use crate::irc::model::server::Server;
+use crate::irc::model::{MTClient, MTServerConnection, ServerError};
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+use crate::try_lock;
+
+use super::serializer::MessageSerializer;
+use super::{Executable, Serializable, ServerExecutable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a kill, solo para operadores.
+/// Desconecta al cliente en el server al que este conectado.
+pub struct Kill<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub nickname: &'a [u8],
+    pub reason: Vec<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Kill<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Kill)?;
+        validate_irc_params_len(
+            &generic.parameters,
+            UNLIMITED_MAX_LEN,
+            2,
+            ERR_NEEDMOREPARAMS,
+        )?;
+
+        let nickname = validate_name_invalid_none(generic.parameters.pop_front())?;
+        let reason = validate_text(generic.parameters)?;
+
+        Ok(Self {
+            prefix: generic.prefix,
+            nickname,
+            reason,
+        })
+    }
+}
+
+impl Serializable for Kill<'_> {
+    fn serialize(&self) -> String {
+        MessageSerializer::new(self.prefix, Command::Kill)
+            .add_parameter(self.nickname)
+            .add_trailing_params(&self.reason)
+            .serialize()
+    }
+}
+
+impl Kill<'_> {
+    /// Desconecta al cliente si es local, si no encamina el kill hacia su server.
+    fn kill(&self, server: &Server, killer: &str) -> Result<(), ServerError> {
+        let nickname = generate_string(self.nickname);
+        let reason = generate_string_from_vec(&self.reason);
+
+        let client = match server.get_client_by_nickname(&nickname) {
+            Some(c) => c,
+            None => {
+                return Err(ServerError {
+                    code: ERR_NOSUCHNICK,
+                    msg: no_such_nick(self.nickname),
+                })
+            }
+        };
+
+        let servername = { try_lock!(client).servername.to_owned() };
+        if servername == server.host {
+            server.kill_local_client(client, &format!("Killed ({} ({}))", killer, reason));
+            return Ok(());
+        }
+
+        server.replicate_to_servername(
+            &format!(":{} KILL {} :{}", killer, nickname, reason),
+            &servername,
+            None,
+        )
+    }
+}
+
+impl Executable for Kill<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        let (nickname, is_oper) = {
+            let c = try_lock!(client);
+            (c.nickname.to_owned(), c.server_operator)
+        };
+
+        if !is_oper {
+            return ResponseBuilder::new()
+                .add_from_error(MessageError::IRCDefined(ERR_NOPRIVILEGES))
+                .build();
+        }
+
+        if let Err(e) = self.kill(server, &nickname) {
+            return ResponseBuilder::new()
+                .add_content_for_response(e.code, e.msg)
+                .build();
+        }
+
+        server.audit(
+            &nickname,
+            &format!(
+                "KILL {} :{}",
+                generate_string(self.nickname),
+                generate_string_from_vec(&self.reason)
+            ),
+        );
+        ResponseBuilder::new().build()
+    }
+}
+
+impl ServerExecutable for Kill<'_> {
+    fn _execute_for_server(&self, server: &Server) -> Vec<ResponseType> {
+        if let Some(killer) = self.prefix {
+            let _ = self.kill(server, &generate_string(killer));
+        }
+
+        ResponseBuilder::new().build()
+    }
+
+    fn execute_for_server(&self, server: &Server, _: MTServerConnection) -> Vec<ResponseType> {
+        // Only travels towards the victim's server, the quit is what gets broadcasted
+        self._execute_for_server(server)
+    }
+}
+
+#[cfg(test)]
+mod kill_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{IRCDefined, InvalidCommand};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_kill_with_different_command_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick");
+        parameters.push_back(b":reason");
+
+        let generic = GenericMessage {
+            command: Command::Kick,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Kill::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_kill_without_reason_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick");
+
+        let generic = GenericMessage {
+            command: Command::Kill,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Kill::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, IRCDefined(ERR_NEEDMOREPARAMS));
+    }
+
+    #[test]
+    fn test_kill_with_prefix() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick");
+        parameters.push_back(b":flooding");
+        parameters.push_back(b"channels");
+
+        let generic = GenericMessage {
+            command: Command::Kill,
+            prefix: Some(b"oper"),
+            parameters,
+        };
+
+        let kill = Kill::from_generic(generic).unwrap();
+
+        assert_eq!(kill.nickname, b"nick");
+        assert_eq!(
+            kill.serialize(),
+            ":oper KILL nick :flooding channels".to_owned()
+        );
+    }
+}
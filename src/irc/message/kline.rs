@@ -0,0 +1,261 @@
+//! Modulo que se centra en las funcionalidades referentes a los mensajes de kline y gline.
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, ERR_NOPRIVILEGES, RPL_ENDOFSTATS, RPL_STATSKLINE};
+use crate::irc::message::utils::{
+    generate_string, generate_string_from_vec, validate_irc_params_len, validate_server_ban_mask,
+    validate_text,
+};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError, UNLIMITED_MAX_LEN};
+use crate::irc::model::server::Server;
+use crate::irc::model::server_ban::ServerBan;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+use crate::try_lock;
+
+use super::serializer::MessageSerializer;
+use super::{Executable, Serializable, ServerExecutable};
+
+#[derive(Debug)]
+/// Struct de los mensajes referidos a kline y gline.
+/// Sin mascara se listan los baneos vigentes, la duracion es en minutos (0 es permanente).
+/// Las G-lines se replican a toda la red.
+pub struct Kline<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub global: bool,
+    pub duration: Option<u64>,
+    pub mask: Option<&'a [u8]>,
+    pub reason: Option<Vec<&'a [u8]>>,
+}
+
+impl<'a> FromGeneric<'a> for Kline<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        let global = match generic.command {
+            Command::Kline => false,
+            Command::Gline => true,
+            _ => return Err(MessageError::InvalidCommand),
+        };
+        validate_irc_params_len(
+            &generic.parameters,
+            UNLIMITED_MAX_LEN,
+            0,
+            ERR_NEEDMOREPARAMS,
+        )?;
+
+        let mut duration = None;
+        if let Some(first) = generic.parameters.front() {
+            if first.iter().all(u8::is_ascii_digit) {
+                duration = match generate_string(first).parse::<u64>() {
+                    Ok(v) => Some(v),
+                    Err(_) => return Err(MessageError::InvalidFormat),
+                };
+                generic.parameters.pop_front();
+            }
+        }
+
+        let mut mask = None;
+        if duration.is_some() || !generic.parameters.is_empty() {
+            mask = Some(validate_server_ban_mask(generic.parameters.pop_front())?);
+        }
+
+        let mut reason = None;
+        if !generic.parameters.is_empty() {
+            reason = Some(validate_text(generic.parameters)?);
+        }
+
+        Ok(Self {
+            prefix: generic.prefix,
+            global,
+            duration,
+            mask,
+            reason,
+        })
+    }
+}
+
+impl Serializable for Kline<'_> {
+    fn serialize(&self) -> String {
+        let command = if self.global {
+            Command::Gline
+        } else {
+            Command::Kline
+        };
+        let mut s = MessageSerializer::new(self.prefix, command);
+
+        if let Some(d) = self.duration {
+            s = s.add_parameter(d.to_string().as_bytes());
+        }
+
+        if let Some(m) = self.mask {
+            s = s.add_parameter(m);
+        }
+
+        if let Some(r) = &self.reason {
+            s = s.add_trailing_params(r);
+        }
+
+        s.serialize()
+    }
+}
+
+impl Kline<'_> {
+    fn create_ban(&self, mask: &[u8], setter: String) -> ServerBan {
+        let reason = match &self.reason {
+            Some(r) => generate_string_from_vec(r),
+            None => "No reason".to_owned(),
+        };
+
+        ServerBan::create(
+            generate_string(mask),
+            reason,
+            setter,
+            self.duration.unwrap_or(0),
+            self.global,
+        )
+    }
+
+    fn describe_bans(&self, server: &Server) -> Vec<ResponseType> {
+        let mut response = ResponseBuilder::new();
+        let kind = if self.global { "G" } else { "K" };
+
+        for ban in server.describe_server_bans(self.global) {
+            response = response.add_content_for_response(RPL_STATSKLINE, ban);
+        }
+
+        response
+            .add_content_for_response(RPL_ENDOFSTATS, format!("{} :End of /STATS report", kind))
+            .build()
+    }
+}
+
+impl Executable for Kline<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        let (nickname, is_oper) = {
+            let c = try_lock!(client);
+            (c.nickname.to_owned(), c.server_operator)
+        };
+
+        if !is_oper {
+            return ResponseBuilder::new()
+                .add_from_error(MessageError::IRCDefined(ERR_NOPRIVILEGES))
+                .build();
+        }
+
+        let mask = match self.mask {
+            Some(v) => v,
+            None => return self.describe_bans(server),
+        };
+
+        let ban = self.create_ban(mask, nickname.to_owned());
+        let notice = format!(
+            ":{} NOTICE {} :Added {}-Line for [{}] [{}]",
+            server.host,
+            nickname,
+            ban.kind(),
+            ban.mask,
+            ban.reason
+        );
+
+        if self.global {
+            server.replicate_to_all_servers(&ban.gline_message(&nickname));
+        }
+        server.add_server_ban(ban);
+
+        server.send_message_to_local_client(&mut *try_lock!(client), &notice);
+        ResponseBuilder::new().build()
+    }
+}
+
+impl ServerExecutable for Kline<'_> {
+    fn _execute_for_server(&self, server: &Server) -> Vec<ResponseType> {
+        if let (true, Some(mask)) = (self.global, self.mask) {
+            let setter = match self.prefix {
+                Some(p) => generate_string(p),
+                None => "*".to_owned(),
+            };
+            server.add_server_ban(self.create_ban(mask, setter));
+        }
+
+        ResponseBuilder::new().build()
+    }
+}
+
+#[cfg(test)]
+mod kline_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{InvalidCommand, InvalidFormat};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_kline_with_different_command_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Kick,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Kline::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_kline_without_params_lists() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Kline,
+            prefix: None,
+            parameters,
+        };
+
+        let kline = Kline::from_generic(generic).unwrap();
+
+        assert!(kline.mask.is_none());
+        assert!(!kline.global);
+        assert_eq!(kline.serialize(), "KLINE".to_owned());
+    }
+
+    #[test]
+    fn test_gline_with_duration_and_reason() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"30");
+        parameters.push_back(b"*@10.0.0.0/8");
+        parameters.push_back(b":spam");
+        parameters.push_back(b"bots");
+
+        let generic = GenericMessage {
+            command: Command::Gline,
+            prefix: Some(b"oper"),
+            parameters,
+        };
+
+        let gline = Kline::from_generic(generic).unwrap();
+
+        assert!(gline.global);
+        assert_eq!(gline.duration, Some(30));
+        assert_eq!(gline.mask.unwrap(), b"*@10.0.0.0/8");
+        assert_eq!(
+            gline.serialize(),
+            ":oper GLINE 30 *@10.0.0.0/8 :spam bots".to_owned()
+        );
+    }
+
+    #[test]
+    fn test_kline_with_invalid_mask_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick");
+
+        let generic = GenericMessage {
+            command: Command::Kline,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Kline::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidFormat);
+    }
+}
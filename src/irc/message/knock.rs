@@ -0,0 +1,188 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de knock.
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, RPL_KNOCKDLVR};
+use crate::irc::message::utils::{
+    generate_string, generate_string_from_vec, validate_channel, validate_command,
+    validate_irc_params_len, validate_prefix, validate_text,
+};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError, UNLIMITED_MAX_LEN};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+use crate::try_lock;
+
+use super::serializer::MessageSerializer;
+use super::{Replicable, Serializable, ServerExecutable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a knock, pide una invitacion a un canal +i.
+/// Se avisa a los operadores del canal en toda la red.
+pub struct Knock<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub channel: &'a [u8],
+    pub reason: Option<Vec<&'a [u8]>>,
+}
+
+impl<'a> FromGeneric<'a> for Knock<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Knock)?;
+        validate_irc_params_len(
+            &generic.parameters,
+            UNLIMITED_MAX_LEN,
+            1,
+            ERR_NEEDMOREPARAMS,
+        )?;
+
+        let prefix = validate_prefix(generic.prefix)?;
+        let channel = validate_channel(generic.parameters.pop_front())?;
+
+        let mut reason = None;
+        if !generic.parameters.is_empty() {
+            reason = Some(validate_text(generic.parameters)?);
+        }
+
+        Ok(Self {
+            prefix,
+            channel,
+            reason,
+        })
+    }
+}
+
+impl Serializable for Knock<'_> {
+    fn serialize(&self) -> String {
+        let mut s = MessageSerializer::new(self.prefix, Command::Knock).add_parameter(self.channel);
+
+        if let Some(r) = &self.reason {
+            s = s.add_trailing_params(r);
+        }
+
+        s.serialize()
+    }
+}
+
+impl Knock<'_> {
+    fn reason(&self) -> Option<String> {
+        self.reason.as_ref().map(|r| generate_string_from_vec(r))
+    }
+}
+
+impl Replicable for Knock<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> (Vec<ResponseType>, bool) {
+        let channel_name = generate_string(self.channel);
+
+        match server.knock_channel(client, &channel_name) {
+            Ok(hostmask) => {
+                server.notify_knock(&channel_name, &hostmask, self.reason());
+                let response = ResponseBuilder::new().add_content_for_response(
+                    RPL_KNOCKDLVR,
+                    format!("{} :Your KNOCK has been delivered", channel_name),
+                );
+                (response.build(), true)
+            }
+            Err(err) => (
+                ResponseBuilder::new()
+                    .add_content_for_response(err.code, err.msg)
+                    .build(),
+                false,
+            ),
+        }
+    }
+
+    fn forward(&mut self, client: MTClient) -> String {
+        let nick = { try_lock!(client).nickname.to_owned() };
+        self.prefix = None;
+        format!(":{} {}", nick, self.serialize())
+    }
+}
+
+impl ServerExecutable for Knock<'_> {
+    fn _execute_for_server(&self, server: &Server) -> Vec<ResponseType> {
+        // The origin server already checked the KNOCK, only the local operators are left
+        if let Some(v) = self.prefix {
+            if let Some(client) = server.get_client_by_nickname(&generate_string(v)) {
+                let hostmask = { try_lock!(client).hostmask() };
+                server.notify_knock(&generate_string(self.channel), &hostmask, self.reason());
+            }
+        }
+        ResponseBuilder::new().build()
+    }
+}
+
+#[cfg(test)]
+mod knock_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{IRCDefined, InvalidCommand, InvalidFormat};
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test_knock_with_different_command_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"#channel");
+
+        let generic = GenericMessage {
+            command: Command::Invite,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Knock::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_knock_without_channel_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Knock,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Knock::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, IRCDefined(ERR_NEEDMOREPARAMS));
+    }
+
+    #[test]
+    fn test_knock_with_invalid_channel_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"channel");
+
+        let generic = GenericMessage {
+            command: Command::Knock,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Knock::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidFormat);
+    }
+
+    #[test]
+    fn test_knock_with_reason() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"#channel");
+        parameters.push_back(b":let");
+        parameters.push_back(b"me");
+        parameters.push_back(b"in");
+
+        let generic = GenericMessage {
+            command: Command::Knock,
+            prefix: Some(b"nick"),
+            parameters,
+        };
+
+        let knock = Knock::from_generic(generic).unwrap();
+
+        assert_eq!(knock.channel, b"#channel");
+        assert_eq!(
+            knock.serialize(),
+            ":nick KNOCK #channel :let me in".to_owned()
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de links.
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, RPL_ENDOFLINKS, RPL_LINKS};
+use crate::irc::message::utils::{generate_string, validate_command, validate_irc_params_len};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+
+use super::serializer::MessageSerializer;
+use super::{Executable, Serializable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a links
+/// Contiene una mascara opcional para filtrar los servers listados.
+pub struct Links<'a> {
+    pub mask: Option<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Links<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Links)?;
+        validate_irc_params_len(&generic.parameters, 1, 0, ERR_NEEDMOREPARAMS)?;
+
+        Ok(Self {
+            mask: generic.parameters.pop_front(),
+        })
+    }
+}
+
+impl Serializable for Links<'_> {
+    fn serialize(&self) -> String {
+        let mut s = MessageSerializer::new(None, Command::Links);
+
+        if let Some(v) = self.mask {
+            s = s.add_parameter(v);
+        }
+
+        s.serialize()
+    }
+}
+
+impl Executable for Links<'_> {
+    fn _execute(&self, server: &Server, _: MTClient) -> Vec<ResponseType> {
+        let mut response = ResponseBuilder::new();
+        let mask = match self.mask {
+            Some(v) => generate_string(v),
+            None => "*".to_owned(),
+        };
+
+        for link in server.describe_links(&mask) {
+            response = response.add_content_for_response(RPL_LINKS, link);
+        }
+
+        response
+            .add_content_for_response(RPL_ENDOFLINKS, format!("{} :End of /LINKS list", mask))
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod links_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{InvalidCommand, TooManyParams};
+    use std::collections::VecDeque;
+
+    #[test]
+    fn links_validate_command_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Who,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Links::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn links_with_many_parameters_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"127.0.0.1:8080");
+        parameters.push_back(b"*");
+
+        let generic = GenericMessage {
+            command: Command::Links,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Links::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, TooManyParams);
+    }
+
+    #[test]
+    fn links_without_mask() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Links,
+            prefix: None,
+            parameters,
+        };
+
+        let links = Links::from_generic(generic).unwrap();
+
+        assert!(links.mask.is_none());
+        assert_eq!(links.serialize(), "LINKS".to_owned());
+    }
+
+    #[test]
+    fn links_with_mask() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"127.0.0.*");
+
+        let generic = GenericMessage {
+            command: Command::Links,
+            prefix: None,
+            parameters,
+        };
+
+        let links = Links::from_generic(generic).unwrap();
+
+        assert_eq!(links.mask.unwrap(), b"127.0.0.*");
+        assert_eq!(links.serialize(), "LINKS 127.0.0.*".to_owned());
+    }
+}
@@ -0,0 +1,163 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de lusers.
+use crate::irc::constants::{
+    ERR_NEEDMOREPARAMS, RPL_LUSERCHANNELS, RPL_LUSERCLIENT, RPL_LUSERME, RPL_LUSEROP,
+};
+use crate::irc::message::utils::{validate_command, validate_irc_params_len};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError};
+use crate::irc::model::server::Server;
+use crate::irc::model::{MTClient, MTServerConnection};
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+
+use super::serializer::MessageSerializer;
+use super::server_query::ServerQuery;
+use super::{Executable, Serializable, ServerExecutable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a lusers
+/// Contiene un server opcional al que se le piden sus estadisticas de usuarios.
+pub struct Lusers<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub target: Option<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Lusers<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Lusers)?;
+        validate_irc_params_len(&generic.parameters, 1, 0, ERR_NEEDMOREPARAMS)?;
+
+        Ok(Self {
+            prefix: generic.prefix,
+            target: generic.parameters.pop_front(),
+        })
+    }
+}
+
+impl Serializable for Lusers<'_> {
+    fn serialize(&self) -> String {
+        let mut s = MessageSerializer::new(self.prefix, Command::Lusers);
+
+        if let Some(v) = self.target {
+            s = s.add_parameter(v);
+        }
+
+        s.serialize()
+    }
+}
+
+impl ServerQuery for Lusers<'_> {
+    fn prefix(&self) -> Option<&[u8]> {
+        self.prefix
+    }
+
+    fn target(&self) -> Option<&[u8]> {
+        self.target
+    }
+
+    fn command(&self) -> Command {
+        Command::Lusers
+    }
+
+    fn replies(&self, server: &Server) -> Vec<ResponseType> {
+        let info = server.lusers();
+
+        ResponseBuilder::new()
+            .add_content_for_response(
+                RPL_LUSERCLIENT,
+                format!(
+                    ":There are {} users and {} invisible on {} servers",
+                    info.users, info.invisible, info.servers
+                ),
+            )
+            .add_content_for_response(
+                RPL_LUSEROP,
+                format!("{} :operator(s) online", info.operators),
+            )
+            .add_content_for_response(
+                RPL_LUSERCHANNELS,
+                format!("{} :channels formed", info.channels),
+            )
+            .add_content_for_response(
+                RPL_LUSERME,
+                format!(
+                    ":I have {} clients and {} servers",
+                    info.local_clients, info.local_servers
+                ),
+            )
+            .build()
+    }
+}
+
+impl Executable for Lusers<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        self.query(server, client)
+    }
+}
+
+impl ServerExecutable for Lusers<'_> {
+    fn _execute_for_server(&self, server: &Server) -> Vec<ResponseType> {
+        self.query_for_server(server);
+        ResponseBuilder::new().build()
+    }
+
+    fn execute_for_server(&self, server: &Server, _: MTServerConnection) -> Vec<ResponseType> {
+        self._execute_for_server(server)
+    }
+}
+
+#[cfg(test)]
+mod lusers_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{InvalidCommand, TooManyParams};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_lusers_with_different_command_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Links,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Lusers::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_lusers_with_too_many_params_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"127.0.0.1:8080");
+        parameters.push_back(b"other");
+
+        let generic = GenericMessage {
+            command: Command::Lusers,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Lusers::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, TooManyParams);
+    }
+
+    #[test]
+    fn test_lusers_with_target() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"127.0.0.1:8080");
+
+        let generic = GenericMessage {
+            command: Command::Lusers,
+            prefix: Some(b"nick"),
+            parameters,
+        };
+
+        let lusers = Lusers::from_generic(generic).unwrap();
+
+        assert_eq!(lusers.target.unwrap(), b"127.0.0.1:8080");
+        assert_eq!(lusers.serialize(), ":nick LUSERS 127.0.0.1:8080".to_owned());
+    }
+}
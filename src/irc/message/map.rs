@@ -0,0 +1,105 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de map.
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, ERR_NOPRIVILEGES, RPL_MAP, RPL_MAPEND};
+use crate::irc::message::utils::{validate_command, validate_irc_params_len};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+use crate::try_lock;
+
+use super::serializer::MessageSerializer;
+use super::{Executable, Serializable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a map, muestra el arbol de servers de la red.
+/// Solo lo pueden usar los operadores.
+pub struct Map {}
+
+impl<'a> FromGeneric<'a> for Map {
+    fn from_generic(generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Map)?;
+        validate_irc_params_len(&generic.parameters, 0, 0, ERR_NEEDMOREPARAMS)?;
+
+        Ok(Self {})
+    }
+}
+
+impl Serializable for Map {
+    fn serialize(&self) -> String {
+        MessageSerializer::new(None, Command::Map).serialize()
+    }
+}
+
+impl Executable for Map {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        let mut response = ResponseBuilder::new();
+        if !try_lock!(client).server_operator {
+            return response
+                .add_from_error(MessageError::IRCDefined(ERR_NOPRIVILEGES))
+                .build();
+        }
+
+        for line in server.describe_map() {
+            response = response.add_content_for_response(RPL_MAP, line);
+        }
+
+        response
+            .add_content_for_response(RPL_MAPEND, "End of /MAP".to_owned())
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod map_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{InvalidCommand, TooManyParams};
+    use std::collections::VecDeque;
+
+    #[test]
+    fn map_validate_command_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Links,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Map::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn map_with_parameters_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"param");
+
+        let generic = GenericMessage {
+            command: Command::Map,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Map::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, TooManyParams);
+    }
+
+    #[test]
+    fn map_valid() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Map,
+            prefix: None,
+            parameters,
+        };
+
+        let map = Map::from_generic(generic).unwrap();
+
+        assert_eq!(map.serialize(), "MAP".to_owned());
+    }
+}
@@ -7,14 +7,27 @@ use std::ops::Deref;
 
 use super::model::{MTClient, MTServerConnection};
 
+pub mod admin;
 pub mod away;
+pub mod cap;
 pub mod channel_mode;
+pub mod end_of_burst;
 pub mod generic_message;
 pub mod generic_mode;
+pub mod info;
 pub mod invite;
+pub mod ison;
 pub mod join;
 pub mod kick;
+pub mod kill;
+pub mod kline;
+pub mod knock;
+pub mod links;
 pub mod list;
+pub mod lusers;
+pub mod map;
+pub mod monitor;
+pub mod motd;
 pub mod names;
 pub mod nickname;
 pub mod notice;
@@ -22,16 +35,31 @@ pub mod oper;
 pub mod part;
 pub mod password;
 pub mod private;
+#[cfg(test)]
+mod properties;
 pub mod quit;
+pub mod rehash;
+pub mod reply;
+pub mod restart;
 pub mod serializer;
 pub mod server;
+pub mod server_query;
 pub mod server_quit;
+pub mod stats;
+pub mod testfilter;
+pub mod time;
 pub mod topic;
+pub mod trace;
+pub mod unkline;
 pub mod user;
 pub mod user_mode;
+pub mod userhost;
 pub mod utils;
+pub mod version;
+pub mod wallops;
 pub mod who;
 pub mod whois;
+pub mod whowas;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum MessageError {
@@ -74,6 +102,34 @@ pub enum Command {
     Who,
     WhoIs,
     Away,
+    Cap,
+    EndOfBurst,
+    Links,
+    Map,
+    Kline,
+    Unkline,
+    Gline,
+    Ungline,
+    Motd,
+    Version,
+    Time,
+    Admin,
+    Info,
+    Lusers,
+    Reply,
+    Stats,
+    Trace,
+    Kill,
+    Wallops,
+    Rehash,
+    Restart,
+    Die,
+    Whowas,
+    Ison,
+    Userhost,
+    Monitor,
+    Knock,
+    TestFilter,
 }
 
 impl Command {
@@ -99,6 +155,34 @@ impl Command {
             Command::Who => "WHO",
             Command::WhoIs => "WHOIS",
             Command::Away => "AWAY",
+            Command::Cap => "CAP",
+            Command::EndOfBurst => "EOB",
+            Command::Links => "LINKS",
+            Command::Map => "MAP",
+            Command::Kline => "KLINE",
+            Command::Unkline => "UNKLINE",
+            Command::Gline => "GLINE",
+            Command::Ungline => "UNGLINE",
+            Command::Motd => "MOTD",
+            Command::Version => "VERSION",
+            Command::Time => "TIME",
+            Command::Admin => "ADMIN",
+            Command::Info => "INFO",
+            Command::Lusers => "LUSERS",
+            Command::Reply => "REPLY",
+            Command::Stats => "STATS",
+            Command::Trace => "TRACE",
+            Command::Kill => "KILL",
+            Command::Wallops => "WALLOPS",
+            Command::Rehash => "REHASH",
+            Command::Restart => "RESTART",
+            Command::Die => "DIE",
+            Command::Whowas => "WHOWAS",
+            Command::Ison => "ISON",
+            Command::Userhost => "USERHOST",
+            Command::Monitor => "MONITOR",
+            Command::Knock => "KNOCK",
+            Command::TestFilter => "TESTFILTER",
         }
     }
 
@@ -124,6 +208,34 @@ impl Command {
             "TOPIC" => Some(Command::Topic),
             "KICK" => Some(Command::Kick),
             "AWAY" => Some(Command::Away),
+            "CAP" => Some(Command::Cap),
+            "EOB" => Some(Command::EndOfBurst),
+            "LINKS" => Some(Command::Links),
+            "MAP" => Some(Command::Map),
+            "KLINE" => Some(Command::Kline),
+            "UNKLINE" => Some(Command::Unkline),
+            "GLINE" => Some(Command::Gline),
+            "UNGLINE" => Some(Command::Ungline),
+            "MOTD" => Some(Command::Motd),
+            "VERSION" => Some(Command::Version),
+            "TIME" => Some(Command::Time),
+            "ADMIN" => Some(Command::Admin),
+            "INFO" => Some(Command::Info),
+            "LUSERS" => Some(Command::Lusers),
+            "REPLY" => Some(Command::Reply),
+            "STATS" => Some(Command::Stats),
+            "TRACE" => Some(Command::Trace),
+            "KILL" => Some(Command::Kill),
+            "WALLOPS" => Some(Command::Wallops),
+            "REHASH" => Some(Command::Rehash),
+            "RESTART" => Some(Command::Restart),
+            "DIE" => Some(Command::Die),
+            "WHOWAS" => Some(Command::Whowas),
+            "ISON" => Some(Command::Ison),
+            "USERHOST" => Some(Command::Userhost),
+            "MONITOR" => Some(Command::Monitor),
+            "KNOCK" => Some(Command::Knock),
+            "TESTFILTER" => Some(Command::TestFilter),
             _ => None,
         }
     }
@@ -141,6 +253,10 @@ pub enum ChannelModes<'a> {
     Limit(Option<u32>),
     SpeakInModeratedChannel(&'a [u8]),
     ChannelKey(Option<&'a [u8]>),
+    Ban(Option<&'a [u8]>),
+    BanException(Option<&'a [u8]>),
+    InviteException(Option<&'a [u8]>),
+    Quiet(Option<&'a [u8]>),
 }
 
 #[derive(Debug)]
@@ -148,6 +264,7 @@ pub enum UserModes {
     Invisible,
     ReceiveServerNotices,
     IRCOperator,
+    Wallops,
 }
 
 #[derive(Debug)]
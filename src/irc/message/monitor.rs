@@ -0,0 +1,235 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de monitor.
+use crate::irc::constants::{
+    ERR_MONLISTFULL, ERR_NEEDMOREPARAMS, MONITOR_LIMIT, RPL_ENDOFMONLIST, RPL_MONLIST,
+    RPL_MONOFFLINE, RPL_MONONLINE,
+};
+use crate::irc::message::utils::{
+    split_csl, validate_command, validate_irc_params_len, validate_name_invalid_none,
+};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+
+use super::serializer::MessageSerializer;
+use super::utils::generate_string;
+use super::{Executable, Serializable};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MonitorAction {
+    Add,
+    Remove,
+    Clear,
+    List,
+    Status,
+}
+
+impl MonitorAction {
+    fn parse(action: &[u8]) -> Result<Self, MessageError> {
+        match action.to_ascii_uppercase().as_slice() {
+            b"+" => Ok(Self::Add),
+            b"-" => Ok(Self::Remove),
+            b"C" => Ok(Self::Clear),
+            b"L" => Ok(Self::List),
+            b"S" => Ok(Self::Status),
+            _ => Err(MessageError::InvalidFormat),
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::Add => "+",
+            Self::Remove => "-",
+            Self::Clear => "C",
+            Self::List => "L",
+            Self::Status => "S",
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Struct del mensaje referido a monitor.
+/// Agrega (+) o saca (-) nicknames de la lista del cliente, la vacia (C),
+/// la lista (L) o informa el estado de todos sus nicknames (S).
+pub struct Monitor<'a> {
+    pub action: MonitorAction,
+    pub targets: Vec<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Monitor<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Monitor)?;
+        validate_irc_params_len(&generic.parameters, 2, 1, ERR_NEEDMOREPARAMS)?;
+
+        let action = match generic.parameters.pop_front() {
+            Some(a) => MonitorAction::parse(a)?,
+            None => return Err(MessageError::IRCDefined(ERR_NEEDMOREPARAMS)),
+        };
+
+        let mut targets = vec![];
+        if let MonitorAction::Add | MonitorAction::Remove = action {
+            let csl = match generic.parameters.pop_front() {
+                Some(t) => t.strip_prefix(b":").unwrap_or(t),
+                None => return Err(MessageError::IRCDefined(ERR_NEEDMOREPARAMS)),
+            };
+            for target in split_csl(Some(csl))? {
+                targets.push(validate_name_invalid_none(Some(target))?);
+            }
+        } else if !generic.parameters.is_empty() {
+            return Err(MessageError::TooManyParams);
+        }
+
+        Ok(Self { action, targets })
+    }
+}
+
+impl Serializable for Monitor<'_> {
+    fn serialize(&self) -> String {
+        let mut s = MessageSerializer::new(None, Command::Monitor)
+            .add_parameter(self.action.to_str().as_bytes());
+
+        if !self.targets.is_empty() {
+            s = s.add_csl_params(&self.targets);
+        }
+
+        s.serialize()
+    }
+}
+
+impl Monitor<'_> {
+    fn status(
+        &self,
+        server: &Server,
+        nicknames: &[String],
+        mut response: ResponseBuilder,
+    ) -> ResponseBuilder {
+        let (online, offline) = server.monitor_status(nicknames);
+
+        if !online.is_empty() {
+            response =
+                response.add_content_for_response(RPL_MONONLINE, format!(":{}", online.join(",")));
+        }
+        if !offline.is_empty() {
+            response = response
+                .add_content_for_response(RPL_MONOFFLINE, format!(":{}", offline.join(",")));
+        }
+        response
+    }
+}
+
+impl Executable for Monitor<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        let targets: Vec<String> = self.targets.iter().map(|t| generate_string(t)).collect();
+        let mut response = ResponseBuilder::new();
+
+        match self.action {
+            MonitorAction::Add => {
+                let (added, rejected) = server.add_monitors(client, &targets);
+                response = self.status(server, &added, response);
+                if !rejected.is_empty() {
+                    response = response.add_content_for_response(
+                        ERR_MONLISTFULL,
+                        format!(
+                            "{} {} :Monitor list is full",
+                            MONITOR_LIMIT,
+                            rejected.join(",")
+                        ),
+                    );
+                }
+            }
+            MonitorAction::Remove => server.remove_monitors(client, &targets),
+            MonitorAction::Clear => server.clear_monitors(client),
+            MonitorAction::List => {
+                let list = server.monitor_list(client);
+                if !list.is_empty() {
+                    response = response
+                        .add_content_for_response(RPL_MONLIST, format!(":{}", list.join(",")));
+                }
+                response = response
+                    .add_content_for_response(RPL_ENDOFMONLIST, ":End of MONITOR list".to_owned());
+            }
+            MonitorAction::Status => {
+                let list = server.monitor_list(client);
+                response = self.status(server, &list, response);
+            }
+        }
+
+        response.build()
+    }
+}
+
+#[cfg(test)]
+mod monitor_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{IRCDefined, InvalidCommand, InvalidFormat};
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test_monitor_with_different_command_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"L");
+
+        let generic = GenericMessage {
+            command: Command::Ison,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Monitor::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_monitor_add_without_targets_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"+");
+
+        let generic = GenericMessage {
+            command: Command::Monitor,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Monitor::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, IRCDefined(ERR_NEEDMOREPARAMS));
+    }
+
+    #[test]
+    fn test_monitor_with_unknown_action_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"X");
+
+        let generic = GenericMessage {
+            command: Command::Monitor,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Monitor::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidFormat);
+    }
+
+    #[test]
+    fn test_monitor_add_targets() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"+");
+        parameters.push_back(b"alice,bob");
+
+        let generic = GenericMessage {
+            command: Command::Monitor,
+            prefix: None,
+            parameters,
+        };
+
+        let monitor = Monitor::from_generic(generic).unwrap();
+
+        assert_eq!(monitor.action, MonitorAction::Add);
+        assert_eq!(monitor.targets, vec![b"alice".as_ref(), b"bob"]);
+        assert_eq!(monitor.serialize(), "MONITOR + alice,bob".to_owned());
+    }
+}
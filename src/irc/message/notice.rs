@@ -47,10 +47,8 @@ impl Serializable for Notice<'_> {
     }
 }
 
-impl Replicable for Notice<'_> {
-    //To mantain semantic coherency, both Notice and Private Message are going to be Replicable, even though the replication strategy will be according to the message
-
-    fn _execute(&self, server: &Server, client: MTClient) -> (Vec<ResponseType>, bool) {
+impl Notice<'_> {
+    fn __execute(&self, server: &Server, client: MTClient) -> (Vec<ResponseType>, bool) {
         let mut response = ResponseBuilder::new();
         let mut should_replicate = true;
         let sender_nick = { try_lock!(client).nickname.to_owned() };
@@ -77,6 +75,23 @@ impl Replicable for Notice<'_> {
         };
         (response.build(), should_replicate)
     }
+}
+
+impl Replicable for Notice<'_> {
+    //To mantain semantic coherency, both Notice and Private Message are going to be Replicable, even though the replication strategy will be according to the message
+
+    fn _execute(&self, server: &Server, client: MTClient) -> (Vec<ResponseType>, bool) {
+        // Notices never get automatic replies, filtered ones are just dropped
+        let target = generate_string(self.nickname);
+        let text = generate_string_from_vec(&self.text);
+        if let Some(rule) = server.filter_message(client.clone(), &target, &text) {
+            if !rule.action.delivers() {
+                return (ResponseBuilder::new().build(), false);
+            }
+        }
+
+        self.__execute(server, client)
+    }
 
     fn execute(&mut self, server: &Server, client: MTClient) -> Vec<ResponseType> {
         self._execute(server, client).0
@@ -93,7 +108,7 @@ impl ServerExecutable for Notice<'_> {
     fn _execute_for_server(&self, server: &Server) -> Vec<ResponseType> {
         if let Some(v) = self.prefix {
             if let Some(client) = server.get_client_by_nickname(&generate_string(v)) {
-                return self._execute(server, client).0;
+                return self.__execute(server, client).0;
             }
         }
         ResponseBuilder::new().build()
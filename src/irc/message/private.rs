@@ -1,9 +1,9 @@
 //! Modulo que se centra en las funcionalidades referentes al mensaje de private.
 use std::collections::vec_deque::VecDeque;
 
-use crate::irc::model::MTClient;
+use crate::irc::model::{filter::FilterAction, MTClient};
 use crate::irc::{
-    constants::ERR_NORECIPIENT,
+    constants::{ERR_MSGFILTERED, ERR_NORECIPIENT},
     model::server::Server,
     responses::{builder::ResponseBuilder, ResponseType},
 };
@@ -148,10 +148,71 @@ impl Private<'_> {
     }
 }
 
+impl<'a> Private<'a> {
+    /// Pasa el mensaje por los filtros del server para cada destinatario y devuelve a quienes
+    /// se les puede entregar. None si el cliente fue desconectado por una regla.
+    fn filter_receivers(
+        &self,
+        server: &Server,
+        client: MTClient,
+        mut response: ResponseBuilder,
+    ) -> (Option<Vec<&'a [u8]>>, ResponseBuilder) {
+        let text = generate_string_from_vec(&self.text);
+        let mut receivers = vec![];
+
+        for receiver in &self.receivers {
+            let target = generate_string(receiver);
+            let rule = match server.filter_message(client.clone(), &target, &text) {
+                Some(r) => r,
+                None => {
+                    receivers.push(*receiver);
+                    continue;
+                }
+            };
+
+            match rule.action {
+                FilterAction::Block => {
+                    response = response.add_content_for_response(
+                        ERR_MSGFILTERED,
+                        format!("{} :Message blocked: {}", target, rule.reason),
+                    )
+                }
+                FilterAction::Warn => {
+                    let mut c = try_lock!(client);
+                    let warning = format!(
+                        ":{} NOTICE {} :Your message to {} was flagged: {}",
+                        server.host, c.nickname, target, rule.reason
+                    );
+                    server.send_message_to_local_client(&mut c, &warning);
+                    receivers.push(*receiver);
+                }
+                FilterAction::Notify => receivers.push(*receiver),
+                FilterAction::Kill | FilterAction::Ban(_) => return (None, response),
+            }
+        }
+
+        (Some(receivers), response)
+    }
+}
+
 impl Replicable for Private<'_> {
     //To mantain semantic coherency, both Notice and Private Message are going to be Replicable, even though the replication strategy will be according to the message
     fn _execute(&self, server: &Server, client: MTClient) -> (Vec<ResponseType>, bool) {
-        self.__execute(server, client, true)
+        let response = ResponseBuilder::new();
+        let (receivers, response) = match self.filter_receivers(server, client.clone(), response) {
+            (Some(r), response) if !r.is_empty() => (r, response),
+            (_, response) => return (response.build(), false),
+        };
+
+        let filtered = Private {
+            prefix: self.prefix,
+            receivers,
+            text: self.text.to_owned(),
+        };
+        let mut responses = response.build();
+        responses.extend(filtered.__execute(server, client, true).0);
+
+        (responses, false)
     }
 
     fn execute(&mut self, server: &Server, client: MTClient) -> Vec<ResponseType> {
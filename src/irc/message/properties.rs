@@ -0,0 +1,276 @@
+use std::collections::VecDeque;
+
+use proptest::prelude::*;
+
+use crate::irc::ctcp::utils::{to_dcc_command, upgrade_dcc_command};
+
+use super::generic_message::{GenericMessage, MESSAGE_LIMIT};
+use super::utils::*;
+use super::MessageError;
+
+const COMMANDS: &[&str] = &[
+    "PASS",
+    "NICK",
+    "USER",
+    "SERVER",
+    "OPER",
+    "QUIT",
+    "SQUIT",
+    "JOIN",
+    "PART",
+    "MODE",
+    "TOPIC",
+    "NAMES",
+    "LIST",
+    "INVITE",
+    "KICK",
+    "PRIVMSG",
+    "NOTICE",
+    "WHO",
+    "WHOIS",
+    "AWAY",
+    "CAP",
+    "EOB",
+    "LINKS",
+    "MAP",
+    "KLINE",
+    "UNKLINE",
+    "GLINE",
+    "UNGLINE",
+    "MOTD",
+    "VERSION",
+    "TIME",
+    "ADMIN",
+    "INFO",
+    "LUSERS",
+    "REPLY",
+    "STATS",
+    "TRACE",
+    "KILL",
+    "WALLOPS",
+    "REHASH",
+    "RESTART",
+    "DIE",
+    "WHOWAS",
+    "ISON",
+    "USERHOST",
+    "MONITOR",
+    "KNOCK",
+    "TESTFILTER",
+];
+
+fn canonical(line: &str) -> Result<String, MessageError> {
+    GenericMessage::parse(line)?.canonical()
+}
+
+fn nickname() -> impl Strategy<Value = String> {
+    "[a-zA-Z][a-zA-Z0-9\\-\\[\\]\\\\`^{}]{0,8}"
+}
+
+fn channel() -> impl Strategy<Value = String> {
+    "[#&][a-zA-Z0-9\\-_.!]{1,12}"
+}
+
+fn hostname() -> impl Strategy<Value = String> {
+    "[a-z0-9]{1,8}(\\.[a-z0-9]{1,8}){0,3}"
+}
+
+fn mask() -> impl Strategy<Value = String> {
+    "[a-z*?]{1,6}![a-z*?]{1,6}@[a-z0-9*?.]{1,10}"
+}
+
+/// Texto de un trailing: no empieza ni termina con espacio y no los repite,
+/// que el parseo colapsa.
+fn text() -> impl Strategy<Value = String> {
+    "[!-~\u{e1}\u{f1}]{1,10}( [!-~\u{e1}\u{f1}]{1,10}){0,5}"
+}
+
+fn list(item: impl Strategy<Value = String>) -> impl Strategy<Value = String> {
+    proptest::collection::vec(item, 1..4).prop_map(|v| v.join(","))
+}
+
+/// Mensajes validos de cada comando, escritos en la forma en que se serializan.
+fn message() -> impl Strategy<Value = String> {
+    let registration = prop_oneof![
+        "[!-~]{1,12}".prop_map(|p| format!("PASS {}", p)),
+        (proptest::option::of(nickname()), nickname()).prop_map(|(p, n)| match p {
+            Some(p) => format!(":{} NICK {}", p, n),
+            None => format!("NICK {}", n),
+        }),
+        (nickname(), hostname(), hostname(), text())
+            .prop_map(|(u, h, s, r)| format!("USER {} {} {} :{}", u, h, s, r)),
+        (hostname(), 1..20u32, hostname(), text())
+            .prop_map(|(n, hop, a, i)| format!("SERVER {} {} :{} {}", n, hop, a, i)),
+        (nickname(), "[!-~]{1,12}").prop_map(|(n, p)| format!("OPER {} {}", n, p)),
+        proptest::option::of(text()).prop_map(|t| match t {
+            Some(t) => format!("QUIT :{}", t),
+            None => "QUIT".to_owned(),
+        }),
+        (hostname(), text()).prop_map(|(s, t)| format!("SQUIT {} :{}", s, t)),
+    ];
+    let channels = prop_oneof![
+        list(channel()).prop_map(|c| format!("JOIN {}", c)),
+        list(channel()).prop_map(|c| format!("PART {}", c)),
+        (channel(), proptest::option::of(text())).prop_map(|(c, t)| match t {
+            Some(t) => format!("TOPIC {} :{}", c, t),
+            None => format!("TOPIC {}", c),
+        }),
+        list(channel()).prop_map(|c| format!("NAMES {}", c)),
+        list(channel()).prop_map(|c| format!("LIST {}", c)),
+        (nickname(), channel()).prop_map(|(n, c)| format!("INVITE {} {}", n, c)),
+        (channel(), nickname(), text()).prop_map(|(c, n, t)| format!("KICK {} {} :{}", c, n, t)),
+        (channel(), "[+-][psitnm]").prop_map(|(c, m)| format!("MODE {} {}", c, m)),
+        (channel(), "[+-][ov]", nickname()).prop_map(|(c, m, n)| format!("MODE {} {} {}", c, m, n)),
+        (channel(), "[+-][beI]", mask()).prop_map(|(c, m, b)| format!("MODE {} {} {}", c, m, b)),
+        (channel(), 1..1000u32).prop_map(|(c, l)| format!("MODE {} +l {}", c, l)),
+        (nickname(), "[+-][iswo]").prop_map(|(n, m)| format!("MODE {} {}", n, m)),
+        (channel(), text()).prop_map(|(c, t)| format!("KNOCK {} :{}", c, t)),
+    ];
+    let messages = prop_oneof![
+        (list(prop_oneof![nickname(), channel()]), text())
+            .prop_map(|(r, t)| format!("PRIVMSG {} :{}", r, t)),
+        (nickname(), text()).prop_map(|(n, t)| format!("NOTICE {} :{}", n, t)),
+        text().prop_map(|t| format!("WALLOPS :{}", t)),
+        text().prop_map(|t| format!("AWAY :{}", t)),
+        (nickname(), text()).prop_map(|(n, t)| format!("KILL {} :{}", n, t)),
+        (nickname(), text()).prop_map(|(n, t)| format!("REPLY {} :{}", n, t)),
+        (nickname(), channel(), text())
+            .prop_map(|(n, c, t)| format!("TESTFILTER {} {} :{}", n, c, t)),
+    ];
+    let queries = prop_oneof![
+        (channel(), "[0-9]{1,3}").prop_map(|(c, t)| format!("WHO {} %tcnfa,{}", c, t)),
+        list(nickname()).prop_map(|n| format!("WHOIS {}", n)),
+        (nickname(), 1..10u32).prop_map(|(n, c)| format!("WHOWAS {} {}", n, c)),
+        proptest::collection::vec(nickname(), 1..5).prop_map(|n| format!("ISON :{}", n.join(" "))),
+        proptest::collection::vec(nickname(), 1..5)
+            .prop_map(|n| format!("USERHOST {}", n.join(" "))),
+        ("[+-]", list(nickname())).prop_map(|(a, n)| format!("MONITOR {} {}", a, n)),
+        proptest::collection::vec("[a-z\\-]{1,8}", 1..4)
+            .prop_map(|c| format!("CAP REQ :{}", c.join(" "))),
+        ("MOTD|VERSION|TIME|ADMIN|INFO", hostname()).prop_map(|(q, s)| format!("{} {}", q, s)),
+        ("[a-z]", hostname()).prop_map(|(q, s)| format!("STATS {} {}", q, s)),
+    ];
+    let operators = prop_oneof![
+        ("[a-z*]{1,6}@[0-9*.]{1,10}", text()).prop_map(|(m, t)| format!("KLINE {} :{}", m, t)),
+        ("[a-z*]{1,6}@[0-9*.]{1,10}", text()).prop_map(|(m, t)| format!("GLINE {} :{}", m, t)),
+        "[a-z*]{1,6}@[0-9*.]{1,10}".prop_map(|m| format!("UNKLINE {}", m)),
+        "[a-z*]{1,6}@[0-9*.]{1,10}".prop_map(|m| format!("UNGLINE {}", m)),
+        "EOB|MAP|LINKS|LUSERS|TRACE|REHASH|RESTART|DIE",
+    ];
+
+    prop_oneof![registration, channels, messages, queries, operators]
+}
+
+/// Lineas con cualquier comando y parametros sin forma, para buscar panics.
+fn arbitrary_line() -> impl Strategy<Value = String> {
+    let parameter = prop_oneof![
+        "[^ \r\n]{0,8}",
+        "[+-][a-zA-Z+-]{0,6}",
+        "[#&][^ ,\r\n]{0,6}(,[^ ,\r\n]{0,4}){0,3}",
+        "-?[0-9]{1,12}",
+        "[*?!@.$a-z]{1,10}",
+        Just(":".to_owned()),
+    ];
+    (
+        proptest::option::of("[^ \r\n]{1,6}"),
+        proptest::sample::select(COMMANDS),
+        proptest::collection::vec(parameter, 0..8),
+        proptest::option::of("[^\r\n]{0,40}"),
+    )
+        .prop_map(|(prefix, command, parameters, trailing)| {
+            let mut line = prefix.map(|p| format!(":{} ", p)).unwrap_or_default();
+            line.push_str(command);
+            for p in parameters {
+                line.push(' ');
+                line.push_str(&p);
+            }
+            if let Some(t) = trailing {
+                line.push_str(" :");
+                line.push_str(&t);
+            }
+            line
+        })
+}
+
+fn bytes() -> impl Strategy<Value = Vec<u8>> {
+    proptest::collection::vec(any::<u8>(), 0..40)
+}
+
+proptest! {
+    #[test]
+    fn test_every_message_type_round_trips(line in message()) {
+        let serialized = canonical(&line);
+
+        prop_assert_eq!(serialized.as_ref(), Ok(&line));
+        prop_assert_eq!(canonical(&line).and_then(|s| canonical(&s)), Ok(line));
+    }
+
+    #[test]
+    fn test_parsing_any_line_never_panics_and_is_stable(line in arbitrary_line()) {
+        if let Ok(serialized) = canonical(&line) {
+            prop_assert_eq!(canonical(&serialized), Ok(serialized), "from {:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_split_message_drops_only_spaces(line in "[ a-z:#]{0,60}") {
+        let tokens = split_message(&line);
+
+        prop_assert!(tokens.iter().all(|t| !t.is_empty() && !t.contains(&b' ')));
+        let joined = tokens.iter().map(|t| generate_string(t)).collect::<Vec<_>>().join(" ");
+        prop_assert_eq!(joined, line.split_whitespace().collect::<Vec<_>>().join(" "));
+    }
+
+    #[test]
+    fn test_validations_never_panic(param in bytes(), params in proptest::collection::vec(bytes(), 0..4)) {
+        let p = Some(param.as_slice());
+        let params: VecDeque<&[u8]> = params.iter().map(|p| p.as_slice()).collect();
+
+        let _ = validate_name_invalid_none(p);
+        let _ = validate_str_starting_w_colon(p);
+        let _ = validate_realname(params.clone());
+        let _ = validate_text(params.clone());
+        let _ = validate_hostname(p);
+        let _ = validate_user(p);
+        let _ = validate_message(p);
+        let _ = validate_hostmask(p);
+        let _ = validate_receiver(&param);
+        let _ = validate_channel(p);
+        let _ = validate_channels(p);
+        let _ = validate_prefix(p);
+        let _ = validate_nickmasks(p);
+        let _ = validate_channel_mask(p);
+        let _ = validate_server_ban_mask(p);
+        let _ = validate_channel_modes(params.clone());
+        let _ = validate_user_modes(params.clone());
+        let _ = retrieve_hostname(&param);
+        let _ = split_trailing_list(params);
+    }
+
+    #[test]
+    fn test_dcc_commands_never_panic(line in "(SEND|RESUME|CHAT|CLOSE|send|chat)( ([^ ]{0,12}|[0-9]{1,12}|[0-9.]{1,16})){0,6}") {
+        let _ = upgrade_dcc_command(&line);
+        let _ = to_dcc_command(&format!("alice:\u{1}CTCP DCC {}\u{1}", line));
+    }
+}
+
+#[test]
+fn test_inputs_found_by_the_properties() {
+    // Empty address after the colon of the trailing, used to panic
+    let server = "SERVER irc.b.com 1 : info";
+    assert_eq!(canonical(server), Ok(server.to_owned()));
+    assert!(validate_str_starting_w_colon(Some(b"")).is_err());
+    assert_eq!(retrieve_hostname(b""), Ok(None));
+
+    // Serializing used to trim whitespace that was part of the content
+    let vertical_tab = "CAP LS :\u{b}";
+    assert_eq!(canonical(vertical_tab), Ok(vertical_tab.to_owned()));
+    // A colon in the middle of the trailing is content
+    assert_eq!(
+        canonical("ISON alice ::bob"),
+        Ok("ISON :alice :bob".to_owned())
+    );
+
+    let long = format!("PRIVMSG alice :{}", "a".repeat(MESSAGE_LIMIT - 15));
+    assert_eq!(canonical(&long), Ok(long.to_owned()));
+}
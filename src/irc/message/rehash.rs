@@ -0,0 +1,106 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de rehash.
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, ERR_NOPRIVILEGES, RPL_REHASHING};
+use crate::irc::message::utils::{validate_command, validate_irc_params_len};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+use crate::try_lock;
+
+use super::serializer::MessageSerializer;
+use super::{Executable, Serializable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a rehash, solo para operadores.
+/// Vuelve a leer el archivo de configuracion del server.
+pub struct Rehash<'a> {
+    pub prefix: Option<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Rehash<'a> {
+    fn from_generic(generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Rehash)?;
+        validate_irc_params_len(&generic.parameters, 0, 0, ERR_NEEDMOREPARAMS)?;
+
+        Ok(Self {
+            prefix: generic.prefix,
+        })
+    }
+}
+
+impl Serializable for Rehash<'_> {
+    fn serialize(&self) -> String {
+        MessageSerializer::new(self.prefix, Command::Rehash).serialize()
+    }
+}
+
+impl Executable for Rehash<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        let (nickname, is_oper) = {
+            let c = try_lock!(client);
+            (c.nickname.to_owned(), c.server_operator)
+        };
+
+        if !is_oper {
+            return ResponseBuilder::new()
+                .add_from_error(MessageError::IRCDefined(ERR_NOPRIVILEGES))
+                .build();
+        }
+
+        match server.rehash() {
+            Ok(file) => {
+                server.audit(&nickname, "REHASH");
+                ResponseBuilder::new()
+                    .add_content_for_response(RPL_REHASHING, format!("{} :Rehashing", file))
+                    .build()
+            }
+            Err(e) => {
+                server.send_message_to_local_client(
+                    &mut *try_lock!(client),
+                    &format!(":{} NOTICE {} :Rehash failed: {}", server.host, nickname, e),
+                );
+                ResponseBuilder::new().build()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod rehash_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{InvalidCommand, TooManyParams};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_rehash_with_different_command_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Restart,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Rehash::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_rehash_with_params_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"server.conf");
+
+        let generic = GenericMessage {
+            command: Command::Rehash,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Rehash::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, TooManyParams);
+    }
+}
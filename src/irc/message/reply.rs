@@ -0,0 +1,128 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de respuesta entre servers.
+use super::serializer::MessageSerializer;
+use super::utils::{generate_string, generate_string_from_vec, validate_text};
+use super::{generic_message::GenericMessage, Command, FromGeneric, MessageError};
+use super::{Serializable, ServerExecutable, UNLIMITED_MAX_LEN};
+use crate::irc::constants::ERR_NEEDMOREPARAMS;
+use crate::irc::message::utils::{
+    validate_command, validate_irc_params_len, validate_name_invalid_none,
+};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTServerConnection;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+
+#[derive(Debug)]
+/// Struct del mensaje con el que un server le hace llegar una respuesta
+/// a un cliente conectado a otro server. Se encamina hasta el server del cliente.
+pub struct Reply<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub nickname: &'a [u8],
+    pub line: Vec<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Reply<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Reply)?;
+        validate_irc_params_len(
+            &generic.parameters,
+            UNLIMITED_MAX_LEN,
+            2,
+            ERR_NEEDMOREPARAMS,
+        )?;
+
+        let nickname = validate_name_invalid_none(generic.parameters.pop_front())?;
+        let line = validate_text(generic.parameters)?;
+
+        Ok(Self {
+            prefix: generic.prefix,
+            nickname,
+            line,
+        })
+    }
+}
+
+impl Serializable for Reply<'_> {
+    fn serialize(&self) -> String {
+        MessageSerializer::new(self.prefix, Command::Reply)
+            .add_parameter(self.nickname)
+            .add_trailing_params(&self.line)
+            .serialize()
+    }
+}
+
+impl ServerExecutable for Reply<'_> {
+    fn _execute_for_server(&self, server: &Server) -> Vec<ResponseType> {
+        server.send_lines_to_client(
+            &generate_string(self.nickname),
+            &[generate_string_from_vec(&self.line)],
+        );
+        ResponseBuilder::new().build()
+    }
+
+    fn execute_for_server(&self, server: &Server, _: MTServerConnection) -> Vec<ResponseType> {
+        // Only travels towards the client's server, never broadcasted
+        self._execute_for_server(server)
+    }
+}
+
+#[cfg(test)]
+mod reply_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{IRCDefined, InvalidCommand};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_reply_with_different_command_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Notice,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Reply::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_reply_without_line_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick");
+
+        let generic = GenericMessage {
+            command: Command::Reply,
+            prefix: Some(b"127.0.0.1:8080"),
+            parameters,
+        };
+
+        let err = Reply::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, IRCDefined(ERR_NEEDMOREPARAMS));
+    }
+
+    #[test]
+    fn test_reply_with_numeric_line() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick");
+        parameters.push_back(b":391:");
+        parameters.push_back(b"127.0.0.1:8080");
+        parameters.push_back(b":2022-11-24");
+
+        let generic = GenericMessage {
+            command: Command::Reply,
+            prefix: Some(b"127.0.0.1:8080"),
+            parameters,
+        };
+
+        let reply = Reply::from_generic(generic).unwrap();
+
+        assert_eq!(reply.nickname, b"nick");
+        assert_eq!(
+            generate_string_from_vec(&reply.line),
+            "391: 127.0.0.1:8080 :2022-11-24".to_owned()
+        );
+    }
+}
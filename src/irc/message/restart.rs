@@ -0,0 +1,130 @@
+//! Modulo que se centra en las funcionalidades referentes a los mensajes de restart y die.
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, ERR_NOPRIVILEGES};
+use crate::irc::message::utils::validate_irc_params_len;
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+use crate::try_lock;
+
+use super::serializer::MessageSerializer;
+use super::{Executable, Serializable};
+
+#[derive(Debug)]
+/// Struct de los mensajes referidos a restart y die, solo para operadores.
+/// Ambos apagan el server, restart ademas lo vuelve a levantar.
+pub struct Restart<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub die: bool,
+}
+
+impl<'a> FromGeneric<'a> for Restart<'a> {
+    fn from_generic(generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        let die = match generic.command {
+            Command::Restart => false,
+            Command::Die => true,
+            _ => return Err(MessageError::InvalidCommand),
+        };
+        validate_irc_params_len(&generic.parameters, 0, 0, ERR_NEEDMOREPARAMS)?;
+
+        Ok(Self {
+            prefix: generic.prefix,
+            die,
+        })
+    }
+}
+
+impl Serializable for Restart<'_> {
+    fn serialize(&self) -> String {
+        let command = if self.die {
+            Command::Die
+        } else {
+            Command::Restart
+        };
+
+        MessageSerializer::new(self.prefix, command).serialize()
+    }
+}
+
+impl Executable for Restart<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        let (nickname, is_oper) = {
+            let c = try_lock!(client);
+            (c.nickname.to_owned(), c.server_operator)
+        };
+
+        let action = if self.die { "DIE" } else { "RESTART" };
+        if !is_oper {
+            server.audit(&nickname, &format!("{} denied", action));
+            return ResponseBuilder::new()
+                .add_from_error(MessageError::IRCDefined(ERR_NOPRIVILEGES))
+                .build();
+        }
+
+        server.audit(&nickname, action);
+        let reason = if self.die {
+            format!("Server terminated by {}", nickname)
+        } else {
+            format!("Server restarted by {}", nickname)
+        };
+        server.terminate(&reason, !self.die);
+
+        ResponseBuilder::new().build()
+    }
+}
+
+#[cfg(test)]
+mod restart_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{InvalidCommand, TooManyParams};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_restart_with_different_command_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Rehash,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Restart::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_die_with_params_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"now");
+
+        let generic = GenericMessage {
+            command: Command::Die,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Restart::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, TooManyParams);
+    }
+
+    #[test]
+    fn test_die_with_prefix() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Die,
+            prefix: Some(b"oper"),
+            parameters,
+        };
+
+        let die = Restart::from_generic(generic).unwrap();
+
+        assert!(die.die);
+        assert_eq!(die.serialize(), ":oper DIE".to_owned());
+    }
+}
@@ -39,8 +39,9 @@ impl MessageSerializer {
     }
 
     pub fn serialize(&self) -> String {
-        let s = format!("{} {} {}\r\n", self.prefix, self.command, self.parameters)
-            .trim()
+        // Only the separators go, the content may end with other whitespace
+        let s = format!("{} {} {}", self.prefix, self.command, self.parameters)
+            .trim_matches(' ')
             .to_owned();
         s
     }
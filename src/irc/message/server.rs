@@ -4,7 +4,7 @@ use super::utils::generate_string_from_vec;
 use super::{generic_message::GenericMessage, Command, FromGeneric, MessageError};
 use super::{Serializable, ServerExecutable};
 use crate::irc::constants::ERR_NEEDMOREPARAMS;
-use crate::irc::constants::{ERR_REGMISSING, RPL_REGISTERED};
+use crate::irc::constants::{DEFAULT_SERVERNAME, ERR_REGMISSING, RPL_REGISTERED};
 use crate::irc::message::utils::{
     generate_string, retrieve_hostname, validate_command, validate_irc_params_len, validate_text,
 };
@@ -16,6 +16,7 @@ use crate::irc::model::utils::mt;
 use crate::irc::model::MTServerConnection;
 use crate::irc::responses::builder::ResponseBuilder;
 use crate::irc::responses::{InternalType, ResponseType};
+use crate::try_lock;
 
 #[derive(Debug)]
 pub struct Sv<'a> {
@@ -103,9 +104,13 @@ impl ServerExecutable for Sv<'_> {
 
     fn forward(&self, server: &Server, _: &MTServerConnection) -> String {
         let servername = generate_string(self.server_name);
+        let uplink = match self.prefix {
+            Some(v) => generate_string(v),
+            None => server.host.to_owned(),
+        };
         format!(
             ":{} SERVER {} {} :{}",
-            server.host,  // I'm the uplink for this server for all next servers
+            uplink,       // The uplink stays the same so everyone knows the real topology
             servername,   // The server that is new to the red
             self.hop + 1, // For the next ones, the new server is one more because they need me
             generate_string_from_vec(&self.address)
@@ -113,20 +118,40 @@ impl ServerExecutable for Sv<'_> {
     }
 
     fn execute_for_server(&self, server: &Server, origin: MTServerConnection) -> Vec<ResponseType> {
+        let servername = generate_string(self.server_name);
+        let link = { try_lock!(origin).servername.to_owned() };
+        if servername != link && server.is_known_server(&servername) {
+            // The server is already reachable through another path, the net is no longer a tree
+            println!(
+                "[SERVER] Loop detected: {} introduced by {}",
+                servername, link
+            );
+            let reason = format!("Server {} already exists", servername);
+            if link == DEFAULT_SERVERNAME {
+                // The link never registered, nobody else knows about it
+                server.drop_unregistered_link(&origin, &reason);
+            } else {
+                server.close_server_link(&link, &reason);
+            }
+            return ResponseBuilder::new()
+                .add_internal_response(InternalType::Quit)
+                .build();
+        }
+
         if self.hop == 1 {
             //Server that I just connected to, is giving me his name.
-            let real_servername = generate_string(self.server_name);
-            server.update_default_servername(real_servername);
+            server.update_default_servername(servername);
         } else {
             let uplink = match self.prefix {
                 Some(u) => Some(generate_string(u)),
                 None => return ResponseBuilder::new().build(),
                 //Any SERVER message executed here, has to have an uplink
             };
-            let servername = generate_string(self.server_name);
             server.add_data_server_connection(mt(ServerConnection::for_data(
                 servername, self.hop, uplink,
             )));
+            // A server joined the net behind the link, its clients come next
+            server.open_netjoin(&link);
 
             self.replicate(server, origin);
         }
@@ -0,0 +1,125 @@
+//! Modulo que se centra en las funcionalidades comunes a las consultas de informacion de un server.
+use crate::irc::constants::ERR_NOSUCHSERVER;
+use crate::irc::message::utils::generate_string;
+use crate::irc::message::Command;
+use crate::irc::model::server::Server;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+use crate::try_lock;
+
+use super::serializer::MessageSerializer;
+
+/// Consulta que responde un server, el local o el indicado como target.
+/// Las consultas a otros servers se encaminan hacia el y la respuesta vuelve con REPLY.
+pub trait ServerQuery {
+    fn prefix(&self) -> Option<&[u8]>;
+
+    fn target(&self) -> Option<&[u8]>;
+
+    fn command(&self) -> Command;
+
+    /// Respuestas que da este server a la consulta.
+    fn replies(&self, server: &Server) -> Vec<ResponseType>;
+
+    /// Respuestas que da este server cuando la consulta solo pasa por el hacia servername.
+    fn relay_replies(&self, _server: &Server, _servername: &str) -> Vec<ResponseType> {
+        vec![]
+    }
+
+    fn query_message(&self, nickname: &str, servername: &str) -> String {
+        MessageSerializer::new(Some(nickname.as_bytes()), self.command())
+            .add_parameter(servername.as_bytes())
+            .serialize()
+    }
+
+    /// Responde la consulta de un cliente local o la reenvia al server pedido.
+    fn query(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        let target = match self.target() {
+            Some(t) => generate_string(t),
+            None => return self.replies(server),
+        };
+
+        let servername = match server.resolve_target_server(&target) {
+            Some(s) => s,
+            None => {
+                return ResponseBuilder::new()
+                    .add_content_for_response(
+                        ERR_NOSUCHSERVER,
+                        format!("{} :No such server", target),
+                    )
+                    .build()
+            }
+        };
+
+        if servername == server.host {
+            return self.replies(server);
+        }
+
+        let nickname = { try_lock!(client).nickname.to_owned() };
+        match server.replicate_to_servername(
+            &self.query_message(&nickname, &servername),
+            &servername,
+            None,
+        ) {
+            Ok(_) => self.relay_replies(server, &servername),
+            Err(e) => ResponseBuilder::new()
+                .add_content_for_response(e.code, e.msg)
+                .build(),
+        }
+    }
+
+    /// Atiende una consulta que llego de otro server: la responde si es para este server
+    /// o la sigue encaminando hacia el target.
+    fn query_for_server(&self, server: &Server) {
+        let nickname = match self.prefix() {
+            Some(p) => generate_string(p),
+            None => return,
+        };
+
+        let target = match self.target() {
+            Some(t) => generate_string(t),
+            None => server.host.to_owned(),
+        };
+
+        let servername = match server.resolve_target_server(&target) {
+            Some(s) => s,
+            None => {
+                let error = ResponseBuilder::new()
+                    .add_content_for_response(
+                        ERR_NOSUCHSERVER,
+                        format!("{} :No such server", target),
+                    )
+                    .build();
+                send_responses(server, &nickname, error);
+                return;
+            }
+        };
+
+        if servername != server.host {
+            if server
+                .replicate_to_servername(
+                    &self.query_message(&nickname, &servername),
+                    &servername,
+                    None,
+                )
+                .is_ok()
+            {
+                send_responses(server, &nickname, self.relay_replies(server, &servername));
+            }
+            return;
+        }
+
+        send_responses(server, &nickname, self.replies(server));
+    }
+}
+
+fn send_responses(server: &Server, nickname: &str, responses: Vec<ResponseType>) {
+    let lines: Vec<String> = responses
+        .into_iter()
+        .filter_map(|r| r.serialize())
+        .collect();
+    if !lines.is_empty() {
+        server.send_lines_to_client(nickname, &lines);
+    }
+}
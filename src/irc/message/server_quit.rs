@@ -1,13 +1,13 @@
 //! Modulo que se centra en las funcionalidades referentes al mensaje de server quit.
 use super::serializer::MessageSerializer;
-use super::utils::generate_string;
+use super::utils::{generate_string, generate_string_from_vec};
 use super::{generic_message::GenericMessage, Command, FromGeneric, MessageError};
-use super::{Serializable, ServerExecutable};
-use crate::irc::constants::ERR_NEEDMOREPARAMS;
+use super::{Executable, Serializable, ServerExecutable};
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, ERR_NOPRIVILEGES};
 use crate::irc::message::utils::{validate_command, validate_irc_params_len, validate_text};
 use crate::irc::message::UNLIMITED_MAX_LEN;
 use crate::irc::model::server::Server;
-use crate::irc::model::MTServerConnection;
+use crate::irc::model::{MTClient, MTServerConnection};
 use crate::irc::responses::builder::ResponseBuilder;
 use crate::irc::responses::{InternalType, ResponseType};
 use crate::try_lock;
@@ -54,6 +54,44 @@ impl Serializable for ServerQuit<'_> {
     }
 }
 
+impl ServerQuit<'_> {
+    /// Devuelve el nickname del operador que pidio el SQUIT si el mensaje viene enrutado
+    /// desde otro server, o None si es el aviso de un link que ya se corto.
+    fn requester(&self, server: &Server) -> Option<String> {
+        let nickname = generate_string(self.prefix?);
+        let client = server.get_client_by_nickname(&nickname)?;
+        let is_oper = { try_lock!(client).server_operator };
+        if is_oper {
+            Some(nickname)
+        } else {
+            None
+        }
+    }
+}
+
+impl Executable for ServerQuit<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        let response = ResponseBuilder::new();
+        let (is_oper, nickname) = {
+            let c = try_lock!(client);
+            (c.server_operator, c.nickname.to_owned())
+        };
+        if !is_oper {
+            return response
+                .add_from_error(MessageError::IRCDefined(ERR_NOPRIVILEGES))
+                .build();
+        }
+
+        let servername = generate_string(self.server);
+        let comment = generate_string_from_vec(&self.message);
+        if let Err(e) = server.squit_server(&servername, &comment, &nickname) {
+            return response.add_content_for_response(e.code, e.msg).build();
+        }
+
+        response.build()
+    }
+}
+
 impl ServerExecutable for ServerQuit<'_> {
     fn _execute_for_server(&self, _: &Server) -> Vec<ResponseType> {
         // Implements for semantic purposes
@@ -63,6 +101,12 @@ impl ServerExecutable for ServerQuit<'_> {
     fn execute_for_server(&self, server: &Server, origin: MTServerConnection) -> Vec<ResponseType> {
         let mut response = ResponseBuilder::new();
         let servername = generate_string(self.server);
+        if let Some(requester) = self.requester(server) {
+            // An operator asked for this SQUIT somewhere else, it's travelling to the target
+            let comment = generate_string_from_vec(&self.message);
+            let _ = server.squit_server(&servername, &comment, &requester);
+            return response.build();
+        }
         {
             if try_lock!(origin).servername == servername {
                 response = response.add_internal_response(InternalType::Quit)
@@ -168,6 +212,27 @@ mod server_quit_parse_tests {
         assert_eq!(quit.server, b"a.b.c");
     }
 
+    #[test]
+    fn test_valid_mssg_with_oper_prefix() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_front(b":Bad link");
+        parameters.push_front(b"127.0.0.1:8081");
+        let generic = GenericMessage {
+            command: Command::ServerQuit,
+            prefix: Some(b"operator"),
+            parameters,
+        };
+
+        let quit = ServerQuit::from_generic(generic).unwrap();
+
+        assert_eq!(quit.prefix.unwrap(), b"operator");
+        assert_eq!(quit.server, b"127.0.0.1:8081");
+        assert_eq!(
+            quit.serialize(),
+            ":operator SQUIT 127.0.0.1:8081 :Bad link".to_owned()
+        );
+    }
+
     #[test]
     fn test_valid_mssg_null() {
         let parameters: VecDeque<&[u8]> = VecDeque::new();
@@ -0,0 +1,209 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de stats.
+use crate::irc::constants::{
+    ERR_NEEDMOREPARAMS, ERR_NOPRIVILEGES, RPL_ENDOFSTATS, RPL_STATSCOMMANDS, RPL_STATSKLINE,
+    RPL_STATSLINKINFO, RPL_STATSOLINE, RPL_STATSUPTIME,
+};
+use crate::irc::message::utils::{generate_string, validate_command, validate_irc_params_len};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError};
+use crate::irc::model::server::Server;
+use crate::irc::model::{MTClient, MTServerConnection};
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+use crate::try_lock;
+
+use super::serializer::MessageSerializer;
+use super::server_query::ServerQuery;
+use super::{Executable, Serializable, ServerExecutable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a stats, solo para operadores.
+/// La consulta es una letra: l links, m comandos, u uptime, o operadores, k K-lines y g G-lines.
+pub struct Stats<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub query: &'a [u8],
+    pub target: Option<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Stats<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Stats)?;
+        validate_irc_params_len(&generic.parameters, 2, 1, ERR_NEEDMOREPARAMS)?;
+
+        let query = match generic.parameters.pop_front() {
+            Some(q) if q.len() == 1 && q[0].is_ascii_alphabetic() => q,
+            _ => return Err(MessageError::InvalidFormat),
+        };
+
+        Ok(Self {
+            prefix: generic.prefix,
+            query,
+            target: generic.parameters.pop_front(),
+        })
+    }
+}
+
+impl Serializable for Stats<'_> {
+    fn serialize(&self) -> String {
+        let mut s = MessageSerializer::new(self.prefix, Command::Stats).add_parameter(self.query);
+
+        if let Some(v) = self.target {
+            s = s.add_parameter(v);
+        }
+
+        s.serialize()
+    }
+}
+
+impl ServerQuery for Stats<'_> {
+    fn prefix(&self) -> Option<&[u8]> {
+        self.prefix
+    }
+
+    fn target(&self) -> Option<&[u8]> {
+        self.target
+    }
+
+    fn command(&self) -> Command {
+        Command::Stats
+    }
+
+    fn query_message(&self, nickname: &str, servername: &str) -> String {
+        MessageSerializer::new(Some(nickname.as_bytes()), Command::Stats)
+            .add_parameter(self.query)
+            .add_parameter(servername.as_bytes())
+            .serialize()
+    }
+
+    fn replies(&self, server: &Server) -> Vec<ResponseType> {
+        let (code, lines) = match self.query[0] {
+            b'l' => (RPL_STATSLINKINFO, server.describe_link_stats()),
+            b'm' => (RPL_STATSCOMMANDS, server.describe_command_usage()),
+            b'u' => (
+                RPL_STATSUPTIME,
+                vec![format!(":{}", server.describe_uptime())],
+            ),
+            b'o' => (RPL_STATSOLINE, server.describe_oper_blocks()),
+            b'k' => (RPL_STATSKLINE, server.describe_server_bans(false)),
+            b'g' => (RPL_STATSKLINE, server.describe_server_bans(true)),
+            _ => (RPL_STATSKLINE, vec![]),
+        };
+
+        let mut response = ResponseBuilder::new();
+        for line in lines {
+            response = response.add_content_for_response(code, line);
+        }
+
+        response
+            .add_content_for_response(
+                RPL_ENDOFSTATS,
+                format!("{} :End of /STATS report", generate_string(self.query)),
+            )
+            .build()
+    }
+}
+
+impl Executable for Stats<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        if !try_lock!(client).server_operator {
+            return ResponseBuilder::new()
+                .add_from_error(MessageError::IRCDefined(ERR_NOPRIVILEGES))
+                .build();
+        }
+
+        self.query(server, client)
+    }
+}
+
+impl ServerExecutable for Stats<'_> {
+    fn _execute_for_server(&self, server: &Server) -> Vec<ResponseType> {
+        let requester = self
+            .prefix
+            .and_then(|p| server.get_client_by_nickname(&generate_string(p)));
+
+        if let Some(client) = requester {
+            if try_lock!(client).server_operator {
+                self.query_for_server(server);
+            }
+        }
+
+        ResponseBuilder::new().build()
+    }
+
+    fn execute_for_server(&self, server: &Server, _: MTServerConnection) -> Vec<ResponseType> {
+        self._execute_for_server(server)
+    }
+}
+
+#[cfg(test)]
+mod stats_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{IRCDefined, InvalidCommand, InvalidFormat};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_stats_with_different_command_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"l");
+
+        let generic = GenericMessage {
+            command: Command::Links,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Stats::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_stats_without_query_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Stats,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Stats::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, IRCDefined(ERR_NEEDMOREPARAMS));
+    }
+
+    #[test]
+    fn test_stats_with_long_query_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"links");
+
+        let generic = GenericMessage {
+            command: Command::Stats,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Stats::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidFormat);
+    }
+
+    #[test]
+    fn test_stats_with_target() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"m");
+        parameters.push_back(b"127.0.0.1:8080");
+
+        let generic = GenericMessage {
+            command: Command::Stats,
+            prefix: Some(b"oper"),
+            parameters,
+        };
+
+        let stats = Stats::from_generic(generic).unwrap();
+
+        assert_eq!(stats.query, b"m");
+        assert_eq!(stats.target.unwrap(), b"127.0.0.1:8080");
+        assert_eq!(stats.serialize(), ":oper STATS m 127.0.0.1:8080".to_owned());
+    }
+}
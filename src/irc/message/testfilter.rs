@@ -0,0 +1,156 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de testfilter.
+use crate::irc::constants::{
+    ERR_NEEDMOREPARAMS, ERR_NOPRIVILEGES, RPL_ENDOFFILTERMATCH, RPL_FILTERMATCH,
+};
+use crate::irc::message::utils::{
+    generate_string, generate_string_from_vec, validate_command, validate_irc_params_len,
+    validate_str_invalid_none, validate_text,
+};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError, UNLIMITED_MAX_LEN};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+use crate::try_lock;
+
+use super::serializer::MessageSerializer;
+use super::{Executable, Serializable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a testfilter, solo para operadores.
+/// Muestra que reglas de filtrado coinciden con un mensaje del origen (nick!user@host)
+/// al destino, sin aplicar ninguna accion.
+pub struct TestFilter<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub source: &'a [u8],
+    pub target: &'a [u8],
+    pub text: Vec<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for TestFilter<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::TestFilter)?;
+        validate_irc_params_len(
+            &generic.parameters,
+            UNLIMITED_MAX_LEN,
+            3,
+            ERR_NEEDMOREPARAMS,
+        )?;
+
+        let source = validate_str_invalid_none(generic.parameters.pop_front())?;
+        let target = validate_str_invalid_none(generic.parameters.pop_front())?;
+        let text = validate_text(generic.parameters)?;
+
+        Ok(Self {
+            prefix: generic.prefix,
+            source,
+            target,
+            text,
+        })
+    }
+}
+
+impl Serializable for TestFilter<'_> {
+    fn serialize(&self) -> String {
+        MessageSerializer::new(self.prefix, Command::TestFilter)
+            .add_parameter(self.source)
+            .add_parameter(self.target)
+            .add_trailing_params(&self.text)
+            .serialize()
+    }
+}
+
+impl Executable for TestFilter<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        if !try_lock!(client).server_operator {
+            return ResponseBuilder::new()
+                .add_from_error(MessageError::IRCDefined(ERR_NOPRIVILEGES))
+                .build();
+        }
+
+        let mut response = ResponseBuilder::new();
+        let matches = server.matching_filters(
+            &generate_string(self.source),
+            &generate_string(self.target),
+            &generate_string_from_vec(&self.text),
+        );
+
+        for (number, rule) in matches {
+            response = response.add_content_for_response(
+                RPL_FILTERMATCH,
+                format!("{} {}", number, rule.describe()),
+            );
+        }
+
+        response
+            .add_content_for_response(RPL_ENDOFFILTERMATCH, ":End of filter test".to_owned())
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod testfilter_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{IRCDefined, InvalidCommand};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_testfilter_with_different_command_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick!user@host");
+        parameters.push_back(b"#channel");
+        parameters.push_back(b":text");
+
+        let generic = GenericMessage {
+            command: Command::Wallops,
+            prefix: None,
+            parameters,
+        };
+
+        let err = TestFilter::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_testfilter_without_text_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick!user@host");
+        parameters.push_back(b"#channel");
+
+        let generic = GenericMessage {
+            command: Command::TestFilter,
+            prefix: None,
+            parameters,
+        };
+
+        let err = TestFilter::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, IRCDefined(ERR_NEEDMOREPARAMS));
+    }
+
+    #[test]
+    fn test_testfilter_valid_parameters_ok() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"*!*@*");
+        parameters.push_back(b"#channel");
+        parameters.push_back(b":buy");
+        parameters.push_back(b"cheap");
+
+        let generic = GenericMessage {
+            command: Command::TestFilter,
+            prefix: None,
+            parameters,
+        };
+
+        let test = TestFilter::from_generic(generic).unwrap();
+
+        assert_eq!(test.source, b"*!*@*");
+        assert_eq!(test.target, b"#channel");
+        assert_eq!(
+            test.serialize(),
+            "TESTFILTER *!*@* #channel :buy cheap".to_owned()
+        );
+    }
+}
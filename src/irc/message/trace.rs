@@ -0,0 +1,194 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de trace.
+use crate::irc::constants::{
+    ERR_NEEDMOREPARAMS, RPL_TRACEEND, RPL_TRACELINK, RPL_TRACEOPERATOR, RPL_TRACESERVER,
+    RPL_TRACEUSER, SERVER_VERSION,
+};
+use crate::irc::message::utils::{generate_string, validate_command, validate_irc_params_len};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError};
+use crate::irc::model::server::Server;
+use crate::irc::model::{MTClient, MTServerConnection};
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+
+use super::serializer::MessageSerializer;
+use super::server_query::ServerQuery;
+use super::{Executable, Serializable, ServerExecutable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a trace
+/// Contiene un target opcional, server o nickname, hasta el que se traza el camino.
+/// Cada server intermedio informa el link por el que sigue la consulta.
+pub struct Trace<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub target: Option<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Trace<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Trace)?;
+        validate_irc_params_len(&generic.parameters, 1, 0, ERR_NEEDMOREPARAMS)?;
+
+        Ok(Self {
+            prefix: generic.prefix,
+            target: generic.parameters.pop_front(),
+        })
+    }
+}
+
+impl Serializable for Trace<'_> {
+    fn serialize(&self) -> String {
+        let mut s = MessageSerializer::new(self.prefix, Command::Trace);
+
+        if let Some(v) = self.target {
+            s = s.add_parameter(v);
+        }
+
+        s.serialize()
+    }
+}
+
+impl ServerQuery for Trace<'_> {
+    fn prefix(&self) -> Option<&[u8]> {
+        self.prefix
+    }
+
+    fn target(&self) -> Option<&[u8]> {
+        self.target
+    }
+
+    fn command(&self) -> Command {
+        Command::Trace
+    }
+
+    fn query_message(&self, nickname: &str, servername: &str) -> String {
+        // The original target travels so a traced nickname is kept until its server
+        let target = self.target.unwrap_or(servername.as_bytes());
+        MessageSerializer::new(Some(nickname.as_bytes()), Command::Trace)
+            .add_parameter(target)
+            .serialize()
+    }
+
+    fn relay_replies(&self, server: &Server, servername: &str) -> Vec<ResponseType> {
+        let next = server
+            .next_hop(servername)
+            .unwrap_or_else(|| servername.to_owned());
+
+        ResponseBuilder::new()
+            .add_content_for_response(
+                RPL_TRACELINK,
+                format!("Link {} {} {}", SERVER_VERSION, servername, next),
+            )
+            .build()
+    }
+
+    fn replies(&self, server: &Server) -> Vec<ResponseType> {
+        let target = self.target.map(generate_string);
+        let mut clients = server.trace_clients(target.as_deref());
+        let traced_nick = !clients.is_empty() && target.is_some();
+        if target.is_some() && !traced_nick {
+            clients = server.trace_clients(None);
+        }
+
+        let mut response = ResponseBuilder::new();
+        for (nickname, oper) in clients {
+            response = if oper {
+                response.add_content_for_response(RPL_TRACEOPERATOR, format!("Oper 1 {}", nickname))
+            } else {
+                response.add_content_for_response(RPL_TRACEUSER, format!("User 1 {}", nickname))
+            };
+        }
+
+        if !traced_nick {
+            for (servername, servers, clients) in server.trace_servers() {
+                response = response.add_content_for_response(
+                    RPL_TRACESERVER,
+                    format!(
+                        "Serv 1 {}S {}C {} *!*@{}",
+                        servers, clients, servername, server.host
+                    ),
+                );
+            }
+        }
+
+        response
+            .add_content_for_response(
+                RPL_TRACEEND,
+                format!("{} {} :End of TRACE", server.host, SERVER_VERSION),
+            )
+            .build()
+    }
+}
+
+impl Executable for Trace<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        self.query(server, client)
+    }
+}
+
+impl ServerExecutable for Trace<'_> {
+    fn _execute_for_server(&self, server: &Server) -> Vec<ResponseType> {
+        self.query_for_server(server);
+        ResponseBuilder::new().build()
+    }
+
+    fn execute_for_server(&self, server: &Server, _: MTServerConnection) -> Vec<ResponseType> {
+        self._execute_for_server(server)
+    }
+}
+
+#[cfg(test)]
+mod trace_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{InvalidCommand, TooManyParams};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_trace_with_different_command_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Stats,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Trace::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_trace_with_too_many_params_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"127.0.0.1:8080");
+        parameters.push_back(b"other");
+
+        let generic = GenericMessage {
+            command: Command::Trace,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Trace::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, TooManyParams);
+    }
+
+    #[test]
+    fn test_trace_with_nickname_target() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick");
+
+        let generic = GenericMessage {
+            command: Command::Trace,
+            prefix: Some(b"oper"),
+            parameters,
+        };
+
+        let trace = Trace::from_generic(generic).unwrap();
+
+        assert_eq!(trace.target.unwrap(), b"nick");
+        assert_eq!(trace.serialize(), ":oper TRACE nick".to_owned());
+    }
+}
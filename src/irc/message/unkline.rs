@@ -0,0 +1,154 @@
+//! Modulo que se centra en las funcionalidades referentes a los mensajes de unkline y ungline.
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, ERR_NOPRIVILEGES};
+use crate::irc::message::utils::{
+    generate_string, validate_irc_params_len, validate_server_ban_mask,
+};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+use crate::try_lock;
+
+use super::serializer::MessageSerializer;
+use super::{Executable, Serializable, ServerExecutable};
+
+#[derive(Debug)]
+/// Struct de los mensajes referidos a unkline y ungline, levantan el ban de la mascara.
+pub struct Unkline<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub global: bool,
+    pub mask: &'a [u8],
+}
+
+impl<'a> FromGeneric<'a> for Unkline<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        let global = match generic.command {
+            Command::Unkline => false,
+            Command::Ungline => true,
+            _ => return Err(MessageError::InvalidCommand),
+        };
+        validate_irc_params_len(&generic.parameters, 1, 1, ERR_NEEDMOREPARAMS)?;
+
+        let mask = validate_server_ban_mask(generic.parameters.pop_front())?;
+
+        Ok(Self {
+            prefix: generic.prefix,
+            global,
+            mask,
+        })
+    }
+}
+
+impl Serializable for Unkline<'_> {
+    fn serialize(&self) -> String {
+        let command = if self.global {
+            Command::Ungline
+        } else {
+            Command::Unkline
+        };
+
+        MessageSerializer::new(self.prefix, command)
+            .add_parameter(self.mask)
+            .serialize()
+    }
+}
+
+impl Executable for Unkline<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        let (nickname, is_oper) = {
+            let c = try_lock!(client);
+            (c.nickname.to_owned(), c.server_operator)
+        };
+
+        if !is_oper {
+            return ResponseBuilder::new()
+                .add_from_error(MessageError::IRCDefined(ERR_NOPRIVILEGES))
+                .build();
+        }
+
+        let mask = generate_string(self.mask);
+        let kind = if self.global { "G" } else { "K" };
+
+        let notice = if server.remove_server_ban(&mask, self.global) {
+            if self.global {
+                server.replicate_to_all_servers(&format!(":{} UNGLINE {}", nickname, mask));
+            }
+            format!("Removed {}-Line for [{}]", kind, mask)
+        } else {
+            format!("No {}-Line for [{}]", kind, mask)
+        };
+
+        server.send_message_to_local_client(
+            &mut *try_lock!(client),
+            &format!(":{} NOTICE {} :{}", server.host, nickname, notice),
+        );
+        ResponseBuilder::new().build()
+    }
+}
+
+impl ServerExecutable for Unkline<'_> {
+    fn _execute_for_server(&self, server: &Server) -> Vec<ResponseType> {
+        if self.global {
+            server.remove_server_ban(&generate_string(self.mask), true);
+        }
+
+        ResponseBuilder::new().build()
+    }
+}
+
+#[cfg(test)]
+mod unkline_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{IRCDefined, InvalidCommand};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_unkline_with_different_command_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"*@127.0.0.1");
+
+        let generic = GenericMessage {
+            command: Command::Kline,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Unkline::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_unkline_without_mask_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Unkline,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Unkline::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, IRCDefined(ERR_NEEDMOREPARAMS));
+    }
+
+    #[test]
+    fn test_ungline_with_prefix() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"*@127.0.0.1");
+
+        let generic = GenericMessage {
+            command: Command::Ungline,
+            prefix: Some(b"oper"),
+            parameters,
+        };
+
+        let ungline = Unkline::from_generic(generic).unwrap();
+
+        assert!(ungline.global);
+        assert_eq!(ungline.serialize(), ":oper UNGLINE *@127.0.0.1".to_owned());
+    }
+}
@@ -1,12 +1,16 @@
 //! Modulo que se centra en las funcionalidades referentes al mensaje de user.
 
-use crate::irc::constants::{ERR_ALREADYREGISTRED, ERR_NEEDMOREPARAMS, RPL_NICKIN, RPL_SUCLOGIN};
+use crate::irc::constants::{
+    ERR_ALREADYREGISTRED, ERR_NEEDMOREPARAMS, ERR_YOUREBANNEDCREEP, RPL_ISUPPORT, RPL_NICKIN,
+    RPL_SUCLOGIN,
+};
 use crate::irc::message::utils::{validate_command, validate_irc_params_len};
 use crate::irc::model::connection::Connection;
 use crate::irc::model::server::Server;
-use crate::irc::model::MTClient;
+use crate::irc::model::{MTClient, MTServerConnection};
 use crate::irc::responses::builder::ResponseBuilder;
 use crate::irc::responses::{InternalType, ResponseType};
+use crate::try_lock;
 
 use super::serializer::MessageSerializer;
 use super::utils::{generate_string, generate_string_from_vec};
@@ -86,10 +90,22 @@ impl User<'_> {
         let servername = generate_string(server.host.as_bytes());
         let realname = generate_string_from_vec(&self.realname);
 
+        if let Some(ban) = server.find_server_ban(&username, connection.address.as_ref()) {
+            connection.quit();
+            return response
+                .add_content_for_response(
+                    ERR_YOUREBANNEDCREEP,
+                    format!("You are banned from this server- {}", ban.reason),
+                )
+                .add_internal_response(InternalType::Quit)
+                .build();
+        }
+
         if server.conn_can_log_in(connection, &username) {
             response = response
                 .add_internal_response(InternalType::Upgrade)
-                .add_content_for_response(RPL_SUCLOGIN, "Succesfull login".to_owned());
+                .add_content_for_response(RPL_SUCLOGIN, "Succesfull login".to_owned())
+                .add_content_for_response(RPL_ISUPPORT, server.isupport());
 
             self.notify(server, connection.get_nickname().unwrap()); //Will always be correct because conn can log in is cheking it
             connection.set_client_connection(username, hostname, servername, realname);
@@ -104,7 +120,7 @@ impl User<'_> {
 
 impl User<'_> {
     fn notify(&self, server: &Server, nickname: &str) {
-        server.server_action_notify(&format!("{}: {}", RPL_NICKIN, nickname))
+        server.server_action_notify_for(nickname, &format!("{}: {}", RPL_NICKIN, nickname))
     }
 }
 
@@ -123,6 +139,17 @@ impl ServerExecutable for User<'_> {
         }
         ResponseBuilder::new().build()
     }
+
+    fn execute_for_server(&self, server: &Server, origin: MTServerConnection) -> Vec<ResponseType> {
+        if let Some(v) = self.prefix {
+            // If the link is still sending its burst, the client is part of a netjoin
+            let link = { try_lock!(origin).servername.to_owned() };
+            server.add_to_netjoin(&link, &generate_string(v));
+        }
+        let res = self._execute_for_server(server);
+        self.replicate(server, origin);
+        res
+    }
 }
 
 #[cfg(test)]
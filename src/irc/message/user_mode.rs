@@ -65,6 +65,7 @@ impl UserMode<'_> {
                 UserModes::Invisible => server.set_client_invisible(client, to),
                 UserModes::ReceiveServerNotices => server.set_client_receive_sv_notices(client, to),
                 UserModes::IRCOperator => server.set_client_sv_operator(client, to),
+                UserModes::Wallops => server.set_client_wallops(client, to),
             }
         }
     }
@@ -105,6 +106,7 @@ impl UserModes {
             b'i' => Ok(UserModes::Invisible),
             b's' => Ok(UserModes::ReceiveServerNotices),
             b'o' => Ok(UserModes::IRCOperator),
+            b'w' => Ok(UserModes::Wallops),
             _ => Err(MessageError::IRCDefined(ERR_UNKNOWNMODE)),
         }
     }
@@ -114,6 +116,7 @@ impl UserModes {
             UserModes::Invisible => b'i',
             UserModes::ReceiveServerNotices => b's',
             UserModes::IRCOperator => b'o',
+            UserModes::Wallops => b'w',
         }
     }
 }
@@ -0,0 +1,128 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de userhost.
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, RPL_USERHOST, USERHOST_MAX_TARGETS};
+use crate::irc::message::utils::{validate_command, validate_irc_params_len};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+
+use super::serializer::MessageSerializer;
+use super::utils::{generate_string, split_trailing_list, starts_with_colon};
+use super::{Executable, Serializable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a userhost.
+/// Contiene hasta cinco nicknames de los que se pide usuario y host.
+pub struct Userhost<'a> {
+    pub nicknames: Vec<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Userhost<'a> {
+    fn from_generic(generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Userhost)?;
+        validate_irc_params_len(
+            &generic.parameters,
+            USERHOST_MAX_TARGETS,
+            1,
+            ERR_NEEDMOREPARAMS,
+        )?;
+
+        // Nicknames never start with a colon, they go as plain parameters when serialized
+        let nicknames = split_trailing_list(generic.parameters)
+            .into_iter()
+            .filter(|n| !starts_with_colon(n))
+            .collect::<Vec<_>>();
+        if nicknames.is_empty() {
+            return Err(MessageError::IRCDefined(ERR_NEEDMOREPARAMS));
+        }
+
+        Ok(Self { nicknames })
+    }
+}
+
+impl Serializable for Userhost<'_> {
+    fn serialize(&self) -> String {
+        let mut s = MessageSerializer::new(None, Command::Userhost);
+
+        for nickname in &self.nicknames {
+            s = s.add_parameter(nickname);
+        }
+
+        s.serialize()
+    }
+}
+
+impl Executable for Userhost<'_> {
+    fn _execute(&self, server: &Server, _client: MTClient) -> Vec<ResponseType> {
+        let replies: Vec<String> = self
+            .nicknames
+            .iter()
+            .filter_map(|n| server.describe_userhost(&generate_string(n)))
+            .collect();
+
+        ResponseBuilder::new()
+            .add_content_for_response(RPL_USERHOST, format!(":{}", replies.join(" ")))
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod userhost_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{InvalidCommand, TooManyParams};
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test_userhost_with_different_command_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick");
+
+        let generic = GenericMessage {
+            command: Command::Ison,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Userhost::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_userhost_with_too_many_nicknames_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        for nick in [b"a", b"b", b"c", b"d", b"e", b"f"] {
+            parameters.push_back(nick);
+        }
+
+        let generic = GenericMessage {
+            command: Command::Userhost,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Userhost::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, TooManyParams);
+    }
+
+    #[test]
+    fn test_userhost_with_nicknames() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"alice");
+        parameters.push_back(b"bob");
+
+        let generic = GenericMessage {
+            command: Command::Userhost,
+            prefix: None,
+            parameters,
+        };
+
+        let userhost = Userhost::from_generic(generic).unwrap();
+
+        assert_eq!(userhost.nicknames, vec![b"alice".as_ref(), b"bob"]);
+        assert_eq!(userhost.serialize(), "USERHOST alice bob".to_owned());
+    }
+}
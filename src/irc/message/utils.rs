@@ -1,6 +1,10 @@
 //! Modulo que se centra en las funcionalidades genericas necesarias para resolver
 //! los distintos modulos
-use std::{collections::vec_deque::VecDeque, net::SocketAddr, str::FromStr};
+use std::{
+    collections::vec_deque::VecDeque,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
 
 use super::{
     ChannelModes, ModesAction, UserModes, AMPERSAND, ASTERISK, BELL, COMMA, DOLLAR, HASH,
@@ -109,11 +113,10 @@ pub fn validate_str_starting_w_colon(n: Option<&[u8]>) -> Result<&[u8], MessageE
         None => return Err(InvalidFormat),
     };
 
-    if name[0] != COLON {
-        return Err(InvalidFormat);
+    match strip_colon(name) {
+        Some(v) => validate_str_invalid_none(Some(v)),
+        None => Err(InvalidFormat),
     }
-
-    validate_str_invalid_none(Some(&name[1..name.len()]))
 }
 
 pub fn validate_realname(mut params: VecDeque<&[u8]>) -> Result<Vec<&[u8]>, MessageError> {
@@ -373,6 +376,23 @@ pub fn validate_text(mut params: VecDeque<&[u8]>) -> Result<Vec<&[u8]>, MessageE
     Ok(Vec::from(params))
 }
 
+/// Lista que puede venir toda o en parte como trailing. Solo el primer parametro del trailing
+/// trae los dos puntos, los siguientes se toman como vienen. Descarta los vacios.
+pub fn split_trailing_list(params: VecDeque<&[u8]>) -> Vec<&[u8]> {
+    let mut in_trailing = false;
+    params
+        .into_iter()
+        .map(|p| match strip_colon(p) {
+            Some(v) if !in_trailing => {
+                in_trailing = true;
+                v
+            }
+            _ => p,
+        })
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
 fn is_valid_chstring_byte(ch: &u8) -> bool {
     !(*ch == SPACE || *ch == BELL || *ch == NUL || *ch == CR || *ch == LF || *ch == COMMA)
 }
@@ -419,18 +439,13 @@ pub fn split_csl_none(keys: Option<&[u8]>) -> Option<Vec<&[u8]>> {
     Some(_csl)
 }
 
-pub fn validate_realname_valid_none(n: Option<&[u8]>) -> Result<Option<&[u8]>, MessageError> {
-    //TODO refactor
-    let name = match n {
-        Some(v) => v,
-        None => return Ok(None),
-    };
-
-    if name[0] != COLON {
-        return Err(InvalidFormat);
+/// Valida el nickname usado como prefijo. El parseo ya le quita los dos puntos
+/// iniciales, pero tambien se aceptan si el mensaje se armo a mano.
+pub fn validate_prefix(n: Option<&[u8]>) -> Result<Option<&[u8]>, MessageError> {
+    match n {
+        Some(v) => validate_name_valid_none(Some(strip_colon(v).unwrap_or(v))),
+        None => Ok(None),
     }
-
-    validate_name_valid_none(Some(&name[1..name.len()]))
 }
 
 pub fn validate_nickmask(nickmask: Option<&[u8]>) -> Result<&[u8], MessageError> {
@@ -499,20 +514,184 @@ pub fn validate_user_modes(
     }
 }
 
-pub fn no_such_nick(nick: &[u8]) -> String {
-    format!("{} :No such nick/channel", generate_string(nick))
+/// Pasa el byte a minuscula con el casemapping rfc1459: ademas de las letras,
+/// []\\~ son las mayusculas de {}|^.
+pub fn rfc1459_lowercase(b: u8) -> u8 {
+    match b {
+        b'[' => b'{',
+        b']' => b'}',
+        b'\\' => b'|',
+        b'~' => b'^',
+        _ => b.to_ascii_lowercase(),
+    }
 }
 
-fn top(n: &[u8]) -> usize {
-    match n.len() {
-        0 => 0,
-        i => i - 1,
+/// Forma canonica de un nickname o canal para compararlos sin importar mayusculas.
+pub fn casefold(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii() {
+                rfc1459_lowercase(c as u8) as char
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Compara un texto contra una mascara con comodines, '*' matchea cualquier secuencia
+/// (incluso vacia) y '?' un unico caracter. No distingue mayusculas de minusculas (rfc1459).
+pub fn mask_matches(mask: &str, text: &str) -> bool {
+    let mask = mask.as_bytes();
+    let text = text.as_bytes();
+    let (mut m, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if m < mask.len() && mask[m] == ASTERISK {
+            backtrack = Some((m, t));
+            m += 1;
+        } else if m < mask.len()
+            && (mask[m] == QUESTION_MARK
+                || rfc1459_lowercase(mask[m]) == rfc1459_lowercase(text[t]))
+        {
+            m += 1;
+            t += 1;
+        } else if let Some((star, matched)) = backtrack {
+            m = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
     }
+
+    mask[m..].iter().all(|c| *c == ASTERISK)
+}
+
+/// Valida una mascara de ban (+b, +e, +I). La mascara es opcional, sin ella se pide el listado.
+pub fn validate_ban_mask(n: Option<&[u8]>) -> Result<Option<&[u8]>, MessageError> {
+    match n {
+        Some(v) => {
+            if v.is_empty() || !v.iter().all(nonwhite) || v.contains(&COMMA) || v.contains(&b';') {
+                return Err(InvalidFormat);
+            }
+            Ok(Some(v))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Valida una mascara de las listas de un canal (+b, +e, +I, +q). Ademas de las mascaras
+/// comunes acepta los bans extendidos `$a`, `$a:cuenta` y `$r:realname`, negados con `$~`.
+pub fn validate_channel_mask(n: Option<&[u8]>) -> Result<Option<&[u8]>, MessageError> {
+    let mask = match validate_ban_mask(n)? {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+
+    let extban = match mask.strip_prefix(b"$~").or_else(|| mask.strip_prefix(b"$")) {
+        Some(e) => e,
+        None => return Ok(Some(mask)),
+    };
+
+    match extban {
+        b"a" => Ok(Some(mask)),
+        [b'a' | b'r', b':', pattern @ ..] if !pattern.is_empty() => Ok(Some(mask)),
+        _ => Err(InvalidFormat),
+    }
+}
+
+/// Completa una mascara de ban a la forma `nick!user@host`. Los bans extendidos quedan igual.
+pub fn normalize_ban_mask(mask: &str) -> String {
+    if mask.starts_with('$') {
+        return mask.to_owned();
+    }
+
+    match (mask.contains('!'), mask.contains('@')) {
+        (true, true) => mask.to_owned(),
+        (true, false) => format!("{}@*", mask),
+        (false, true) => format!("*!{}", mask),
+        (false, false) => format!("{}!*@*", mask),
+    }
+}
+
+/// Valida la mascara `user@host` de un ban a nivel server. El host es una mascara de IP o un
+/// rango CIDR: el hostname lo elige el cliente, asi que no sirve para banearlo.
+pub fn validate_server_ban_mask(n: Option<&[u8]>) -> Result<&[u8], MessageError> {
+    let mask = match validate_ban_mask(n)? {
+        Some(v) => v,
+        None => return Err(IRCDefined(ERR_NEEDMOREPARAMS)),
+    };
+
+    let mask_str = generate_string(mask);
+    let (user, host) = match mask_str.split_once('@') {
+        Some(v) => v,
+        None => return Err(InvalidFormat),
+    };
+
+    if user.is_empty() || host.is_empty() || host.contains('@') {
+        return Err(InvalidFormat);
+    }
+
+    let valid_host = match host.contains('/') {
+        true => parse_cidr(host).is_some(),
+        false => is_ip_mask(host),
+    };
+    if !valid_host {
+        return Err(InvalidFormat);
+    }
+
+    Ok(mask)
+}
+
+/// Indica si la mascara solo tiene caracteres de una direccion IPv4 o IPv6 y comodines.
+fn is_ip_mask(host: &str) -> bool {
+    let ipv6 = host.contains(':');
+    host.chars()
+        .all(|c| matches!(c, '*' | '?' | '.' | ':' | '0'..='9') || (ipv6 && c.is_ascii_hexdigit()))
+}
+
+fn parse_cidr(cidr: &str) -> Option<(IpAddr, u32)> {
+    let (address, bits) = cidr.split_once('/')?;
+    let address = IpAddr::from_str(address).ok()?;
+    let bits = bits.parse::<u32>().ok()?;
+    let max = match address {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    };
+
+    (bits <= max).then_some((address, bits))
+}
+
+/// Indica si la direccion pertenece al rango CIDR (`10.0.0.0/8`).
+pub fn cidr_matches(cidr: &str, address: &IpAddr) -> bool {
+    let (network, bits) = match parse_cidr(cidr) {
+        Some(v) => v,
+        None => return false,
+    };
+
+    match (network, address) {
+        (IpAddr::V4(n), IpAddr::V4(a)) => {
+            let mask = u32::MAX.checked_shl(32 - bits).unwrap_or(0);
+            u32::from(n) & mask == u32::from(*a) & mask
+        }
+        (IpAddr::V6(n), IpAddr::V6(a)) => {
+            let mask = u128::MAX.checked_shl(128 - bits).unwrap_or(0);
+            u128::from(n) & mask == u128::from(*a) & mask
+        }
+        _ => false,
+    }
+}
+
+pub fn no_such_nick(nick: &[u8]) -> String {
+    format!("{} :No such nick/channel", generate_string(nick))
 }
 
 pub fn retrieve_hostname(n: &[u8]) -> Result<Option<&[u8]>, MessageError> {
     {
-        if n[0] == BRACKET_OPEN && n[top(n)] == BRACKET_CLOSE {
+        // The address may be empty, as in "SERVER name 1 : info"
+        if n.first() == Some(&BRACKET_OPEN) && n.last() == Some(&BRACKET_CLOSE) {
             let a = match n.strip_prefix(&[BRACKET_OPEN]) {
                 Some(v) => v,
                 None => return Err(MessageError::IRCDefined(ERR_NEEDMOREPARAMS)),
@@ -1162,6 +1341,34 @@ mod test {
         }
     }
 
+    mod test_split_trailing_list {
+        use std::collections::vec_deque::VecDeque;
+
+        use crate::irc::message::utils::split_trailing_list;
+
+        #[test]
+        fn test_only_the_trailing_start_loses_the_colon() {
+            let mut params: VecDeque<&[u8]> = VecDeque::new();
+            params.push_back(b"LS");
+            params.push_back(b"::batch");
+            params.push_back(b":echo");
+            params.push_back(b":");
+
+            let res = split_trailing_list(params);
+
+            assert_eq!(res, vec![b"LS".as_ref(), b":batch", b":echo", b":"]);
+        }
+
+        #[test]
+        fn test_empty_trailing_is_dropped() {
+            let mut params: VecDeque<&[u8]> = VecDeque::new();
+            params.push_back(b"alice");
+            params.push_back(b":");
+
+            assert_eq!(split_trailing_list(params), vec![b"alice".as_ref()]);
+        }
+    }
+
     mod test_validate_channel {
         use crate::irc::message::{utils::validate_channel, MessageError, BELL, HASH};
 
@@ -1240,65 +1447,156 @@ mod test {
             assert_eq!(splitted, None)
         }
     }
-    mod test_valid_realname_valid_none {
-        use crate::irc::message::utils::validate_realname_valid_none;
-        use crate::irc::message::MessageError::InvalidFormat;
+    mod test_validate_prefix {
+        use crate::irc::message::utils::validate_prefix;
 
         #[test]
-        fn test_valid_realname_without_space_is_valid() {
-            let valid: &[u8] = b":valid";
-            let valid_res: &[u8] = b"valid";
+        fn test_prefix_with_and_without_colon() {
+            assert_eq!(validate_prefix(Some(b"nick")).unwrap().unwrap(), b"nick");
+            assert_eq!(validate_prefix(Some(b":nick")).unwrap().unwrap(), b"nick");
+            assert!(validate_prefix(None).unwrap().is_none());
+        }
+    }
+    mod test_mask_matches {
+        use crate::irc::message::utils::mask_matches;
 
-            assert_eq!(
-                validate_realname_valid_none(Some(valid)),
-                Ok(Some(valid_res))
-            );
+        #[test]
+        fn test_exact_mask_matches() {
+            assert!(mask_matches("nick", "nick"));
+            assert!(mask_matches("NiCk", "nick"));
+            assert!(!mask_matches("nick", "nickname"));
         }
 
         #[test]
-        fn test_valid_realname_with_space_is_invalid() {
-            let invalid: &[u8] = b":another valid";
+        fn test_wildcard_mask_matches() {
+            assert!(mask_matches("*", ""));
+            assert!(mask_matches("*", "anything"));
+            assert!(mask_matches("nick*", "nickname"));
+            assert!(mask_matches("*!*@127.0.0.*", "nick!user@127.0.0.1"));
+            assert!(mask_matches("n?ck", "nick"));
+            assert!(mask_matches("*a*b*c", "xxaxxbxxc"));
+        }
 
-            assert_eq!(
-                validate_realname_valid_none(Some(invalid)),
-                Err(InvalidFormat)
-            );
+        #[test]
+        fn test_wildcard_mask_does_not_match() {
+            assert!(!mask_matches("n?ck", "nck"));
+            assert!(!mask_matches("*!*@10.*", "nick!user@127.0.0.1"));
+            assert!(!mask_matches("*a*b*c", "xxaxxbxxcx"));
+            assert!(!mask_matches("", "a"));
         }
 
         #[test]
-        fn test_valid_realname_without_colon_is_invalid() {
-            let invalid: &[u8] = b"invalid";
+        fn test_mask_matches_with_rfc1459_casemapping() {
+            assert!(mask_matches("nick[a]*", "NICK{A}\\x"));
+            assert!(mask_matches("a\\b~", "A|B^"));
+            assert!(!mask_matches("a{", "a("));
+        }
+    }
 
-            assert_eq!(
-                validate_realname_valid_none(Some(invalid)),
-                Err(InvalidFormat)
-            );
+    mod test_casefold {
+        use crate::irc::message::utils::casefold;
+
+        #[test]
+        fn test_casefold_letters_and_specials() {
+            assert_eq!(casefold("Valen[]\\~"), "valen{}|^");
+            assert_eq!(casefold("#Rust"), "#rust");
         }
+    }
+
+    mod test_ban_mask {
+        use crate::irc::message::utils::{
+            normalize_ban_mask, validate_ban_mask, validate_channel_mask,
+        };
+        use crate::irc::message::MessageError::InvalidFormat;
 
         #[test]
-        fn test_valid_realname_none_is_valid() {
-            assert_eq!(validate_realname_valid_none(None), Ok(None));
+        fn test_normalize_ban_mask() {
+            assert_eq!(normalize_ban_mask("nick"), "nick!*@*");
+            assert_eq!(normalize_ban_mask("user@host"), "*!user@host");
+            assert_eq!(normalize_ban_mask("nick!user"), "nick!user@*");
+            assert_eq!(normalize_ban_mask("*!*@127.0.0.*"), "*!*@127.0.0.*");
+            assert_eq!(normalize_ban_mask("$~a"), "$~a");
         }
 
         #[test]
-        fn test_invalid_realname_without_ascii_characters() {
-            let invalid_one: &[u8] = b":1invalid";
-            let invalid_two: &[u8] = b":@invalid";
-            let invalid_three: &[u8] = b":#invalid";
+        fn test_validate_extended_ban_mask() {
+            let valid: [&[u8]; 4] = [b"$a", b"$~a", b"$a:valen*", b"$~r:*bot*"];
+            let invalid: [&[u8]; 4] = [b"$r", b"$a:", b"$x:nick", b"$"];
 
+            for mask in valid {
+                assert_eq!(validate_channel_mask(Some(mask)).unwrap(), Some(mask));
+            }
+            for mask in invalid {
+                assert_eq!(
+                    validate_channel_mask(Some(mask)).unwrap_err(),
+                    InvalidFormat
+                );
+            }
+        }
+
+        #[test]
+        fn test_validate_ban_mask() {
+            let valid: &[u8] = b"*!*@127.0.0.*";
+            let invalid: &[u8] = b"nick,other";
+
+            assert_eq!(validate_ban_mask(Some(valid)).unwrap(), Some(valid));
+            assert_eq!(validate_ban_mask(None).unwrap(), None);
+            assert_eq!(validate_ban_mask(Some(invalid)).unwrap_err(), InvalidFormat);
+        }
+    }
+    mod test_server_ban_mask {
+        use std::net::IpAddr;
+        use std::str::FromStr;
+
+        use crate::irc::message::utils::{cidr_matches, validate_server_ban_mask};
+        use crate::irc::message::MessageError::InvalidFormat;
+
+        #[test]
+        fn test_validate_server_ban_mask() {
+            let valid: &[u8] = b"*@10.0.0.0/8";
+            let without_user: &[u8] = b"127.0.0.1";
+            let invalid_cidr: &[u8] = b"user@10.0.0.0/40";
+
+            assert_eq!(validate_server_ban_mask(Some(valid)).unwrap(), valid);
             assert_eq!(
-                validate_realname_valid_none(Some(invalid_one)),
-                Err(InvalidFormat)
-            );
-            assert_eq!(
-                validate_realname_valid_none(Some(invalid_two)),
-                Err(InvalidFormat)
+                validate_server_ban_mask(Some(without_user)).unwrap_err(),
+                InvalidFormat
             );
             assert_eq!(
-                validate_realname_valid_none(Some(invalid_three)),
-                Err(InvalidFormat)
+                validate_server_ban_mask(Some(invalid_cidr)).unwrap_err(),
+                InvalidFormat
             );
         }
+
+        #[test]
+        fn test_server_ban_mask_host_is_an_ip_mask() {
+            let masks: [&[u8]; 4] = [b"*@192.168.*", b"guest@10.0.0.?", b"*@2001:db8::*", b"*@*"];
+            for mask in masks {
+                assert_eq!(validate_server_ban_mask(Some(mask)).unwrap(), mask);
+            }
+        }
+
+        #[test]
+        fn test_server_ban_mask_with_hostname_error() {
+            let masks: [&[u8]; 3] = [b"*@*.evil.example", b"user@localhost", b"*@cafe"];
+            for mask in masks {
+                assert_eq!(
+                    validate_server_ban_mask(Some(mask)).unwrap_err(),
+                    InvalidFormat
+                );
+            }
+        }
+
+        #[test]
+        fn test_cidr_matches() {
+            let address = IpAddr::from_str("10.1.2.3").unwrap();
+
+            assert!(cidr_matches("10.0.0.0/8", &address));
+            assert!(cidr_matches("10.1.2.3/32", &address));
+            assert!(cidr_matches("0.0.0.0/0", &address));
+            assert!(!cidr_matches("10.1.3.0/24", &address));
+            assert!(!cidr_matches("::/0", &address));
+        }
     }
     mod test_validate_o_param {
         use crate::irc::message::utils::validate_o_param;
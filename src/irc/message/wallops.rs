@@ -0,0 +1,134 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de wallops.
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, ERR_NOPRIVILEGES};
+use crate::irc::message::utils::{validate_command, validate_irc_params_len, validate_text};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError, UNLIMITED_MAX_LEN};
+use crate::irc::model::server::Server;
+use crate::irc::model::MTClient;
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+use crate::try_lock;
+
+use super::serializer::MessageSerializer;
+use super::{Executable, Serializable, ServerExecutable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a wallops, solo para operadores.
+/// Llega a todos los clientes de la red con el modo +w.
+pub struct Wallops<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub text: Vec<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Wallops<'a> {
+    fn from_generic(generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Wallops)?;
+        validate_irc_params_len(
+            &generic.parameters,
+            UNLIMITED_MAX_LEN,
+            1,
+            ERR_NEEDMOREPARAMS,
+        )?;
+
+        Ok(Self {
+            prefix: generic.prefix,
+            text: validate_text(generic.parameters)?,
+        })
+    }
+}
+
+impl Serializable for Wallops<'_> {
+    fn serialize(&self) -> String {
+        MessageSerializer::new(self.prefix, Command::Wallops)
+            .add_trailing_params(&self.text)
+            .serialize()
+    }
+}
+
+impl Executable for Wallops<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        let (nickname, is_oper) = {
+            let c = try_lock!(client);
+            (c.nickname.to_owned(), c.server_operator)
+        };
+
+        if !is_oper {
+            return ResponseBuilder::new()
+                .add_from_error(MessageError::IRCDefined(ERR_NOPRIVILEGES))
+                .build();
+        }
+
+        let message = MessageSerializer::new(Some(nickname.as_bytes()), Command::Wallops)
+            .add_trailing_params(&self.text)
+            .serialize();
+        server.send_wallops(&message);
+        server.replicate_to_all_servers(&message);
+
+        ResponseBuilder::new().build()
+    }
+}
+
+impl ServerExecutable for Wallops<'_> {
+    fn _execute_for_server(&self, server: &Server) -> Vec<ResponseType> {
+        server.send_wallops(&self.serialize());
+        ResponseBuilder::new().build()
+    }
+}
+
+#[cfg(test)]
+mod wallops_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{IRCDefined, InvalidCommand};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_wallops_with_different_command_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b":hello");
+
+        let generic = GenericMessage {
+            command: Command::Notice,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Wallops::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_wallops_without_text_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Wallops,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Wallops::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, IRCDefined(ERR_NEEDMOREPARAMS));
+    }
+
+    #[test]
+    fn test_wallops_with_prefix() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b":maintenance");
+        parameters.push_back(b"tonight");
+
+        let generic = GenericMessage {
+            command: Command::Wallops,
+            prefix: Some(b"oper"),
+            parameters,
+        };
+
+        let wallops = Wallops::from_generic(generic).unwrap();
+
+        assert_eq!(
+            wallops.serialize(),
+            ":oper WALLOPS :maintenance tonight".to_owned()
+        );
+    }
+}
@@ -1,10 +1,10 @@
 //! Modulo que se centra en las funcionalidades referentes al mensaje de who.
-use crate::irc::constants::{ERR_NEEDMOREPARAMS, RPL_ENDOFWHO, RPL_WHOREPLY};
-use crate::irc::message::utils::{validate_command, validate_irc_params_len};
+use crate::irc::constants::{ERR_NEEDMOREPARAMS, RPL_ENDOFWHO, RPL_WHOREPLY, RPL_WHOSPCRPL};
+use crate::irc::message::utils::{generate_string, validate_command, validate_irc_params_len};
 use crate::irc::message::GenericMessage;
 use crate::irc::message::MessageError::InvalidFormat;
 use crate::irc::message::{Command, FromGeneric, MessageError};
-use crate::irc::model::server::Server;
+use crate::irc::model::server::{Server, WhoInfo};
 use crate::irc::model::MTClient;
 use crate::irc::responses::builder::ResponseBuilder;
 use crate::irc::responses::ResponseType;
@@ -13,10 +13,72 @@ use super::serializer::MessageSerializer;
 use super::utils::validate_o_param;
 use super::{Executable, Serializable};
 
+/// Campos de WHOX en el orden en que se responden.
+const WHOX_FIELDS: &[u8] = b"tcuihsnfdlaor";
+const WHOX_MAX_TOKEN_LEN: usize = 3;
+const PERCENT: u8 = b'%';
+
+#[derive(Debug)]
+/// Pedido de campos de la extension WHOX: `%<campos>[,<token>]`.
+pub struct Whox<'a> {
+    pub fields: &'a [u8],
+    pub token: Option<&'a [u8]>,
+}
+
 #[derive(Debug)]
 pub struct Who<'a> {
     pub nick: Option<&'a [u8]>,
     pub o: bool,
+    pub whox: Option<Whox<'a>>,
+}
+
+impl<'a> Whox<'a> {
+    fn parse(param: &'a [u8]) -> Result<Self, MessageError> {
+        let mut split = param.splitn(2, |c| *c == b',');
+        let fields = split.next().unwrap_or_default();
+        let token = split.next();
+
+        if !fields.iter().all(|f| WHOX_FIELDS.contains(f)) {
+            return Err(InvalidFormat);
+        }
+        if let Some(t) = token {
+            if t.is_empty() || t.len() > WHOX_MAX_TOKEN_LEN || !t.iter().all(u8::is_ascii_digit) {
+                return Err(InvalidFormat);
+            }
+        }
+
+        Ok(Self { fields, token })
+    }
+
+    fn has(&self, field: u8) -> bool {
+        self.fields.contains(&field)
+    }
+
+    fn describe(&self, info: &WhoInfo) -> String {
+        let mut columns = vec![];
+        for field in WHOX_FIELDS {
+            if !self.has(*field) {
+                continue;
+            }
+            let column = match field {
+                b't' => generate_string(self.token.unwrap_or(b"0")),
+                b'c' => info.channel.to_owned(),
+                b'u' => info.username.to_owned(),
+                b'i' => "255.255.255.255".to_owned(),
+                b'h' => info.hostname.to_owned(),
+                b's' => info.servername.to_owned(),
+                b'n' => info.nickname.to_owned(),
+                b'f' => info.flags.to_owned(),
+                b'd' => info.hopcount.to_string(),
+                b'l' => "0".to_owned(),
+                b'a' => info.account.to_owned(),
+                b'o' => "n/a".to_owned(),
+                _ => format!(":{}", info.realname),
+            };
+            columns.push(column);
+        }
+        columns.join(" ")
+    }
 }
 
 impl<'a> FromGeneric<'a> for Who<'a> {
@@ -28,6 +90,7 @@ impl<'a> FromGeneric<'a> for Who<'a> {
             return Ok(Self {
                 nick: None,
                 o: false,
+                whox: None,
             });
         }
 
@@ -35,11 +98,28 @@ impl<'a> FromGeneric<'a> for Who<'a> {
             Some(v) => v,
             None => return Err(InvalidFormat),
         };
-        let o = validate_o_param(generic.parameters.pop_front())?;
+
+        let (o, whox) = match generic.parameters.pop_front() {
+            Some(options) => match options.iter().position(|c| *c == PERCENT) {
+                Some(i) => {
+                    let flags = match i {
+                        0 => None,
+                        _ => Some(&options[..i]),
+                    };
+                    (
+                        validate_o_param(flags)?,
+                        Some(Whox::parse(&options[i + 1..])?),
+                    )
+                }
+                None => (validate_o_param(Some(options))?, None),
+            },
+            None => (false, None),
+        };
 
         Ok(Self {
             nick: Some(nick),
             o,
+            whox,
         })
     }
 }
@@ -52,26 +132,59 @@ impl Serializable for Who<'_> {
             s = s.add_parameter(v);
         }
 
+        let mut options = vec![];
         if self.o {
-            s = s.add_parameter(&[111u8]);
+            options.push(b'o');
+        }
+        if let Some(whox) = &self.whox {
+            options.push(PERCENT);
+            options.extend_from_slice(whox.fields);
+            if let Some(token) = whox.token {
+                options.push(b',');
+                options.extend_from_slice(token);
+            }
+        }
+        if !options.is_empty() {
+            s = s.add_parameter(&options);
         }
 
         s.serialize()
     }
 }
 
+impl Who<'_> {
+    fn describe(info: &WhoInfo) -> String {
+        format!(
+            "{} {} {} {} {} {} :{} {}",
+            info.channel,
+            info.username,
+            info.hostname,
+            info.servername,
+            info.nickname,
+            info.flags,
+            info.hopcount,
+            info.realname
+        )
+    }
+}
+
 impl Executable for Who<'_> {
     fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
-        // TODO: pattern matching for the parameters
         let mut response = ResponseBuilder::new();
-
-        let users_str = server.describe_connected_clients(client);
-
-        for us in users_str {
-            response = response.add_content_for_response(RPL_WHOREPLY, us)
+        let mask = self.nick.map(generate_string);
+
+        for info in server.who(client, mask.as_deref(), self.o) {
+            response = match &self.whox {
+                Some(whox) => {
+                    response.add_content_for_response(RPL_WHOSPCRPL, whox.describe(&info))
+                }
+                None => response.add_content_for_response(RPL_WHOREPLY, Self::describe(&info)),
+            };
         }
 
-        response = response.add_content_for_response(RPL_ENDOFWHO, "End of /WHO list".to_owned());
+        let mask = mask.unwrap_or_else(|| "*".to_owned());
+        response =
+            response.add_content_for_response(RPL_ENDOFWHO, format!("{} :End of /WHO list", mask));
 
         response.build()
     }
@@ -82,6 +195,7 @@ mod who_parse_tests {
     use crate::irc::message::who::Who;
     use crate::irc::message::GenericMessage;
     use crate::irc::message::MessageError::InvalidCommand;
+    use crate::irc::message::Serializable;
     use crate::irc::message::{Command, FromGeneric, MessageError};
     use std::collections::VecDeque;
 
@@ -184,4 +298,79 @@ mod who_parse_tests {
 
         assert_eq!(err, MessageError::InvalidFormat);
     }
+
+    #[test]
+    fn test_valid_whox_fields_and_token() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"#channel");
+        parameters.push_back(b"%nuhf,42");
+
+        let generic = GenericMessage {
+            command: Command::Who,
+            prefix: None,
+            parameters,
+        };
+
+        let who = Who::from_generic(generic).unwrap();
+        let whox = who.whox.as_ref().unwrap();
+
+        assert!(!who.o);
+        assert_eq!(whox.fields, b"nuhf");
+        assert_eq!(whox.token.unwrap(), b"42");
+        assert_eq!(who.serialize(), "WHO #channel %nuhf,42".to_owned());
+    }
+
+    #[test]
+    fn test_valid_whox_with_o_flag() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"*");
+        parameters.push_back(b"o%tnr");
+
+        let generic = GenericMessage {
+            command: Command::Who,
+            prefix: None,
+            parameters,
+        };
+
+        let who = Who::from_generic(generic).unwrap();
+
+        assert!(who.o);
+        assert_eq!(who.whox.as_ref().unwrap().fields, b"tnr");
+        assert!(who.whox.as_ref().unwrap().token.is_none());
+        assert_eq!(who.serialize(), "WHO * o%tnr".to_owned());
+    }
+
+    #[test]
+    fn whox_with_unknown_field_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"*");
+        parameters.push_back(b"%nxz");
+
+        let generic = GenericMessage {
+            command: Command::Who,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Who::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, MessageError::InvalidFormat);
+    }
+
+    #[test]
+    fn whox_with_invalid_token_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"*");
+        parameters.push_back(b"%n,1234");
+
+        let generic = GenericMessage {
+            command: Command::Who,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Who::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, MessageError::InvalidFormat);
+    }
 }
@@ -0,0 +1,248 @@
+//! Modulo que se centra en las funcionalidades referentes al mensaje de whowas.
+use crate::irc::constants::{
+    ERR_NONICKNAMEGIVEN, ERR_WASNOSUCHNICK, RPL_ENDOFWHOWAS, RPL_WHOISSERVER, RPL_WHOWASUSER,
+};
+use crate::irc::message::utils::{validate_command, validate_irc_params_len};
+use crate::irc::message::GenericMessage;
+use crate::irc::message::{Command, FromGeneric, MessageError};
+use crate::irc::model::server::Server;
+use crate::irc::model::utils::format_unix_time;
+use crate::irc::model::{MTClient, MTServerConnection};
+use crate::irc::responses::builder::ResponseBuilder;
+use crate::irc::responses::ResponseType;
+
+use super::serializer::MessageSerializer;
+use super::server_query::ServerQuery;
+use super::utils::{generate_string, validate_nickmasks};
+use super::{Executable, Serializable, ServerExecutable};
+
+#[derive(Debug)]
+/// Struct del mensaje referido a whowas
+/// Contiene los nicknames a buscar en el historial, la cantidad maxima de entradas
+/// por nickname (0 o negativa para todas) y un server opcional al que preguntarle.
+pub struct Whowas<'a> {
+    pub prefix: Option<&'a [u8]>,
+    pub nicknames: Vec<&'a [u8]>,
+    pub count: Option<i64>,
+    pub target: Option<&'a [u8]>,
+}
+
+impl<'a> FromGeneric<'a> for Whowas<'a> {
+    fn from_generic(mut generic: GenericMessage<'a>) -> Result<Self, MessageError> {
+        validate_command(generic.command, Command::Whowas)?;
+        validate_irc_params_len(&generic.parameters, 3, 1, ERR_NONICKNAMEGIVEN)?;
+
+        let nicknames = validate_nickmasks(generic.parameters.pop_front())?;
+
+        let count = match generic.parameters.pop_front() {
+            Some(c) => match generate_string(c).parse::<i64>() {
+                Ok(v) => Some(v),
+                Err(_) => return Err(MessageError::InvalidFormat),
+            },
+            None => None,
+        };
+
+        Ok(Self {
+            prefix: generic.prefix,
+            nicknames,
+            count,
+            target: generic.parameters.pop_front(),
+        })
+    }
+}
+
+impl Whowas<'_> {
+    fn serializer(&self, prefix: Option<&[u8]>, target: Option<&[u8]>) -> String {
+        let mut s = MessageSerializer::new(prefix, Command::Whowas).add_csl_params(&self.nicknames);
+
+        if let Some(c) = self.count {
+            s = s.add_parameter(c.to_string().as_bytes());
+        } else if target.is_some() {
+            s = s.add_parameter(b"0");
+        }
+
+        if let Some(t) = target {
+            s = s.add_parameter(t);
+        }
+
+        s.serialize()
+    }
+
+    fn limit(&self) -> Option<usize> {
+        match self.count {
+            Some(c) if c > 0 => Some(c as usize),
+            _ => None,
+        }
+    }
+}
+
+impl Serializable for Whowas<'_> {
+    fn serialize(&self) -> String {
+        self.serializer(self.prefix, self.target)
+    }
+}
+
+impl ServerQuery for Whowas<'_> {
+    fn prefix(&self) -> Option<&[u8]> {
+        self.prefix
+    }
+
+    fn target(&self) -> Option<&[u8]> {
+        self.target
+    }
+
+    fn command(&self) -> Command {
+        Command::Whowas
+    }
+
+    fn query_message(&self, nickname: &str, servername: &str) -> String {
+        self.serializer(Some(nickname.as_bytes()), Some(servername.as_bytes()))
+    }
+
+    fn replies(&self, server: &Server) -> Vec<ResponseType> {
+        // One builder per entry, the builder groups lines by numeric
+        let mut responses = vec![];
+
+        for nick in &self.nicknames {
+            let nick = generate_string(nick);
+            let entries = server.whowas(&nick, self.limit());
+
+            if entries.is_empty() {
+                responses.append(
+                    &mut ResponseBuilder::new()
+                        .add_content_for_response(
+                            ERR_WASNOSUCHNICK,
+                            format!("{} :There was no such nickname", nick),
+                        )
+                        .build(),
+                );
+            }
+
+            for entry in entries {
+                responses.append(
+                    &mut ResponseBuilder::new()
+                        .add_content_for_response(
+                            RPL_WHOWASUSER,
+                            format!(
+                                "{} {} {} * :{}",
+                                entry.nickname, entry.username, entry.hostname, entry.realname
+                            ),
+                        )
+                        .add_content_for_response(
+                            RPL_WHOISSERVER,
+                            format!(
+                                "{} {} :{}",
+                                entry.nickname,
+                                entry.servername,
+                                format_unix_time(entry.signoff)
+                            ),
+                        )
+                        .build(),
+                );
+            }
+
+            responses.append(
+                &mut ResponseBuilder::new()
+                    .add_content_for_response(RPL_ENDOFWHOWAS, format!("{} :End of WHOWAS", nick))
+                    .build(),
+            );
+        }
+
+        responses
+    }
+}
+
+impl Executable for Whowas<'_> {
+    fn _execute(&self, server: &Server, client: MTClient) -> Vec<ResponseType> {
+        self.query(server, client)
+    }
+}
+
+impl ServerExecutable for Whowas<'_> {
+    fn _execute_for_server(&self, server: &Server) -> Vec<ResponseType> {
+        self.query_for_server(server);
+        ResponseBuilder::new().build()
+    }
+
+    fn execute_for_server(&self, server: &Server, _: MTServerConnection) -> Vec<ResponseType> {
+        self._execute_for_server(server)
+    }
+}
+
+#[cfg(test)]
+mod whowas_parse_tests {
+    use super::*;
+    use crate::irc::message::MessageError::{IRCDefined, InvalidCommand, InvalidFormat};
+    use std::collections::vec_deque::VecDeque;
+
+    #[test]
+    fn test_whowas_with_different_command_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick");
+
+        let generic = GenericMessage {
+            command: Command::WhoIs,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Whowas::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidCommand);
+    }
+
+    #[test]
+    fn test_whowas_without_nickname_error() {
+        let parameters: VecDeque<&[u8]> = VecDeque::new();
+
+        let generic = GenericMessage {
+            command: Command::Whowas,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Whowas::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, IRCDefined(ERR_NONICKNAMEGIVEN));
+    }
+
+    #[test]
+    fn test_whowas_with_invalid_count_error() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick");
+        parameters.push_back(b"many");
+
+        let generic = GenericMessage {
+            command: Command::Whowas,
+            prefix: None,
+            parameters,
+        };
+
+        let err = Whowas::from_generic(generic).unwrap_err();
+
+        assert_eq!(err, InvalidFormat);
+    }
+
+    #[test]
+    fn test_whowas_with_count_and_server() {
+        let mut parameters: VecDeque<&[u8]> = VecDeque::new();
+        parameters.push_back(b"nick,other");
+        parameters.push_back(b"2");
+        parameters.push_back(b"127.0.0.1:8080");
+
+        let generic = GenericMessage {
+            command: Command::Whowas,
+            prefix: Some(b"asker"),
+            parameters,
+        };
+
+        let whowas = Whowas::from_generic(generic).unwrap();
+
+        assert_eq!(whowas.nicknames.len(), 2);
+        assert_eq!(whowas.limit(), Some(2));
+        assert_eq!(
+            whowas.serialize(),
+            ":asker WHOWAS nick,other 2 127.0.0.1:8080".to_owned()
+        );
+    }
+}
@@ -1,19 +1,20 @@
+pub mod bot;
 pub mod constants;
 pub mod ctcp;
 pub mod message;
 pub mod model;
 pub mod responses;
+#[cfg(test)]
+pub mod testing;
 
+/// Toma el lock. Un lock envenenado quiere decir que un hilo entro en panico a mitad de
+/// una modificacion, seguir con ese estado es peor que cortar.
 #[macro_export]
 macro_rules! try_lock {
     ($locked: expr) => {
         match $locked.lock() {
             Ok(unlocked) => unlocked,
-            Err(e) => {
-                println!("[SERVER-MULTITHREAD] FATAL: Lock poisoned");
-                println!("{}", e);
-                e.into_inner()
-            }
+            Err(e) => panic!("[SERVER-MULTITHREAD] FATAL: Lock poisoned\n{}", e),
         }
     };
 }
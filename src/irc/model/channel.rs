@@ -1,7 +1,7 @@
 //! Modulo que se centra en las funcionalidades referentes a la representacion de canales.
 use std::collections::HashMap;
 
-use crate::irc::constants::{ERR_CHANLIMIT, ERR_SERVERERR};
+use crate::irc::constants::{ERR_BANNEDFROMCHAN, ERR_CHANLIMIT, ERR_SERVERERR};
 use crate::irc::{
     constants::{ERR_BADCHANNELKEY, ERR_NOTONCHANNEL},
     message::utils::validate_channel,
@@ -10,9 +10,11 @@ use crate::irc::{
 use crate::try_lock;
 
 use super::{
+    extban::BanSubject,
+    irc_key::IrcKey,
     utils::{
-        deserialize_bool, deserialize_err, deserialize_num, deseriaze_usernames, serialize_bool,
-        serialize_list, serialize_option,
+        deserialize_bool, deserialize_err, deserialize_masks, deserialize_num, deseriaze_usernames,
+        serialize_bool, serialize_list, serialize_option,
     },
     MTClient, ServerError,
 };
@@ -22,7 +24,7 @@ pub struct Channel {
     pub name: String,
     pub topic: Option<String>,
     pub password: Option<String>,
-    pub clients: HashMap<String, MTClient>,
+    pub clients: HashMap<IrcKey, MTClient>,
     pub private: bool,
     pub secret: bool,
     pub invite_only: bool,
@@ -30,10 +32,29 @@ pub struct Channel {
     pub no_msg_outside: bool,
     pub moderated: bool,
     pub limit: Option<u32>,
-    pub allow_moderated: HashMap<String, MTClient>,
-    pub registered_operators: HashMap<String, bool>,
+    pub allow_moderated: HashMap<IrcKey, MTClient>,
+    pub registered_operators: HashMap<IrcKey, bool>,
+    pub bans: Vec<String>,
+    pub ban_exceptions: Vec<String>,
+    pub invite_exceptions: Vec<String>,
+    pub quiets: Vec<String>,
 }
 
+/// Listas de mascaras que guarda cada canal (+b, +e, +I, +q), pueden ser bans extendidos.
+#[derive(Debug, Clone, Copy)]
+pub enum ChannelList {
+    Bans,
+    BanExceptions,
+    InviteExceptions,
+    Quiets,
+}
+
+// Cantidad de campos de los formatos anteriores a las listas de mascaras y a la lista de quiets,
+// se siguen aceptando al leer.
+const LEGACY_SERIALIZE_LEN: usize = 11;
+const NO_QUIETS_SERIALIZE_LEN: usize = 14;
+const SERIALIZE_LEN: usize = 15;
+
 enum Serialize {
     Name = 0,
     Key,
@@ -46,6 +67,10 @@ enum Serialize {
     Moderated,
     Limit,
     RegisteredOperators,
+    Bans,
+    BanExceptions,
+    InviteExceptions,
+    Quiets,
 }
 
 // Creation
@@ -65,12 +90,16 @@ impl Channel {
             limit: None,
             allow_moderated: HashMap::new(),
             registered_operators: HashMap::new(),
+            bans: Vec::new(),
+            ban_exceptions: Vec::new(),
+            invite_exceptions: Vec::new(),
+            quiets: Vec::new(),
         }
     }
 
     pub fn deserialize(data: &[&str]) -> Result<Channel, String> {
         let mut c = Channel::create_from("d".to_owned(), None);
-        if data.len() != 11 {
+        if ![SERIALIZE_LEN, NO_QUIETS_SERIALIZE_LEN, LEGACY_SERIALIZE_LEN].contains(&data.len()) {
             return Err(deserialize_err("Invalid format"));
         }
 
@@ -101,11 +130,21 @@ impl Channel {
         c.registered_operators =
             deseriaze_usernames(data[Serialize::RegisteredOperators as usize])?;
 
+        if data.len() >= NO_QUIETS_SERIALIZE_LEN {
+            c.bans = deserialize_masks(data[Serialize::Bans as usize])?;
+            c.ban_exceptions = deserialize_masks(data[Serialize::BanExceptions as usize])?;
+            c.invite_exceptions = deserialize_masks(data[Serialize::InviteExceptions as usize])?;
+        }
+
+        if data.len() == SERIALIZE_LEN {
+            c.quiets = deserialize_masks(data[Serialize::Quiets as usize])?;
+        }
+
         Ok(c)
     }
 
     pub fn serialize(&self) -> Vec<String> {
-        let mut r: Vec<String> = vec![String::new(); 11];
+        let mut r: Vec<String> = vec![String::new(); SERIALIZE_LEN];
         r[Serialize::Name as usize] = self.name.to_owned();
         r[Serialize::Key as usize] = serialize_option(&self.password);
         r[Serialize::Topic as usize] = serialize_option(&self.topic);
@@ -117,7 +156,11 @@ impl Channel {
         r[Serialize::Moderated as usize] = serialize_bool(self.moderated);
         r[Serialize::Limit as usize] = serialize_option(&self.limit);
         r[Serialize::RegisteredOperators as usize] =
-            serialize_list(&self.registered_operators.keys().collect::<Vec<&String>>());
+            serialize_list(&self.registered_operators.keys().collect::<Vec<&IrcKey>>());
+        r[Serialize::Bans as usize] = serialize_list(&self.bans);
+        r[Serialize::BanExceptions as usize] = serialize_list(&self.ban_exceptions);
+        r[Serialize::InviteExceptions as usize] = serialize_list(&self.invite_exceptions);
+        r[Serialize::Quiets as usize] = serialize_list(&self.quiets);
         r
     }
 }
@@ -129,6 +172,9 @@ impl Channel {
         client: MTClient,
         password: Option<String>,
     ) -> Result<(), ServerError> {
+        let subject = { try_lock!(client).ban_subject() };
+        self.check_ban(&subject)?;
+
         if password.as_deref() == self.password.as_deref() {
             return self.add_client(client);
         }
@@ -162,12 +208,12 @@ impl Channel {
     }
 
     pub fn insert_client(&mut self, client: MTClient) {
-        let nickname = { try_lock!(client).nickname.to_owned() };
+        let nickname = { IrcKey::from(&try_lock!(client).nickname) };
         self.clients.insert(nickname, client);
     }
 
     pub fn remove_client_by_nickname(&mut self, nickname: &str) -> Result<(), ServerError> {
-        match self.clients.remove(nickname) {
+        match self.clients.remove(&IrcKey::from(nickname)) {
             Some(_) => Ok(()),
             None => Err(ServerError {
                 code: ERR_NOTONCHANNEL,
@@ -181,12 +227,13 @@ impl Channel {
     }
 
     pub fn change_nickname(&mut self, old_nick: &str, new_nick: &str) {
-        if let Some(client) = self.clients.remove(old_nick) {
-            self.clients.insert(new_nick.to_owned(), client);
+        let (old_nick, new_nick) = (IrcKey::from(old_nick), IrcKey::from(new_nick));
+        if let Some(client) = self.clients.remove(&old_nick) {
+            self.clients.insert(new_nick.clone(), client);
         }
 
-        if self.registered_operators.remove(old_nick).is_some() {
-            self.registered_operators.insert(new_nick.to_owned(), false);
+        if self.registered_operators.remove(&old_nick).is_some() {
+            self.registered_operators.insert(new_nick, false);
         }
     }
 
@@ -201,7 +248,7 @@ impl Channel {
             }
         }
 
-        self.allow_moderated.insert(nickname, client);
+        self.allow_moderated.insert(IrcKey::from(nickname), client);
         Ok(())
     }
 
@@ -213,13 +260,77 @@ impl Channel {
                 msg: format!("{} :{} not on channel", &self.name, &c.nickname),
             });
         }
-        self.allow_moderated.remove(&c.nickname);
+        self.allow_moderated.remove(&IrcKey::from(&c.nickname));
         Ok(())
     }
 
     pub fn is_allowed_for_moderated(&self, client: &MTClient) -> bool {
         self.allow_moderated
-            .contains_key(&try_lock!(client).nickname)
+            .contains_key(&IrcKey::from(&try_lock!(client).nickname))
+    }
+}
+
+// Mask lists
+impl Channel {
+    pub fn get_list(&self, list: ChannelList) -> &Vec<String> {
+        match list {
+            ChannelList::Bans => &self.bans,
+            ChannelList::BanExceptions => &self.ban_exceptions,
+            ChannelList::InviteExceptions => &self.invite_exceptions,
+            ChannelList::Quiets => &self.quiets,
+        }
+    }
+
+    fn get_list_mut(&mut self, list: ChannelList) -> &mut Vec<String> {
+        match list {
+            ChannelList::Bans => &mut self.bans,
+            ChannelList::BanExceptions => &mut self.ban_exceptions,
+            ChannelList::InviteExceptions => &mut self.invite_exceptions,
+            ChannelList::Quiets => &mut self.quiets,
+        }
+    }
+
+    pub fn add_mask(&mut self, list: ChannelList, mask: &str) {
+        let masks = self.get_list_mut(list);
+        if !masks.iter().any(|m| m.eq_ignore_ascii_case(mask)) {
+            masks.push(mask.to_owned());
+        }
+    }
+
+    pub fn remove_mask(&mut self, list: ChannelList, mask: &str) {
+        self.get_list_mut(list)
+            .retain(|m| !m.eq_ignore_ascii_case(mask));
+    }
+
+    fn list_matches(&self, list: ChannelList, subject: &BanSubject) -> bool {
+        self.get_list(list).iter().any(|mask| subject.matches(mask))
+    }
+
+    /// Un cliente esta baneado si matchea algun ban y ninguna excepcion.
+    pub fn is_banned(&self, subject: &BanSubject) -> bool {
+        self.list_matches(ChannelList::Bans, subject)
+            && !self.list_matches(ChannelList::BanExceptions, subject)
+    }
+
+    /// Un cliente silenciado puede quedarse en el canal pero no hablar, las excepciones de ban
+    /// tambien lo liberan.
+    pub fn is_quieted(&self, subject: &BanSubject) -> bool {
+        self.list_matches(ChannelList::Quiets, subject)
+            && !self.list_matches(ChannelList::BanExceptions, subject)
+    }
+
+    pub fn is_invite_exempt(&self, subject: &BanSubject) -> bool {
+        self.list_matches(ChannelList::InviteExceptions, subject)
+    }
+
+    pub fn check_ban(&self, subject: &BanSubject) -> Result<(), ServerError> {
+        if self.is_banned(subject) {
+            return Err(ServerError {
+                code: ERR_BANNEDFROMCHAN,
+                msg: format!("{} :Cannot join channel (+b)", self.name),
+            });
+        }
+        Ok(())
     }
 }
 
@@ -231,7 +342,7 @@ impl Channel {
             if try_lock!(client).invisible && !is_oper {
                 continue;
             }
-            clients_nicknames.push(nicks.to_owned());
+            clients_nicknames.push(nicks.to_string());
         }
         clients_nicknames.sort();
         clients_nicknames
@@ -260,24 +371,6 @@ impl Channel {
         Some(self.channel_to_string())
     }
 
-    pub fn describe_clients(&self, asker: MTClient) -> Vec<String> {
-        let asker_operator = { try_lock!(asker).is_channel_operator(&self.name) };
-
-        let mut res = Vec::new();
-        for (_, client) in self.clients.iter() {
-            let lclient = try_lock!(client);
-            if lclient.invisible && !asker_operator {
-                continue;
-            }
-            res.push(format!(
-                "{} :{} {} {} :{}",
-                self.name, lclient.username, lclient.hostname, lclient.nickname, lclient.realname
-            ));
-        }
-
-        res
-    }
-
     pub fn get_topic(&self) -> Option<String> {
         self.topic.as_ref().map(|t| t.to_owned())
     }
@@ -349,6 +442,25 @@ impl Channel {
             format!("MODE {} -k", self.name)
         }
     }
+
+    pub fn masks_messages(&self) -> Vec<String> {
+        let lists = [
+            (ChannelList::Bans, 'b'),
+            (ChannelList::BanExceptions, 'e'),
+            (ChannelList::InviteExceptions, 'I'),
+            (ChannelList::Quiets, 'q'),
+        ];
+
+        lists
+            .iter()
+            .flat_map(|(list, mode)| {
+                self.get_list(*list)
+                    .iter()
+                    .map(move |mask| format!("MODE {} +{} {}", self.name, mode, mask))
+            })
+            .collect()
+    }
+
     pub fn mode_message(&self) -> String {
         format!("MODE {}", self.name.to_owned())
     }
@@ -0,0 +1,18 @@
+//! Modulo que se centra en las funcionalidades referentes a las capacidades negociadas por el cliente.
+use super::Client;
+
+impl Client {
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+
+    pub fn add_capability(&mut self, capability: &str) {
+        if !self.has_capability(capability) {
+            self.capabilities.push(capability.to_owned());
+        }
+    }
+
+    pub fn remove_capability(&mut self, capability: &str) {
+        self.capabilities.retain(|c| c != capability);
+    }
+}
@@ -1,46 +1,94 @@
-use crate::{irc::model::MTChannel, try_lock};
-
-use super::Client;
-
-impl Client {
-    pub fn add_channel(&mut self, channel: MTChannel) {
-        let channel_name = { try_lock!(channel).name.to_owned() };
-        self.channels.insert(channel_name, channel);
-    }
-
-    pub fn is_in_channel(&self, channel_name: &str) -> bool {
-        self.channels.contains_key(channel_name)
-    }
-
-    pub fn remove_channel(&mut self, channel_name: &str) {
-        let _ = self.channels.remove(channel_name);
-    }
-
-    pub fn set_channel_operator(&mut self, channel_name: String, channel: MTChannel) {
-        self.channel_operator.insert(channel_name, channel);
-    }
-
-    pub fn del_channel_operator(&mut self, channel_name: &str) {
-        self.channel_operator.remove(channel_name);
-    }
-
-    pub fn is_channel_operator(&self, channel_name: &str) -> bool {
-        self.channel_operator.contains_key(channel_name)
-    }
-
-    pub fn channel_amount(&self) -> usize {
-        self.channels.keys().count()
-    }
-
-    pub fn is_invited(&self, channel_name: &str) -> bool {
-        self.channel_invites.contains(&channel_name.to_owned())
-    }
-
-    pub fn add_invite(&mut self, channel_name: &str) {
-        self.channel_invites.push(channel_name.to_owned());
-    }
-
-    pub fn remove_invite(&mut self, channel_name: &str) {
-        self.channel_invites.retain(|ch_n| ch_n != channel_name);
-    }
-}
+use crate::{
+    irc::{
+        constants::KNOCK_DELAY,
+        model::{irc_key::IrcKey, MTChannel},
+    },
+    try_lock,
+};
+
+use super::Client;
+
+impl Client {
+    pub fn add_channel(&mut self, channel: MTChannel) {
+        let channel_name = { try_lock!(channel).name.to_owned() };
+        self.channels.insert(IrcKey::from(channel_name), channel);
+    }
+
+    pub fn is_in_channel(&self, channel_name: &str) -> bool {
+        self.channels.contains_key(&IrcKey::from(channel_name))
+    }
+
+    pub fn remove_channel(&mut self, channel_name: &str) {
+        let _ = self.channels.remove(&IrcKey::from(channel_name));
+    }
+
+    pub fn set_channel_operator(&mut self, channel_name: String, channel: MTChannel) {
+        self.channel_operator
+            .insert(IrcKey::from(channel_name), channel);
+    }
+
+    pub fn del_channel_operator(&mut self, channel_name: &str) {
+        self.channel_operator.remove(&IrcKey::from(channel_name));
+    }
+
+    pub fn is_channel_operator(&self, channel_name: &str) -> bool {
+        self.channel_operator
+            .contains_key(&IrcKey::from(channel_name))
+    }
+
+    pub fn channel_amount(&self) -> usize {
+        self.channels.keys().count()
+    }
+
+    pub fn is_invited(&self, channel_name: &str) -> bool {
+        self.channel_invites.contains(&IrcKey::from(channel_name))
+    }
+
+    pub fn add_invite(&mut self, channel_name: &str) {
+        self.channel_invites.push(IrcKey::from(channel_name));
+    }
+
+    pub fn remove_invite(&mut self, channel_name: &str) {
+        let channel_name = IrcKey::from(channel_name);
+        self.channel_invites.retain(|ch_n| *ch_n != channel_name);
+    }
+
+    /// Registra un KNOCK al canal si paso el tiempo minimo desde el anterior.
+    /// Solo se recuerdan los KNOCK que todavia demoran al siguiente.
+    pub fn try_knock(&mut self, channel_name: &str, now: u64) -> bool {
+        self.knocks.retain(|_, last| now < *last + KNOCK_DELAY);
+
+        let channel_name = IrcKey::from(channel_name);
+        if self.knocks.contains_key(&channel_name) {
+            return false;
+        }
+        self.knocks.insert(channel_name, now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod channels_tests {
+    use super::*;
+
+    #[test]
+    fn test_knock_waits_the_delay_per_channel() {
+        let mut client = Client::for_data("valen".to_owned());
+
+        assert!(client.try_knock("#a", 100));
+        assert!(!client.try_knock("#A", 100 + KNOCK_DELAY - 1));
+        assert!(client.try_knock("#b", 101));
+        assert!(client.try_knock("#a", 100 + KNOCK_DELAY));
+    }
+
+    #[test]
+    fn test_old_knocks_are_forgotten() {
+        let mut client = Client::for_data("valen".to_owned());
+        for i in 0..50 {
+            assert!(client.try_knock(&format!("#chan{}", i), 100));
+        }
+
+        assert!(client.try_knock("#other", 100 + KNOCK_DELAY));
+        assert_eq!(client.knocks.len(), 1);
+    }
+}
@@ -3,9 +3,12 @@ use std::net::TcpStream;
 
 use super::Client;
 use crate::irc::model::connection::Connection;
+use crate::irc::model::flood::FloodState;
+use crate::irc::model::link_stats::LinkStats;
+use crate::irc::model::writer::ConnectionWriter;
+use crate::irc::model::ConnectionError::InvalidArguments;
 use crate::irc::model::{ConnectionError, CLIENT_ARGS, CLIENT_IP_POS, CLIENT_PORT_POS};
 use crate::unwrap;
-use crate::ConnectionError::InvalidArguments;
 
 impl Client {
     pub fn create_fromargs(argv: Vec<String>) -> Result<Client, ConnectionError> {
@@ -33,24 +36,32 @@ impl Client {
             Err(_) => Client::random_nick(),
         };
         Client {
-            stream: Some(tcp),
+            stream: Some(ConnectionWriter::new(tcp)),
             nickname: (a),
             hostname: String::new(),
             username: String::new(),
             servername: String::new(),
             pass: None,
             realname: String::new(),
+            account: None,
             away_message: None,
             channels: HashMap::new(),
             server_operator: false,
             invisible: false,
             rec_sv_notices: true,
+            wallops: false,
             channel_operator: HashMap::new(),
             channel_invites: Vec::new(),
+            capabilities: Vec::new(),
+            monitoring: Vec::new(),
+            knocks: HashMap::new(),
+            address: None,
+            stats: LinkStats::default(),
+            flood: FloodState::default(),
         }
     }
 
-    pub fn from_connection(conn: Connection) -> Result<Client, ()> {
+    pub(crate) fn from_connection(conn: Connection) -> Result<Client, ()> {
         let nickname = unwrap!(conn.conn_nick);
         let hostname = unwrap!(conn.hostname);
         let username = unwrap!(conn.username);
@@ -67,13 +78,21 @@ impl Client {
             servername,
             pass: Some(password),
             realname,
+            account: None,
             away_message: None,
             channels: HashMap::new(),
             server_operator: false,
             invisible: false,
             rec_sv_notices: true,
+            wallops: false,
             channel_operator: HashMap::new(),
             channel_invites: Vec::new(),
+            capabilities: Vec::new(),
+            monitoring: Vec::new(),
+            knocks: HashMap::new(),
+            address: conn.address,
+            stats: LinkStats::default(),
+            flood: FloodState::default(),
         })
     }
 
@@ -86,13 +105,21 @@ impl Client {
             servername: String::new(),
             pass: None,
             realname: String::new(),
+            account: None,
             away_message: None,
             channels: HashMap::new(),
             server_operator: false,
             invisible: false,
             rec_sv_notices: true,
+            wallops: false,
             channel_operator: HashMap::new(),
             channel_invites: Vec::new(),
+            capabilities: Vec::new(),
+            monitoring: Vec::new(),
+            knocks: HashMap::new(),
+            address: None,
+            stats: LinkStats::default(),
+            flood: FloodState::default(),
         }
     }
 
@@ -1,12 +1,12 @@
 use std::{
-    io::{self, BufRead, BufReader},
+    io::{BufRead, BufReader},
     net::TcpStream,
     sync::mpsc::{Receiver, Sender},
     thread::{self, JoinHandle},
 };
 
 use crate::{
-    gui::{GuiMessage, IncomingMessage},
+    gui::{plugins::SharedPlugins, GuiMessage, IncomingMessage},
     irc::{
         constants::ERR_SERVERERR,
         model::{
@@ -17,6 +17,7 @@ use crate::{
 };
 
 use super::Client;
+use crate::try_lock;
 
 impl ConnectionTypeWrapper {
     fn should_be_handled(self) -> bool {
@@ -30,10 +31,11 @@ impl Client {
         mut self,
         rx_from_gui: Receiver<GuiMessage>,
         tx_to_ui: Sender<IncomingMessage>,
+        plugins: SharedPlugins,
     ) -> Result<(), ServerError> {
         let thread = self.listen_from_sv(tx_to_ui.clone())?;
 
-        self.receive_from_gui(rx_from_gui, tx_to_ui);
+        self.receive_from_gui(rx_from_gui, tx_to_ui, plugins);
 
         self.tcp_destroy()?;
 
@@ -46,6 +48,7 @@ impl Client {
         &mut self,
         rx_from_gui: Receiver<GuiMessage>,
         tx_to_ui: Sender<IncomingMessage>,
+        plugins: SharedPlugins,
     ) {
         let mut dcc_handler = DccMessageHandler::init();
 
@@ -53,6 +56,11 @@ impl Client {
             match gui_message {
                 GuiMessage::Close => break,
                 GuiMessage::MessageIRC(sv_message) => {
+                    // Every outgoing line goes through the plugin filters, whoever sent it
+                    let sv_message = match try_lock!(plugins).filter(&sv_message) {
+                        Some(m) => m,
+                        None => continue,
+                    };
                     if let Err(e) = self.write_to_sv(&sv_message) {
                         println!("Can't send message to server: {:?}", e);
                         break;
@@ -113,30 +121,23 @@ impl Client {
     ) -> Result<JoinHandle<()>, ServerError> {
         let stream = self.clone_stream()?;
 
+        // Blocking reads, the thread sleeps until the server sends something. It ends when the
+        // connection closes, either from the server or from tcp_destroy after GuiMessage::Close
         Ok(thread::spawn(move || {
-            let _ = stream.set_nonblocking(true);
-            let mut lines = BufReader::new(stream).lines();
-            loop {
-                if let Some(line) = lines.next() {
-                    match line {
-                        Ok(p) => {
-                            if let Err(e) = tx_to_ui.send(IncomingMessage::Server(p)) {
-                                println!("[CLIENT-MESSAGE SENDER] {:?}", e);
-                            }
-                        }
-                        Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
-                            continue;
-                        }
-                        Err(_) => {
-                            println!("Disconnected from the server. Terminating [E1]");
-                            break;
+            for line in BufReader::new(stream).lines() {
+                match line {
+                    Ok(p) => {
+                        if let Err(e) = tx_to_ui.send(IncomingMessage::Server(p)) {
+                            println!("[CLIENT-MESSAGE SENDER] {:?}", e);
                         }
                     }
-                } else {
-                    println!("Disconnected from the server. Terminating [E2]");
-                    break;
+                    Err(_) => {
+                        println!("Disconnected from the server. Terminating [E1]");
+                        return;
+                    }
                 }
             }
+            println!("Disconnected from the server. Terminating [E2]");
         }))
     }
 }
@@ -1,11 +1,15 @@
 //! Modulo que se centra en las funcionalidades referentes a la representacion de clientes.
 
-use std::{collections::HashMap, net::TcpStream};
+use std::{collections::HashMap, net::IpAddr};
 
-use super::MTChannel;
+use super::{
+    flood::FloodState, irc_key::IrcKey, link_stats::LinkStats, writer::ConnectionWriter, MTChannel,
+};
 
+pub mod capabilities;
 pub mod channels;
 pub mod create;
+#[cfg(feature = "gui")]
 pub mod gtk_runtime;
 pub mod nick;
 pub mod no_gui_runtime;
@@ -15,18 +19,30 @@ pub mod sv_comm;
 
 #[derive(Debug)]
 pub struct Client {
-    pub stream: Option<TcpStream>,
+    pub stream: Option<ConnectionWriter>,
     pub nickname: String,
     pub hostname: String,
     pub username: String,
     pub servername: String,
     pub pass: Option<String>,
     pub realname: String,
+    /// Cuenta con la que esta registrado el cliente, None hasta que termina de registrarse.
+    pub account: Option<String>,
     pub away_message: Option<String>,
-    pub channels: HashMap<String, MTChannel>,
+    pub channels: HashMap<IrcKey, MTChannel>,
     pub server_operator: bool,
     pub invisible: bool,
     pub rec_sv_notices: bool,
-    pub channel_operator: HashMap<String, MTChannel>,
-    pub channel_invites: Vec<String>,
+    pub wallops: bool,
+    pub channel_operator: HashMap<IrcKey, MTChannel>,
+    pub channel_invites: Vec<IrcKey>,
+    pub capabilities: Vec<String>,
+    /// Nicknames seguidos con MONITOR, solo se usa en los clientes locales.
+    pub monitoring: Vec<IrcKey>,
+    /// Momento del ultimo KNOCK a cada canal, para limitar la frecuencia.
+    pub knocks: HashMap<IrcKey, u64>,
+    /// Direccion desde la que se conecto, solo se conoce para los clientes locales.
+    pub address: Option<IpAddr>,
+    pub stats: LinkStats,
+    pub flood: FloodState,
 }
@@ -53,18 +53,13 @@ impl Client {
         };
 
         let t = thread::spawn(move || {
-            let mut lines = BufReader::new(stream).lines();
-            loop {
-                if let Some(line) = lines.next() {
-                    match line {
-                        Ok(p) => println!("{}", p),
-                        Err(_) => {
-                            println!("Disconnected from the server. Terminating ");
-                            break;
-                        }
-                    }
+            for line in BufReader::new(stream).lines() {
+                match line {
+                    Ok(p) => println!("{}", p),
+                    Err(_) => break,
                 }
             }
+            println!("Disconnected from the server. Terminating ");
         });
         Ok(t)
     }
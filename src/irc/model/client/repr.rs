@@ -1,3 +1,5 @@
+use crate::irc::model::extban::BanSubject;
+
 use super::Client;
 
 impl Client {
@@ -8,6 +10,18 @@ impl Client {
         )
     }
 
+    pub fn hostmask(&self) -> String {
+        format!("{}!{}@{}", self.nickname, self.username, self.hostname)
+    }
+
+    pub fn ban_subject(&self) -> BanSubject {
+        BanSubject {
+            hostmask: self.hostmask(),
+            account: self.account.to_owned(),
+            realname: self.realname.to_owned(),
+        }
+    }
+
     pub fn describe_channels(&self) -> Vec<String> {
         self.channels
             .iter()
@@ -16,7 +30,7 @@ impl Client {
                     return format!("@{}", channel_name);
                 }
 
-                channel_name.to_owned()
+                channel_name.to_string()
             })
             .collect()
     }
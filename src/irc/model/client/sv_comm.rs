@@ -1,12 +1,20 @@
-use std::io::Write;
-
 use super::Client;
 
 impl Client {
     pub fn write_to_sv(&mut self, line: &str) -> std::io::Result<usize> {
         if let Some(s) = &mut self.stream {
-            let _ = s.write(format!("{}\r\n", line).as_bytes())?;
+            let written = s.write_line(line)?;
+            self.stats.record_sent(written);
         }
         Ok(0)
     }
+
+    /// Bytes que todavia no se le pudieron mandar al cliente.
+    pub fn sendq(&self) -> usize {
+        self.stream.as_ref().map_or(0, |s| s.pending())
+    }
+
+    pub fn sendq_exceeded(&self) -> bool {
+        self.stream.as_ref().is_some_and(|s| s.exceeded())
+    }
 }
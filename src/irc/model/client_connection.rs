@@ -0,0 +1,133 @@
+//! Modulo que se centra en las funcionalidades referentes a la conexion de un cliente con un server.
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{Shutdown, SocketAddr, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use crate::irc::message::Serializable;
+
+/// Conexion de un cliente con un server, para usar desde otros programas sin la interfaz.
+/// Las lineas se mandan y se reciben sin el CRLF.
+#[derive(Debug)]
+pub struct ClientConnection {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl ClientConnection {
+    pub fn connect(address: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::from_stream(TcpStream::connect(address)?)
+    }
+
+    pub fn from_stream(stream: TcpStream) -> io::Result<Self> {
+        let reader = BufReader::new(stream.try_clone()?);
+        Ok(Self { stream, reader })
+    }
+
+    /// Manda PASS, NICK y USER. Las respuestas del server se leen con read_line.
+    pub fn register(
+        &mut self,
+        password: &str,
+        nickname: &str,
+        username: &str,
+        realname: &str,
+    ) -> io::Result<()> {
+        self.send(&format!("PASS {}", password))?;
+        self.send(&format!("NICK {}", nickname))?;
+        self.send(&format!(
+            "USER {} {} {} :{}",
+            username, username, username, realname
+        ))
+    }
+
+    /// Manda la linea con el CRLF. Rechaza con InvalidInput las que tienen CR, LF o NUL,
+    /// que el server tomaria como el final del comando y el principio de otro.
+    pub fn send(&mut self, line: &str) -> io::Result<()> {
+        if line.contains(['\r', '\n', '\0']) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Line breaks and NUL can't be sent: {:?}", line),
+            ));
+        }
+        self.stream.write_all(format!("{}\r\n", line).as_bytes())
+    }
+
+    pub fn send_message(&mut self, message: &impl Serializable) -> io::Result<()> {
+        self.send(&message.serialize())
+    }
+
+    /// Proxima linea del server. Devuelve None cuando el server cerro la conexion.
+    pub fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = vec![];
+        if self.reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = String::from_utf8_lossy(&line);
+        Ok(Some(line.trim_end_matches(['\r', '\n']).to_owned()))
+    }
+
+    /// Tiempo maximo que espera read_line, None espera indefinidamente.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.stream.set_read_timeout(timeout)
+    }
+
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.stream.peer_addr()
+    }
+
+    /// Otra conexion sobre el mismo socket, por ejemplo para escribir desde otro hilo.
+    /// Cada una tiene su propio buffer de lectura, asi que conviene leer de una sola.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Self::from_stream(self.stream.try_clone()?)
+    }
+
+    /// Corta la conexion, los read_line pendientes terminan devolviendo None.
+    pub fn shutdown(&self) -> io::Result<()> {
+        self.stream.shutdown(Shutdown::Both)
+    }
+}
+
+#[cfg(test)]
+mod client_connection_tests {
+    use super::*;
+    use std::{io::Read, net::TcpListener};
+
+    fn pair() -> (ClientConnection, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let connection = ClientConnection::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        (connection, server)
+    }
+
+    #[test]
+    fn test_lines_are_sent_with_crlf() {
+        let (mut connection, mut server) = pair();
+
+        connection.send("PING :server").unwrap();
+
+        let mut received = [0; 14];
+        server.read_exact(&mut received).unwrap();
+        assert_eq!(&received, b"PING :server\r\n");
+    }
+
+    #[test]
+    fn test_lines_with_breaks_or_nul_are_not_sent() {
+        let (mut connection, mut server) = pair();
+
+        for line in ["PRIVMSG #c :x\r\nQUIT", "PRIVMSG #c :x\nQUIT", "NICK a\0b"] {
+            let err = connection.send(line).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+        connection.send("PING :after").unwrap();
+
+        // Nothing of the rejected lines reached the socket
+        let mut received = [0; 13];
+        server.read_exact(&mut received).unwrap();
+        assert_eq!(&received, b"PING :after\r\n");
+    }
+}
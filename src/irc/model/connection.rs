@@ -1,14 +1,13 @@
 //! Modulo que se centra en las funcionalidades referentes a la representacion de conecciones.
 use std::{
-    io::{self, BufRead, BufReader, Write},
-    net::{Shutdown, TcpStream},
-    sync::{mpsc::Receiver, Arc},
-    time::Duration,
+    io,
+    net::{IpAddr, TcpStream},
+    time::Instant,
 };
 
 use crate::{
     irc::{
-        constants::ERR_REGMISSING,
+        constants::{ERR_REGMISSING, ERR_TOOMANYCOMMANDS},
         message::generic_message::GenericMessage,
         responses::{builder::ResponseBuilder, InternalType, ResponseType},
     },
@@ -16,8 +15,8 @@ use crate::{
 };
 
 use super::{
-    client::Client, server::Server, server_connection::ServerConnection, utils::mt, MTClient,
-    MTServerConnection,
+    client::Client, flood::FloodVerdict, server::Server, server_connection::ServerConnection,
+    utils::mt, writer::ConnectionWriter, MTClient, MTServerConnection,
 };
 
 #[derive(Debug, PartialOrd, PartialEq, Eq)]
@@ -37,7 +36,7 @@ enum ConnectionType {
 pub struct Connection {
     pub password: Option<String>,
     pub conn_nick: Option<String>,
-    pub write_stream: TcpStream,
+    pub write_stream: ConnectionWriter,
     conn_step: ConnectionStep,
     conn_type: ConnectionType,
     pub username: Option<String>,
@@ -46,29 +45,33 @@ pub struct Connection {
     pub realname: Option<String>,
     pub hopcount: Option<u32>,
     pub uplink: Option<String>,
+    pub address: Option<IpAddr>,
 }
 
-impl Connection {
-    pub fn handle_connection(server: Arc<Server>, stream: TcpStream) -> Result<(), ()> {
-        if stream.set_nonblocking(false).is_err() {
-            let _ = stream.shutdown(Shutdown::Both);
-            println!("[SERVER - CONNECTION] Can't properly setup the connection");
-            return Err(());
-        };
-        let read_stream = stream;
-        let write_stream = match read_stream.try_clone() {
-            Ok(v) => v,
-            Err(_) => {
-                println!("[SERVER - CONNECTION] Can't properly setup the connection");
-                let _ = read_stream.shutdown(Shutdown::Both);
-                return Err(());
-            }
-        };
+/// Lo que el reactor tiene que hacer con la conexion despues de procesar una linea.
+#[derive(Debug, PartialEq, Eq)]
+pub enum LineOutcome {
+    Continue,
+    /// La linea paso el control de flood pero no puede ejecutarse hasta el instante indicado,
+    /// las siguientes lineas esperan detras de ella.
+    Hold(Instant),
+    /// El registro termino, la conexion pasa a ser de un cliente o de un server.
+    Upgrade,
+    Close,
+}
+
+/// Conexion ya registrada, lista para procesar mensajes.
+pub enum Registered {
+    Client(MTClient),
+    Server(MTServerConnection),
+}
 
-        let mut conn = Connection {
+impl Connection {
+    pub fn new(stream: &TcpStream) -> io::Result<Connection> {
+        Ok(Connection {
             password: None,
             conn_nick: None,
-            write_stream,
+            write_stream: ConnectionWriter::new(stream.try_clone()?),
             conn_step: ConnectionStep::PasswordNotSetted,
             conn_type: ConnectionType::Unknown,
             username: None,
@@ -77,50 +80,33 @@ impl Connection {
             realname: None,
             hopcount: None,
             uplink: None,
-        };
+            address: stream.peer_addr().ok().map(|a| a.ip()),
+        })
+    }
 
-        conn.handle_initial_connection(server.clone(), &read_stream);
-        match conn.conn_type {
+    /// Termina el registro creando el cliente o la conexion de server.
+    /// Si se corto antes de registrarse no hay nada que crear.
+    pub fn register(self) -> Option<Registered> {
+        match self.conn_type {
             ConnectionType::Unknown => {
-                if let Err(e) = read_stream.shutdown(Shutdown::Both) {
-                    println!(
-                        "[SERVER - CONNECTION] Error while trying to shutdown stream.\n {}",
-                        e
-                    )
-                };
                 println!("[SERVER - CONNECTION] Connection closed before registration finished");
+                None
             }
-            ConnectionType::Client => match Client::from_connection(conn) {
-                Ok(client) => {
-                    Connection::handle_client(server, mt(client), &read_stream)?;
-                }
+            ConnectionType::Client => match Client::from_connection(self) {
+                Ok(client) => Some(Registered::Client(mt(client))),
                 Err(_) => {
                     println!("[SERVER - CONNECTION] Error while creating the client connection");
+                    None
                 }
             },
-            ConnectionType::Server => match ServerConnection::from_connection(conn) {
-                Ok(sv_connection) => {
-                    Connection::handle_server_connection(
-                        server,
-                        mt(sv_connection),
-                        &read_stream,
-                        None,
-                    )?;
-                }
+            ConnectionType::Server => match ServerConnection::from_connection(self) {
+                Ok(sv_connection) => Some(Registered::Server(mt(sv_connection))),
                 Err(_) => {
-                    println!("[SERVER - CONNECTION] Error while creating the client connection")
+                    println!("[SERVER - CONNECTION] Error while creating the client connection");
+                    None
                 }
             },
         }
-
-        if let Err(e) = read_stream.shutdown(Shutdown::Both) {
-            println!(
-                "[SERVER - CONNECTION] Error while trying to shutdown stream.\n {}",
-                e
-            )
-        };
-
-        Ok(())
     }
 
     pub fn set_password(&mut self, password: String) {
@@ -182,185 +168,134 @@ impl Connection {
         self.conn_type != ConnectionType::Client
     }
 
-    fn handle_initial_connection(&mut self, server: Arc<Server>, read_stream: &TcpStream) {
-        let addr = match read_stream.peer_addr() {
-            Ok(sa) => sa.to_string(),
-            Err(_) => "Unknown".to_owned(),
-        };
-
-        let reader = BufReader::new(read_stream);
-        let mut lines = reader.lines();
-
-        let mut reg_done = false;
-        loop {
-            if let Some(line) = lines.next() {
-                let l = match line {
-                    Ok(p) => {
-                        if p.is_empty() {
-                            break;
-                        }
-                        p
-                    }
-                    Err(_) => {
-                        break;
-                    }
-                };
-                println!("[UNREGISTERED - {}]: {} ", addr, l);
+    pub fn handle_registration_line(
+        &mut self,
+        server: &Server,
+        addr: &str,
+        l: &str,
+    ) -> LineOutcome {
+        if l.is_empty() {
+            return LineOutcome::Close;
+        }
+        println!("[UNREGISTERED - {}]: {} ", addr, l);
 
-                let responses = match GenericMessage::parse(&l) {
-                    Ok(v) => v.execute_registration(&server, self),
-                    Err(e) => ResponseBuilder::new().add_from_error(e).build(),
-                };
+        let responses = match GenericMessage::parse(l) {
+            Ok(v) => v.execute_registration(server, self),
+            Err(e) => ResponseBuilder::new().add_from_error(e).build(),
+        };
 
-                for response in responses {
-                    if let ResponseType::InternalResponse(t) = &response {
-                        match *t {
-                            InternalType::Quit => {
-                                return;
-                            }
-                            InternalType::Upgrade => reg_done = true,
-                        }
-                    }
-                    if let Some(res) = response.serialize() {
-                        if let Err(e) = self.write_stream.write(format!("{}\r\n", &res).as_bytes())
-                        {
-                            eprintln!("{}", e);
-                        }
-                    }
+        let mut outcome = LineOutcome::Continue;
+        for response in responses {
+            if let ResponseType::InternalResponse(t) = &response {
+                match *t {
+                    InternalType::Quit => return LineOutcome::Close,
+                    InternalType::Upgrade => outcome = LineOutcome::Upgrade,
                 }
             }
-
-            if reg_done {
-                break;
+            if let Some(res) = response.serialize() {
+                if let Err(e) = self.write_stream.write_line(&res) {
+                    eprintln!("{}", e);
+                }
             }
         }
+
+        outcome
     }
 }
 
 impl Connection {
-    pub fn handle_client(
-        server: Arc<Server>,
+    /// Procesa una linea de un cliente registrado, que llego en arrived, pasando por el control de flood.
+    pub fn handle_client_line(
+        server: &Server,
         client: MTClient,
-        stream: &TcpStream,
-    ) -> Result<(), ()> {
-        let addr = match stream.peer_addr() {
-            Ok(sa) => sa.to_string(),
-            Err(_) => "Unknown".to_owned(),
+        addr: &str,
+        l: &str,
+        arrived: Instant,
+    ) -> LineOutcome {
+        if l.is_empty() {
+            return LineOutcome::Close;
+        }
+        println!("[CLIENT {}]: {} ", addr, l);
+        try_lock!(client).stats.record_received(l.len() + 2);
+
+        let responses = match GenericMessage::parse(l) {
+            Ok(v) => {
+                server.record_command(v.command.to_str(), l.len() + 2, false);
+                match server.check_flood(client.clone(), &v.command, arrived) {
+                    FloodVerdict::Accept => v.execute(server, client.clone()),
+                    FloodVerdict::Delay(wait) => return LineOutcome::Hold(arrived + wait),
+                    FloodVerdict::Drop => ResponseBuilder::new()
+                        .add_content_for_response(
+                            ERR_TOOMANYCOMMANDS,
+                            format!("{} :Too many commands, slow down", v.command.to_str()),
+                        )
+                        .build(),
+                    FloodVerdict::ExcessFlood => {
+                        server.kill_local_client(client, "Excess Flood");
+                        return LineOutcome::Close;
+                    }
+                }
+            }
+            Err(e) => ResponseBuilder::new().add_from_error(e).build(),
         };
 
-        server.introduce_new_client(client.clone());
-
-        let reader = BufReader::new(stream);
-
-        let mut lines = reader.lines();
-        let mut keep_listening = true;
+        Self::respond_to_client(client, responses)
+    }
 
-        loop {
-            if let Some(line) = lines.next() {
-                let l = match line {
-                    Ok(p) => {
-                        if p.is_empty() {
-                            break;
-                        }
-                        p
-                    }
-                    Err(_) => {
-                        break;
-                    }
-                };
-                println!("[CLIENT {}]: {} ", addr, l);
+    /// Ejecuta una linea que el control de flood ya habia aceptado y quedo esperando.
+    pub fn run_held_client_line(server: &Server, client: MTClient, l: &str) -> LineOutcome {
+        let responses = match GenericMessage::parse(l) {
+            Ok(v) => v.execute(server, client.clone()),
+            Err(e) => ResponseBuilder::new().add_from_error(e).build(),
+        };
 
-                let responses = match GenericMessage::parse(&l) {
-                    Ok(v) => v.execute(server.as_ref(), client.clone()),
-                    Err(e) => ResponseBuilder::new().add_from_error(e).build(),
-                };
+        Self::respond_to_client(client, responses)
+    }
 
-                for response in responses {
-                    let mut locked_client = try_lock!(client);
-                    if let ResponseType::InternalResponse(InternalType::Quit) = &response {
-                        keep_listening = false;
-                    }
-                    if let Some(res) = response.serialize() {
-                        if let Err(e) = locked_client.write_to_sv(&res) {
-                            eprintln!("{}", e);
-                        }
-                    }
-                }
+    fn respond_to_client(client: MTClient, responses: Vec<ResponseType>) -> LineOutcome {
+        let mut outcome = LineOutcome::Continue;
+        let mut locked_client = try_lock!(client);
+        for response in responses {
+            if let ResponseType::InternalResponse(InternalType::Quit) = &response {
+                outcome = LineOutcome::Close;
             }
-
-            if !keep_listening {
-                break;
+            if let Some(res) = response.serialize() {
+                if let Err(e) = locked_client.write_to_sv(&res) {
+                    eprintln!("{}", e);
+                }
             }
         }
-
-        Ok(())
+        outcome
     }
 
-    pub fn handle_server_connection(
-        server: Arc<Server>,
+    pub fn handle_server_line(
+        server: &Server,
         server_connection: MTServerConnection,
-        read_stream: &TcpStream,
-        killer: Option<Receiver<ResponseType>>,
-    ) -> Result<(), ()> {
-        let addr = match read_stream.peer_addr() {
-            Ok(sa) => sa.to_string(),
-            Err(_) => "Unknown".to_owned(),
-        };
-
-        if let Err(e) = server.register_server_connection(server_connection.clone()) {
-            server.write_to_server(server_connection, &format!("{} :{}", e.code, e.msg));
-            return Err(());
-        };
-
-        server.introduce_server(server_connection.clone());
-
-        let reader = BufReader::new(read_stream);
-
-        let mut lines = reader.lines();
-        let mut keep_listening = true;
-        loop {
-            if let Some(line) = lines.next() {
-                let l = match line {
-                    Ok(p) => {
-                        if p.is_empty() {
-                            break;
-                        }
-                        p
-                    }
-                    Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
-                        if let Some(tx) = &killer {
-                            if let Ok(ResponseType::InternalResponse(InternalType::Quit)) =
-                                tx.recv_timeout(Duration::from_millis(50))
-                            {
-                                //FOR SOME REASON TRY RECV DOESN'T WORK
-                                break;
-                            }
-                        }
-                        continue;
-                    }
-                    Err(_) => {
-                        break;
-                    }
-                };
-                println!("[FROM SERVER - {}]: {} ", addr, l);
-
-                let responses = match GenericMessage::parse(&l) {
-                    Ok(v) => v.execute_for_server(&server, server_connection.clone()),
-                    Err(e) => ResponseBuilder::new().add_from_error(e).build(),
-                };
-
-                for response in responses {
-                    if let ResponseType::InternalResponse(InternalType::Quit) = &response {
-                        keep_listening = false;
-                    }
-                }
+        addr: &str,
+        l: &str,
+    ) -> LineOutcome {
+        if l.is_empty() {
+            return LineOutcome::Close;
+        }
+        println!("[FROM SERVER - {}]: {} ", addr, l);
+        try_lock!(server_connection)
+            .stats
+            .record_received(l.len() + 2);
+
+        let responses = match GenericMessage::parse(l) {
+            Ok(v) => {
+                server.record_command(v.command.to_str(), l.len() + 2, true);
+                v.execute_for_server(server, server_connection)
             }
+            Err(e) => ResponseBuilder::new().add_from_error(e).build(),
+        };
 
-            if !keep_listening {
-                break;
+        for response in responses {
+            if let ResponseType::InternalResponse(InternalType::Quit) = &response {
+                return LineOutcome::Close;
             }
         }
 
-        Ok(())
+        LineOutcome::Continue
     }
 }
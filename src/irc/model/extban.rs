@@ -0,0 +1,42 @@
+//! Modulo que se centra en las funcionalidades referentes a las mascaras de ban extendidas.
+use crate::irc::message::utils::mask_matches;
+
+/// Datos de un cliente contra los que se comparan las mascaras de los canales (+b, +e, +I, +q).
+/// Ademas de `nick!user@host` se aceptan bans extendidos: `$a` (con cuenta), `$a:mascara`
+/// (cuenta que matchea), `$r:mascara` (realname que matchea), y `$~` adelante los niega,
+/// asi `$~a` son los clientes sin cuenta.
+#[derive(Debug)]
+pub struct BanSubject {
+    pub hostmask: String,
+    pub account: Option<String>,
+    pub realname: String,
+}
+
+impl BanSubject {
+    pub fn matches(&self, mask: &str) -> bool {
+        let (negated, extban) = match mask.strip_prefix("$~") {
+            Some(e) => (true, e),
+            None => match mask.strip_prefix('$') {
+                Some(e) => (false, e),
+                None => return mask_matches(mask, &self.hostmask),
+            },
+        };
+
+        let (kind, pattern) = match extban.split_once(':') {
+            Some((k, p)) => (k, Some(p)),
+            None => (extban, None),
+        };
+
+        let matched = match (kind, pattern) {
+            ("a", None) => self.account.is_some(),
+            ("a", Some(p)) => self
+                .account
+                .as_ref()
+                .is_some_and(|account| mask_matches(p, account)),
+            ("r", Some(p)) => mask_matches(p, &self.realname),
+            _ => return false,
+        };
+
+        matched != negated
+    }
+}
@@ -0,0 +1,169 @@
+//! Modulo que se centra en las funcionalidades referentes a los filtros de mensajes (PRIVMSG y NOTICE).
+use std::fmt::Display;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::irc::message::utils::mask_matches;
+
+/// Que se hace con el mensaje (y con quien lo mando) cuando coincide con una regla.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterAction {
+    /// No se entrega y se le avisa al que lo mando.
+    Block,
+    /// Se entrega, pero se le avisa al que lo mando.
+    Warn,
+    /// Se entrega y se avisa a los operadores.
+    Notify,
+    /// No se entrega y se desconecta al que lo mando.
+    Kill,
+    /// No se entrega y se banea al host del que lo mando por los minutos indicados.
+    Ban(u64),
+}
+
+impl FilterAction {
+    fn parse(action: &str) -> Result<Self, String> {
+        match action.split_once(':') {
+            Some(("ban", minutes)) => match minutes.parse::<u64>() {
+                Ok(m) if m > 0 => Ok(FilterAction::Ban(m)),
+                _ => Err(format!("{} is not a valid ban duration", minutes)),
+            },
+            Some(_) => Err(format!("unknown action {}", action)),
+            None => match action {
+                "block" => Ok(FilterAction::Block),
+                "warn" => Ok(FilterAction::Warn),
+                "notify" => Ok(FilterAction::Notify),
+                "kill" => Ok(FilterAction::Kill),
+                _ => Err(format!("unknown action {}", action)),
+            },
+        }
+    }
+
+    /// Si el mensaje igual llega a destino.
+    pub fn delivers(&self) -> bool {
+        matches!(self, FilterAction::Warn | FilterAction::Notify)
+    }
+
+    /// Si hay que avisarle a los operadores.
+    pub fn notifies_operators(&self) -> bool {
+        matches!(
+            self,
+            FilterAction::Notify | FilterAction::Kill | FilterAction::Ban(_)
+        )
+    }
+}
+
+impl Display for FilterAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterAction::Block => write!(f, "block"),
+            FilterAction::Warn => write!(f, "warn"),
+            FilterAction::Notify => write!(f, "notify"),
+            FilterAction::Kill => write!(f, "kill"),
+            FilterAction::Ban(minutes) => write!(f, "ban:{}", minutes),
+        }
+    }
+}
+
+/// Patron de una regla: una expresion regular si se escribe entre barras, si no una mascara
+/// con comodines. Ninguno distingue mayusculas de minusculas.
+#[derive(Debug, Clone)]
+pub enum FilterPattern {
+    Glob(String),
+    Regex(Regex),
+}
+
+impl FilterPattern {
+    fn parse(pattern: &str) -> Result<Self, String> {
+        if pattern.len() > 1 && pattern.starts_with('/') && pattern.ends_with('/') {
+            return match RegexBuilder::new(&pattern[1..pattern.len() - 1])
+                .case_insensitive(true)
+                .build()
+            {
+                Ok(r) => Ok(FilterPattern::Regex(r)),
+                Err(e) => Err(format!("invalid regex {}: {}", pattern, e)),
+            };
+        }
+
+        Ok(FilterPattern::Glob(pattern.to_owned()))
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        match self {
+            FilterPattern::Glob(mask) => mask_matches(mask, text),
+            FilterPattern::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+impl Display for FilterPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterPattern::Glob(mask) => write!(f, "{}", mask),
+            FilterPattern::Regex(regex) => write!(f, "/{}/", regex.as_str()),
+        }
+    }
+}
+
+/// Regla del archivo de filtros, una por linea:
+/// "accion texto destino origen :motivo", por ejemplo "ban:30 /buy\s+cheap/ #* *!*@* :Spam".
+/// El origen se compara contra el nick!user@host del que manda el mensaje.
+#[derive(Debug, Clone)]
+pub struct FilterRule {
+    pub action: FilterAction,
+    pub text: FilterPattern,
+    pub target: FilterPattern,
+    pub source: FilterPattern,
+    pub reason: String,
+}
+
+impl FilterRule {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let (rule, reason) = match line.split_once(" :") {
+            Some((r, reason)) => (r, reason.trim()),
+            None => (line, "Message filtered"),
+        };
+
+        let fields: Vec<&str> = rule.split_whitespace().collect();
+        if fields.len() != 4 {
+            return Err("expected action text target source :reason".to_owned());
+        }
+
+        Ok(FilterRule {
+            action: FilterAction::parse(fields[0])?,
+            text: FilterPattern::parse(fields[1])?,
+            target: FilterPattern::parse(fields[2])?,
+            source: FilterPattern::parse(fields[3])?,
+            reason: reason.to_owned(),
+        })
+    }
+
+    pub fn matches(&self, source: &str, target: &str, text: &str) -> bool {
+        self.source.matches(source) && self.target.matches(target) && self.text.matches(text)
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "{} {} {} {} :{}",
+            self.action, self.text, self.target, self.source, self.reason
+        )
+    }
+}
+
+/// Lee las reglas del contenido del archivo de filtros, ignorando lineas vacias y comentarios.
+pub fn parse_filters(content: &str) -> Result<Vec<FilterRule>, String> {
+    let mut rules = vec![];
+
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match FilterRule::parse(line) {
+            Ok(rule) => rules.push(rule),
+            Err(e) => return Err(format!("Line {}: {}", number + 1, e)),
+        }
+    }
+
+    Ok(rules)
+}
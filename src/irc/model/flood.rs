@@ -0,0 +1,183 @@
+//! Modulo que se centra en las funcionalidades referentes al control de flood de los clientes.
+use std::time::{Duration, Instant};
+
+use crate::irc::message::Command;
+
+use super::server::ConnectionLimits;
+
+/// Que hacer con un comando segun la penalizacion acumulada por el cliente.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FloodVerdict {
+    Accept,
+    /// Se procesa cuando pasa el tiempo indicado desde que llego el comando.
+    Delay(Duration),
+    /// Se descarta, el cliente tendria que esperar demasiado.
+    Drop,
+    /// El cliente descarto demasiados comandos seguidos y se lo desconecta.
+    ExcessFlood,
+}
+
+/// Penalizacion de un cliente. Cada comando adelanta un reloj virtual segun su costo,
+/// mientras el reloj no se adelante mas que la rafaga permitida los comandos pasan sin esperar.
+/// Los comandos que llegan mientras se espera a otro se encolan y acumulan su espera.
+#[derive(Debug)]
+pub struct FloodState {
+    clock: Instant,
+    drops: usize,
+}
+
+impl Default for FloodState {
+    fn default() -> Self {
+        FloodState {
+            clock: Instant::now(),
+            drops: 0,
+        }
+    }
+}
+
+impl FloodState {
+    pub fn charge(&mut self, cost: u32, limits: &ConnectionLimits, now: Instant) -> FloodVerdict {
+        if cost == 0 || limits.flood_rate == 0 {
+            return FloodVerdict::Accept;
+        }
+
+        let per_unit = Duration::from_secs(1) / limits.flood_rate as u32;
+        let burst = per_unit * limits.flood_burst as u32;
+        let clock = self.clock.max(now) + per_unit * cost;
+        let wait = clock.saturating_duration_since(now + burst);
+
+        if wait > Duration::from_secs(limits.flood_delay as u64) {
+            self.drops += 1;
+            if self.drops > limits.flood_drops {
+                return FloodVerdict::ExcessFlood;
+            }
+            return FloodVerdict::Drop;
+        }
+
+        self.clock = clock;
+        self.drops = 0;
+        if wait.is_zero() {
+            FloodVerdict::Accept
+        } else {
+            FloodVerdict::Delay(wait)
+        }
+    }
+}
+
+/// Costo de cada comando, los que le generan mas trabajo al server (o a la red) cuestan mas.
+pub fn command_cost(command: &Command) -> u32 {
+    match command {
+        Command::Quit | Command::Password | Command::User | Command::Cap => 0,
+        Command::Nick | Command::List | Command::Knock => 3,
+        Command::Join
+        | Command::Part
+        | Command::Who
+        | Command::WhoIs
+        | Command::Whowas
+        | Command::Names
+        | Command::Invite
+        | Command::Away => 2,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod flood_tests {
+    use super::*;
+
+    /// Dos unidades por segundo con rafaga de 10: cada comando de costo 1 adelanta medio segundo.
+    fn limits() -> ConnectionLimits {
+        ConnectionLimits {
+            flood_rate: 2,
+            flood_burst: 10,
+            flood_delay: 5,
+            flood_drops: 3,
+            ..ConnectionLimits::default()
+        }
+    }
+
+    #[test]
+    fn test_burst_passes_and_then_the_wait_accumulates() {
+        let mut state = FloodState::default();
+        let now = Instant::now();
+
+        for _ in 0..10 {
+            assert_eq!(state.charge(1, &limits(), now), FloodVerdict::Accept);
+        }
+        assert_eq!(
+            state.charge(1, &limits(), now),
+            FloodVerdict::Delay(Duration::from_millis(500))
+        );
+        assert_eq!(
+            state.charge(3, &limits(), now),
+            FloodVerdict::Delay(Duration::from_millis(2000))
+        );
+    }
+
+    #[test]
+    fn test_penalty_is_paid_back_over_time() {
+        let mut state = FloodState::default();
+        let now = Instant::now();
+        for _ in 0..12 {
+            state.charge(1, &limits(), now);
+        }
+
+        // Two units back per second, the client is at the edge of the burst again
+        let later = now + Duration::from_millis(1500);
+        assert_eq!(state.charge(1, &limits(), later), FloodVerdict::Accept);
+        assert_eq!(
+            state.charge(1, &limits(), later),
+            FloodVerdict::Delay(Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn test_commands_over_the_delay_are_dropped_until_excess_flood() {
+        let mut state = FloodState::default();
+        let now = Instant::now();
+        // 10 free plus 10 delayed, the last one waits exactly the maximum
+        for _ in 0..20 {
+            assert_ne!(state.charge(1, &limits(), now), FloodVerdict::Drop);
+        }
+
+        for _ in 0..3 {
+            assert_eq!(state.charge(1, &limits(), now), FloodVerdict::Drop);
+        }
+        assert_eq!(state.charge(1, &limits(), now), FloodVerdict::ExcessFlood);
+    }
+
+    #[test]
+    fn test_accepting_again_resets_the_drops() {
+        let mut state = FloodState::default();
+        let now = Instant::now();
+        for _ in 0..23 {
+            state.charge(1, &limits(), now);
+        }
+
+        let later = now + Duration::from_millis(5500);
+        assert_eq!(state.charge(1, &limits(), later), FloodVerdict::Accept);
+        for _ in 0..10 {
+            state.charge(1, &limits(), later);
+        }
+        assert_eq!(state.charge(1, &limits(), later), FloodVerdict::Drop);
+    }
+
+    #[test]
+    fn test_free_commands_and_disabled_control_always_pass() {
+        let mut state = FloodState::default();
+        let now = Instant::now();
+        let disabled = ConnectionLimits {
+            flood_rate: 0,
+            ..limits()
+        };
+
+        for _ in 0..100 {
+            assert_eq!(state.charge(0, &limits(), now), FloodVerdict::Accept);
+            assert_eq!(state.charge(5, &disabled, now), FloodVerdict::Accept);
+        }
+        assert_eq!(command_cost(&Command::Quit), 0);
+        assert_eq!(command_cost(&Command::Nick), 3);
+        assert_eq!(command_cost(&Command::Join), 2);
+        assert_eq!(command_cost(&Command::PrivateMessage), 1);
+    }
+}
@@ -0,0 +1,128 @@
+//! Modulo que se centra en las funcionalidades referentes a las claves de nicknames y canales.
+use std::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+use crate::irc::message::utils::casefold;
+
+/// Nombre de un cliente o canal usado como clave. Conserva el nombre tal cual se
+/// escribio, pero se compara y hashea con el casemapping rfc1459: `Valen` y `valen`,
+/// o `#Rust` y `#rust`, son la misma clave.
+#[derive(Debug, Clone)]
+pub struct IrcKey {
+    name: String,
+    folded: String,
+}
+
+impl IrcKey {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            folded: casefold(name),
+        }
+    }
+}
+
+impl PartialEq for IrcKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.folded == other.folded
+    }
+}
+
+impl Eq for IrcKey {}
+
+impl Hash for IrcKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.folded.hash(state);
+    }
+}
+
+impl PartialOrd for IrcKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for IrcKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.folded.cmp(&other.folded)
+    }
+}
+
+impl Deref for IrcKey {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for IrcKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl From<&str> for IrcKey {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for IrcKey {
+    fn from(name: String) -> Self {
+        Self {
+            folded: casefold(&name),
+            name,
+        }
+    }
+}
+
+impl From<&String> for IrcKey {
+    fn from(name: &String) -> Self {
+        Self::new(name)
+    }
+}
+
+#[cfg(test)]
+mod irc_key_tests {
+    use std::collections::{hash_map::DefaultHasher, HashMap};
+
+    use super::*;
+
+    fn hash(key: &IrcKey) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_rfc1459_specials_are_the_same_key() {
+        assert_eq!(IrcKey::from("nick{}|^"), IrcKey::from("NICK[]\\~"));
+        assert_eq!(IrcKey::from("#Rust"), IrcKey::from("#rust"));
+        assert_ne!(IrcKey::from("nick{}"), IrcKey::from("nick()"));
+    }
+
+    #[test]
+    fn test_equal_keys_hash_the_same() {
+        let lower = IrcKey::from("valen{}|^");
+        let upper = IrcKey::from("VALEN[]\\~");
+        assert_eq!(hash(&lower), hash(&upper));
+
+        let mut clients = HashMap::new();
+        clients.insert(lower, 1);
+        assert_eq!(clients.get(&upper), Some(&1));
+        clients.insert(upper, 2);
+        assert_eq!(clients.len(), 1);
+    }
+
+    #[test]
+    fn test_keeps_the_name_as_written() {
+        let key = IrcKey::from("Valen[]".to_owned());
+        assert_eq!(&*key, "Valen[]");
+        assert_eq!(key.to_string(), "Valen[]");
+        assert!(IrcKey::from("alice") < IrcKey::from("BOB"));
+    }
+}
@@ -0,0 +1,50 @@
+//! Modulo que se centra en las funcionalidades referentes a los contadores de trafico de una conexion.
+use std::time::Instant;
+
+/// Mensajes y bytes enviados y recibidos por una conexion desde que se abrio.
+#[derive(Debug)]
+pub struct LinkStats {
+    pub opened_at: Instant,
+    pub sent_messages: u64,
+    pub sent_bytes: u64,
+    pub received_messages: u64,
+    pub received_bytes: u64,
+}
+
+impl Default for LinkStats {
+    fn default() -> Self {
+        LinkStats {
+            opened_at: Instant::now(),
+            sent_messages: 0,
+            sent_bytes: 0,
+            received_messages: 0,
+            received_bytes: 0,
+        }
+    }
+}
+
+impl LinkStats {
+    pub fn record_sent(&mut self, bytes: usize) {
+        self.sent_messages += 1;
+        self.sent_bytes += bytes as u64;
+    }
+
+    pub fn record_received(&mut self, bytes: usize) {
+        self.received_messages += 1;
+        self.received_bytes += bytes as u64;
+    }
+
+    /// Columnas de RPL_STATSLINKINFO a partir de la cola de envio:
+    /// "<sendq> <sent msgs> <sent KB> <recv msgs> <recv KB> <segundos abierta>".
+    pub fn describe(&self, sendq: usize) -> String {
+        format!(
+            "{} {} {} {} {} {}",
+            sendq,
+            self.sent_messages,
+            self.sent_bytes / 1024,
+            self.received_messages,
+            self.received_bytes / 1024,
+            self.opened_at.elapsed().as_secs()
+        )
+    }
+}
@@ -8,11 +8,21 @@ use self::{
 pub mod channel;
 pub mod client;
 pub mod client_account;
+pub mod client_connection;
 pub mod connection;
+pub mod extban;
+pub mod filter;
+pub mod flood;
+pub mod irc_key;
+pub mod link_stats;
+pub mod reactor;
 pub mod server;
+pub mod server_ban;
 pub mod server_connection;
+pub mod session;
 pub mod utils;
 pub mod workers;
+pub mod writer;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ConnectionError {
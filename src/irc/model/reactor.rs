@@ -0,0 +1,625 @@
+//! Modulo que se centra en las funcionalidades referentes al reactor que atiende todas las conexiones del server.
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    io::{self, ErrorKind, Read, Write},
+    mem,
+    net::{IpAddr, Shutdown, TcpListener, TcpStream},
+    os::unix::io::AsRawFd,
+    sync::Arc,
+    time::Instant,
+};
+
+use mio::{unix::SourceFd, Events, Interest, Poll, Token, Waker};
+
+use crate::{irc::message::generic_message::MESSAGE_LIMIT, try_lock};
+
+use super::{
+    connection::{Connection, LineOutcome, Registered},
+    server::Server,
+    writer::SENDQ_EXCEEDED,
+    MTClient, MTServerConnection,
+};
+
+const LISTENER: Token = Token(0);
+const WAKER: Token = Token(1);
+const FIRST_PEER: usize = 2;
+
+const EVENTS_CAPACITY: usize = 1024;
+const READ_CHUNK: usize = 4096;
+/// Lineas leidas que puede acumular una conexion, por ejemplo mientras el control de flood
+/// demora una. Con la cola llena se deja de leer el socket hasta que se procesen.
+const MAX_QUEUED_LINES: usize = 64;
+/// Lo que se guarda de cada linea: un byte mas que lo que acepta el parser, para que
+/// una linea mas larga igual se rechace por larga. El resto de la linea se descarta.
+const MAX_LINE_LEN: usize = MESSAGE_LIMIT + 1;
+/// Motivo del netsplit cuando un link se cierra sin SQUIT.
+pub const LINK_LOST: &str = "Remote host closed the connection";
+
+enum PeerState {
+    Registering(Box<Connection>),
+    Client(MTClient),
+    Server(MTServerConnection),
+    Closed,
+}
+
+enum Progress {
+    Open,
+    /// Hay una linea demorada por el control de flood hasta el instante indicado.
+    Wait(Instant),
+    Closed,
+}
+
+/// Conexion atendida por el reactor: el socket no bloqueante, lo leido que todavia
+/// no forma una linea completa y las lineas que esperan ser procesadas.
+struct Peer {
+    stream: TcpStream,
+    addr: String,
+    ip: Option<IpAddr>,
+    input: Vec<u8>,
+    lines: VecDeque<(String, Instant)>,
+    held: Option<(String, Instant)>,
+    /// Se dejo de leer con datos en el socket porque la cola de lineas estaba llena.
+    throttled: bool,
+    eof: bool,
+    state: PeerState,
+}
+
+impl Peer {
+    fn new(stream: TcpStream, state: PeerState) -> Self {
+        let peer_addr = stream.peer_addr().ok();
+        Self {
+            stream,
+            addr: match peer_addr {
+                Some(sa) => sa.to_string(),
+                None => "Unknown".to_owned(),
+            },
+            ip: peer_addr.map(|a| a.ip()),
+            input: vec![],
+            lines: VecDeque::new(),
+            held: None,
+            throttled: false,
+            eof: false,
+            state,
+        }
+    }
+
+    /// Lee lo disponible en el socket y separa las lineas completas, hasta llenar la cola.
+    fn read(&mut self) {
+        let mut chunk = [0; READ_CHUNK];
+        self.throttled = false;
+        loop {
+            if self.lines.len() >= MAX_QUEUED_LINES {
+                // The rest waits in the socket, the client can't send faster than it's served
+                self.throttled = true;
+                break;
+            }
+            match self.stream.read(&mut chunk) {
+                Ok(0) => {
+                    self.eof = true;
+                    break;
+                }
+                Ok(read) => {
+                    self.input.extend_from_slice(&chunk[..read]);
+                    self.split_lines();
+                    // Until its end arrives, a line can't grow past the limit
+                    self.input.truncate(MAX_LINE_LEN);
+                }
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => {
+                    self.eof = true;
+                    break;
+                }
+            }
+        }
+
+        if self.eof && !self.input.is_empty() {
+            let line = mem::take(&mut self.input);
+            self.push_line(&line, Instant::now());
+        }
+    }
+
+    fn split_lines(&mut self) {
+        let arrived = Instant::now();
+        while let Some(end) = self.input.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.input.drain(..=end).collect();
+            self.push_line(&line[..end], arrived);
+        }
+    }
+
+    /// Si se dejo de leer por la cola llena y ya hay lugar, hay que volver a leer: el socket
+    /// no vuelve a avisar que esta listo por lo que ya tenia.
+    fn can_resume(&self) -> bool {
+        self.throttled && self.lines.len() < MAX_QUEUED_LINES
+    }
+
+    fn push_line(&mut self, line: &[u8], arrived: Instant) {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let line = &line[..line.len().min(MAX_LINE_LEN)];
+        self.lines
+            .push_back((String::from_utf8_lossy(line).to_string(), arrived));
+    }
+
+    /// Escribe lo que haya quedado pendiente en el escritor de la conexion.
+    fn flush(&mut self) {
+        let result = match &mut self.state {
+            PeerState::Registering(connection) => connection.write_stream.flush(),
+            PeerState::Client(client) => match &mut try_lock!(client).stream {
+                Some(writer) => writer.flush(),
+                None => Ok(()),
+            },
+            PeerState::Server(sv_connection) => match &mut try_lock!(sv_connection).write_stream {
+                Some(writer) => writer.flush(),
+                None => Ok(()),
+            },
+            PeerState::Closed => Ok(()),
+        };
+
+        if let Err(e) = result {
+            println!(
+                "[SERVER - CONNECTION] Write to {} failed.\n{}",
+                self.addr, e
+            );
+        }
+    }
+
+    /// Procesa las lineas leidas en orden, hasta que se terminan o una queda demorada.
+    fn process(&mut self, server: &Server) -> Progress {
+        loop {
+            if let Some((line, due)) = self.held.take() {
+                if due > Instant::now() {
+                    self.held = Some((line, due));
+                    return Progress::Wait(due);
+                }
+                if let PeerState::Client(client) = &self.state {
+                    let outcome = Connection::run_held_client_line(server, client.clone(), &line);
+                    if outcome == LineOutcome::Close {
+                        return Progress::Closed;
+                    }
+                }
+                continue;
+            }
+
+            let (line, arrived) = match self.lines.pop_front() {
+                Some(l) => l,
+                None => break,
+            };
+
+            let outcome = match &mut self.state {
+                PeerState::Registering(connection) => {
+                    connection.handle_registration_line(server, &self.addr, &line)
+                }
+                PeerState::Client(client) => Connection::handle_client_line(
+                    server,
+                    client.clone(),
+                    &self.addr,
+                    &line,
+                    arrived,
+                ),
+                PeerState::Server(sv_connection) => {
+                    Connection::handle_server_line(server, sv_connection.clone(), &self.addr, &line)
+                }
+                PeerState::Closed => LineOutcome::Close,
+            };
+
+            match outcome {
+                LineOutcome::Continue => {}
+                LineOutcome::Hold(due) => self.held = Some((line, due)),
+                LineOutcome::Upgrade => {
+                    if !self.register(server) {
+                        return Progress::Closed;
+                    }
+                }
+                LineOutcome::Close => return Progress::Closed,
+            }
+        }
+
+        if self.eof {
+            return Progress::Closed;
+        }
+        Progress::Open
+    }
+
+    /// Reemplaza la conexion en registro por el cliente o el server que se registro.
+    fn register(&mut self, server: &Server) -> bool {
+        let connection = match mem::replace(&mut self.state, PeerState::Closed) {
+            PeerState::Registering(connection) => connection,
+            other => {
+                self.state = other;
+                return true;
+            }
+        };
+
+        match connection.register() {
+            Some(Registered::Client(client)) => {
+                server.introduce_new_client(client.clone());
+                self.state = PeerState::Client(client);
+                true
+            }
+            Some(Registered::Server(sv_connection)) => {
+                if !start_server_link(server, sv_connection.clone()) {
+                    return false;
+                }
+                self.state = PeerState::Server(sv_connection);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn close(self, server: &Server) {
+        // A peer that couldn't keep up had its reading cut by its writer, it leaves the network here
+        match &self.state {
+            PeerState::Client(client) if try_lock!(client).sendq_exceeded() => {
+                server.kill_local_client(client.clone(), SENDQ_EXCEEDED)
+            }
+            // A link still registered went down without SQUIT, the rest of the net sees a netsplit
+            PeerState::Server(sv_connection) => {
+                let reason = if try_lock!(sv_connection).sendq_exceeded() {
+                    SENDQ_EXCEEDED
+                } else {
+                    LINK_LOST
+                };
+                server.lose_server_link(sv_connection, reason);
+            }
+            _ => {}
+        }
+
+        if let Err(e) = self.stream.shutdown(Shutdown::Both) {
+            if e.kind() != ErrorKind::NotConnected {
+                println!(
+                    "[SERVER - CONNECTION] Error while trying to shutdown stream.\n {}",
+                    e
+                )
+            }
+        };
+        if let Some(ip) = self.ip {
+            server.release_connection(ip);
+        }
+    }
+}
+
+/// Da de alta un link con otro server ya registrado y le manda el estado de la red.
+fn start_server_link(server: &Server, sv_connection: MTServerConnection) -> bool {
+    let sendq = server.limits().sendq_server;
+    if let Some(writer) = &mut try_lock!(sv_connection).write_stream {
+        writer.set_limit(sendq);
+    }
+
+    if let Err(e) = server.register_server_connection(sv_connection.clone()) {
+        server.write_to_server(sv_connection, &format!("{} :{}", e.code, e.msg));
+        return false;
+    };
+
+    server.introduce_server(sv_connection);
+    true
+}
+
+fn refuse_connection(mut stream: TcpStream, reason: &str) {
+    println!("[SERVER] Refused connection: {}", reason);
+    let _ = stream.write(format!("ERROR :Closing Link: {}\r\n", reason).as_bytes());
+    let _ = stream.shutdown(Shutdown::Both);
+}
+
+/// Atiende el listener y todas las conexiones del server desde un unico hilo, esperando
+/// a que los sockets esten listos en lugar de tener un hilo bloqueado por conexion.
+pub struct Reactor {
+    poll: Poll,
+    events: Events,
+    listener: TcpListener,
+    waker: Arc<Waker>,
+    peers: HashMap<Token, Peer>,
+    timers: BinaryHeap<Reverse<(Instant, Token)>>,
+    next_token: usize,
+}
+
+impl Reactor {
+    pub fn bind(host: &str) -> io::Result<Reactor> {
+        Self::from_listener(TcpListener::bind(host)?)
+    }
+
+    /// Reactor que acepta conexiones de un listener ya abierto.
+    pub fn from_listener(listener: TcpListener) -> io::Result<Reactor> {
+        listener.set_nonblocking(true)?;
+
+        let poll = Poll::new()?;
+        poll.registry().register(
+            &mut SourceFd(&listener.as_raw_fd()),
+            LISTENER,
+            Interest::READABLE,
+        )?;
+        let waker = Arc::new(Waker::new(poll.registry(), WAKER)?);
+
+        Ok(Reactor {
+            poll,
+            events: Events::with_capacity(EVENTS_CAPACITY),
+            listener,
+            waker,
+            peers: HashMap::new(),
+            timers: BinaryHeap::new(),
+            next_token: FIRST_PEER,
+        })
+    }
+
+    /// Permite despertar al reactor desde otro hilo, por ejemplo para que atienda un comando.
+    pub fn waker(&self) -> Arc<Waker> {
+        self.waker.clone()
+    }
+
+    /// Agrega el link con un server al que este se conecto y que ya lo registro.
+    pub fn add_server_link(
+        &mut self,
+        server: &Server,
+        sv_connection: MTServerConnection,
+        stream: TcpStream,
+    ) {
+        if let Err(e) = stream.set_nonblocking(true) {
+            println!(
+                "[SERVER - CONNECTION] Can't properly setup the connection\n{}",
+                e
+            );
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+        if !start_server_link(server, sv_connection.clone()) {
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+        self.add_peer(server, Peer::new(stream, PeerState::Server(sv_connection)));
+    }
+
+    /// Espera a que alguna conexion este lista, o a que venza la demora de una linea, y la atiende.
+    /// No espera mas alla de deadline, para que el loop principal pueda hacer sus tareas periodicas.
+    pub fn turn(&mut self, server: &Server, deadline: Instant) -> io::Result<()> {
+        let wake_at = match self.timers.peek() {
+            Some(Reverse((due, _))) => deadline.min(*due),
+            None => deadline,
+        };
+        let timeout = wake_at.saturating_duration_since(Instant::now());
+
+        match self.poll.poll(&mut self.events, Some(timeout)) {
+            Ok(()) => {}
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => return Ok(()),
+            Err(e) => return Err(e),
+        }
+
+        let ready: Vec<(Token, bool, bool)> = self
+            .events
+            .iter()
+            .map(|e| {
+                let readable = e.is_readable() || e.is_read_closed() || e.is_error();
+                (e.token(), readable, e.is_writable())
+            })
+            .collect();
+
+        for (token, readable, writable) in ready {
+            match token {
+                LISTENER => self.accept(server),
+                // Only wakes the loop up, the caller checks for pending commands
+                WAKER => {}
+                _ => {
+                    if let Some(peer) = self.peers.get_mut(&token) {
+                        if writable {
+                            peer.flush();
+                        }
+                        if readable {
+                            peer.read();
+                            self.process(server, token);
+                        }
+                    }
+                }
+            }
+        }
+
+        let now = Instant::now();
+        while let Some(Reverse((due, token))) = self.timers.peek().copied() {
+            if due > now {
+                break;
+            }
+            self.timers.pop();
+            self.process(server, token);
+        }
+
+        Ok(())
+    }
+
+    fn accept(&mut self, server: &Server) {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, address)) => {
+                    if let Err(reason) = server.accept_connection(address.ip()) {
+                        refuse_connection(stream, &reason);
+                        continue;
+                    }
+                    self.add_connection(server, stream, address.ip());
+                }
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                // IO error, can't do much about it
+                Err(e) => {
+                    println!("[SERVER] IO Error.\n[SERVER - IO ERROR]{}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn add_connection(&mut self, server: &Server, stream: TcpStream, ip: IpAddr) {
+        let mut connection = match stream
+            .set_nonblocking(true)
+            .and_then(|_| Connection::new(&stream))
+        {
+            Ok(c) => c,
+            Err(_) => {
+                println!("[SERVER - CONNECTION] Can't properly setup the connection");
+                let _ = stream.shutdown(Shutdown::Both);
+                server.release_connection(ip);
+                return;
+            }
+        };
+
+        // Client limit from the start, a server link gets its own once it registers
+        let sendq = server.limits().sendq_client;
+        connection.write_stream.set_limit(sendq);
+
+        self.add_peer(
+            server,
+            Peer::new(stream, PeerState::Registering(Box::new(connection))),
+        );
+    }
+
+    fn add_peer(&mut self, server: &Server, peer: Peer) {
+        let token = Token(self.next_token);
+        self.next_token += 1;
+
+        if let Err(e) = self.poll.registry().register(
+            &mut SourceFd(&peer.stream.as_raw_fd()),
+            token,
+            Interest::READABLE | Interest::WRITABLE,
+        ) {
+            println!("[SERVER - CONNECTION] Can't watch the connection\n{}", e);
+            peer.close(server);
+            return;
+        }
+
+        self.peers.insert(token, peer);
+    }
+
+    fn process(&mut self, server: &Server, token: Token) {
+        let progress = loop {
+            let peer = match self.peers.get_mut(&token) {
+                Some(peer) => peer,
+                None => return,
+            };
+            let progress = peer.process(server);
+            if matches!(progress, Progress::Closed) || !peer.can_resume() {
+                break progress;
+            }
+            peer.read();
+        };
+
+        match progress {
+            Progress::Open => {}
+            Progress::Wait(due) => self.timers.push(Reverse((due, token))),
+            Progress::Closed => self.close(server, token),
+        }
+    }
+
+    fn close(&mut self, server: &Server, token: Token) {
+        if let Some(peer) = self.peers.remove(&token) {
+            let _ = self
+                .poll
+                .registry()
+                .deregister(&mut SourceFd(&peer.stream.as_raw_fd()));
+            peer.close(server);
+        }
+    }
+}
+
+#[cfg(test)]
+mod reactor_tests {
+    use std::{thread, time::Duration};
+
+    use super::*;
+    use crate::irc::message::generic_message::GenericMessage;
+
+    /// Peer del lado del server y el socket del cliente que le escribe.
+    fn peer() -> (Peer, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let remote = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        stream.set_nonblocking(true).unwrap();
+        (Peer::new(stream, PeerState::Closed), remote)
+    }
+
+    /// Lee hasta que se cumpla la condicion, lo escrito puede tardar en llegar.
+    fn read_until<P: Fn(&Peer) -> bool>(peer: &mut Peer, condition: P) {
+        for _ in 0..100 {
+            peer.read();
+            if condition(peer) {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("The peer never got there");
+    }
+
+    fn take_lines(peer: &mut Peer) -> Vec<String> {
+        peer.lines.drain(..).map(|(line, _)| line).collect()
+    }
+
+    #[test]
+    fn test_lines_are_split_across_reads() {
+        let (mut peer, mut remote) = peer();
+
+        remote.write_all(b"PRIV").unwrap();
+        read_until(&mut peer, |p| p.input == b"PRIV");
+        assert!(peer.lines.is_empty());
+
+        remote.write_all(b"MSG alice :hi\r\nPING a\nPI").unwrap();
+        read_until(&mut peer, |p| p.lines.len() == 2);
+        assert_eq!(take_lines(&mut peer), vec!["PRIVMSG alice :hi", "PING a"]);
+        assert_eq!(peer.input, b"PI");
+
+        remote.write_all(b"NG b\r").unwrap();
+        read_until(&mut peer, |p| p.input == b"PING b\r");
+        remote.write_all(b"\n").unwrap();
+        read_until(&mut peer, |p| !p.lines.is_empty());
+        assert_eq!(take_lines(&mut peer), vec!["PING b"]);
+        assert!(peer.input.is_empty());
+    }
+
+    #[test]
+    fn test_unfinished_line_is_kept_until_the_connection_closes() {
+        let (mut peer, mut remote) = peer();
+
+        remote.write_all(b"PING a\r\nQUIT :bye").unwrap();
+        remote.shutdown(Shutdown::Write).unwrap();
+        read_until(&mut peer, |p| p.eof);
+
+        assert_eq!(take_lines(&mut peer), vec!["PING a", "QUIT :bye"]);
+        assert!(peer.input.is_empty());
+    }
+
+    #[test]
+    fn test_long_lines_are_cut_without_buffering_them() {
+        let (mut peer, mut remote) = peer();
+
+        let long = "A".repeat(READ_CHUNK * 4);
+        remote.write_all(long.as_bytes()).unwrap();
+        read_until(&mut peer, |p| p.input.len() == MAX_LINE_LEN);
+        thread::sleep(Duration::from_millis(50));
+        peer.read();
+        assert_eq!(peer.input.len(), MAX_LINE_LEN);
+
+        remote.write_all(b"AAAA\r\nPING a\r\n").unwrap();
+        read_until(&mut peer, |p| p.lines.len() == 2);
+        let lines = take_lines(&mut peer);
+        assert_eq!(lines[0], long[..MAX_LINE_LEN]);
+        assert_eq!(lines[1], "PING a");
+        assert!(GenericMessage::parse(&lines[0]).is_err());
+    }
+
+    #[test]
+    fn test_reading_stops_while_the_queue_is_full() {
+        let (mut peer, mut remote) = peer();
+        remote
+            .write_all("PING queued\r\n".repeat(1000).as_bytes())
+            .unwrap();
+
+        read_until(&mut peer, |p| p.throttled);
+        let queued = take_lines(&mut peer).len();
+        assert!((MAX_QUEUED_LINES..1000).contains(&queued));
+        assert!(peer.can_resume());
+
+        let mut total = queued;
+        while total < 1000 {
+            read_until(&mut peer, |p| !p.lines.is_empty());
+            assert!(peer.lines.len() <= MAX_QUEUED_LINES + READ_CHUNK / "PING queued\r\n".len());
+            total += take_lines(&mut peer).len();
+        }
+        assert_eq!(total, 1000);
+        assert!(!peer.can_resume());
+    }
+}
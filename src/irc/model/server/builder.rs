@@ -0,0 +1,325 @@
+//! Modulo que se centra en las funcionalidades referentes a correr un server dentro de otro programa.
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    net::{SocketAddr, TcpListener},
+    path::PathBuf,
+    sync::{
+        mpsc::{channel, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use mio::Waker;
+
+use crate::irc::model::{
+    filter::{parse_filters, FilterRule},
+    reactor::Reactor,
+    workers::ServerCommand,
+};
+
+use super::{ConnectionLimits, Server, ServerFiles};
+
+/// Arma un server para correrlo en un hilo del programa que lo usa, sin leer comandos de la
+/// consola. Por defecto escucha en un puerto libre de 127.0.0.1, con los limites por defecto,
+/// sin filtros, sin MOTD y sin guardar el estado en disco. Solo lee los archivos que se le dan.
+#[derive(Debug)]
+pub struct ServerBuilder {
+    address: String,
+    listener: Option<TcpListener>,
+    limits: ConnectionLimits,
+    filters: Vec<FilterRule>,
+    files: ServerFiles,
+    storage: Option<PathBuf>,
+    uplink: Option<(String, String)>,
+}
+
+impl Default for ServerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ServerBuilder {
+    pub fn new() -> Self {
+        Self {
+            address: "127.0.0.1:0".to_owned(),
+            listener: None,
+            limits: ConnectionLimits::default(),
+            filters: vec![],
+            files: ServerFiles::default(),
+            storage: None,
+            uplink: None,
+        }
+    }
+
+    /// Direccion en la que escucha, por ejemplo "127.0.0.1:6667".
+    pub fn address(mut self, address: &str) -> Self {
+        self.address = address.to_owned();
+        self
+    }
+
+    /// Acepta las conexiones de un listener ya abierto en vez de abrir uno en la direccion.
+    pub fn listener(mut self, listener: TcpListener) -> Self {
+        self.listener = Some(listener);
+        self
+    }
+
+    pub fn limits(mut self, limits: ConnectionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Toma los limites de un archivo con el formato de server.conf, REHASH los vuelve a leer de ahi.
+    pub fn config_file(mut self, path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let limits = ConnectionLimits::load(&path)?;
+        self.files.config = Some(path);
+        Ok(self.limits(limits))
+    }
+
+    pub fn filters(mut self, filters: Vec<FilterRule>) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    /// Toma las reglas de un archivo con el formato de filters.conf, REHASH las vuelve a leer de ahi.
+    pub fn filter_file(mut self, path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let content = match fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) => return Err(format!("Can't read {}: {}", path.display(), e)),
+        };
+        let filters = parse_filters(&content)?;
+        self.files.filters = Some(path);
+        Ok(self.filters(filters))
+    }
+
+    /// Archivo con el mensaje del dia, se lee en cada MOTD.
+    pub fn motd_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.files.motd = Some(path.into());
+        self
+    }
+
+    /// Archivo con la ubicacion, la institucion y el mail del administrador, para ADMIN.
+    pub fn admin_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.files.admin = Some(path.into());
+        self
+    }
+
+    /// Directorio del que se carga y en el que se guarda el estado del server, junto al log
+    /// de auditoria de los operadores.
+    pub fn storage(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.storage = Some(dir.into());
+        self
+    }
+
+    /// Server de la red al que se conecta al arrancar, con la contraseña del link.
+    pub fn uplink(mut self, address: &str, password: &str) -> Self {
+        self.uplink = Some((address.to_owned(), password.to_owned()));
+        self
+    }
+
+    /// Abre el listener, carga el estado guardado, se conecta al uplink si tiene uno
+    /// y deja al server atendiendo conexiones en un hilo propio.
+    pub fn spawn(self) -> io::Result<ServerHandle> {
+        let listener = match self.listener {
+            Some(l) => l,
+            None => TcpListener::bind(&self.address)?,
+        };
+        let address = listener.local_addr()?;
+
+        let mut server = Server::new(
+            address.to_string(),
+            self.limits,
+            self.filters,
+            self.files,
+            self.storage,
+        );
+        if let Err(reason) = server.load_file() {
+            println!("[SERVER - PERSISTENCY] Can't load datafile {}", reason)
+        }
+
+        let reactor = Reactor::from_listener(listener)?;
+
+        let uplink = match &self.uplink {
+            Some((uplink, password)) => match Server::connect_uplink(
+                &server.host,
+                server.limits().link_timeout,
+                uplink,
+                password,
+            ) {
+                Ok(link) => Some(link),
+                Err(e) => {
+                    return Err(io::Error::new(
+                        ErrorKind::ConnectionRefused,
+                        format!("Can't link to {}: {:?}", uplink, e),
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        let (commands, comm_rx) = channel();
+        let waker = reactor.waker();
+        let name = server.host.to_owned();
+        // The reactor thread is the only owner of the server state from here on
+        let thread = {
+            let commands = commands.clone();
+            thread::spawn(move || {
+                Server::serve(&server, reactor, uplink, commands, comm_rx);
+            })
+        };
+
+        Ok(ServerHandle {
+            name,
+            address,
+            commands,
+            waker,
+            thread,
+        })
+    }
+}
+
+/// Server corriendo en un hilo propio, devuelto por ServerBuilder::spawn.
+#[derive(Debug)]
+pub struct ServerHandle {
+    name: String,
+    address: SocketAddr,
+    commands: Sender<ServerCommand>,
+    waker: Arc<Waker>,
+    thread: JoinHandle<()>,
+}
+
+impl ServerHandle {
+    /// Direccion en la que acepta conexiones.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Nombre con el que el server se presenta en la red.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Apaga el server como el comando SHUTDOWN de la consola y espera a que termine:
+    /// guarda el estado, avisa a los links y cierra todas las conexiones.
+    pub fn shutdown(self) -> thread::Result<()> {
+        if self.commands.send(ServerCommand::Shutdown).is_ok() {
+            let _ = self.waker.wake();
+        }
+        self.thread.join()
+    }
+
+    /// Conecta el server, ya corriendo, con otro de la red. Vuelve cuando el otro server
+    /// acepto el link, o con el motivo por el que no se pudo.
+    pub fn connect(&self, address: &str, password: &str) -> Result<(), String> {
+        let (result, linked) = channel();
+        let command = ServerCommand::Connect(address.to_owned(), password.to_owned(), result);
+        if self.commands.send(command).is_err() || self.waker.wake().is_err() {
+            return Err("The server is not running".to_owned());
+        }
+        linked
+            .recv()
+            .unwrap_or_else(|_| Err("The server is not running".to_owned()))
+    }
+
+    /// Espera a que el server se apague por su cuenta, por ejemplo con un DIE de un operador.
+    pub fn join(self) -> thread::Result<()> {
+        self.thread.join()
+    }
+}
+
+#[cfg(test)]
+mod builder_tests {
+    use super::*;
+    use crate::irc::{
+        constants::{INFO_PASSWORD, RPL_REHASHING, RPL_SUCLOGIN, RPL_YOUREOPER},
+        model::client_connection::ClientConnection,
+        testing::{TestClient, TestNetwork},
+    };
+    use std::{env, process, time::Duration};
+
+    fn read_until(conn: &mut ClientConnection, text: &str) -> bool {
+        while let Ok(Some(line)) = conn.read_line() {
+            if line.contains(text) {
+                return true;
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn test_spawned_server_registers_clients_and_shuts_down() {
+        let server = ServerBuilder::new().spawn().unwrap();
+
+        let mut conn = ClientConnection::connect(server.address()).unwrap();
+        conn.set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        conn.register("pass", "embedded", "embedded", "Embedded Client")
+            .unwrap();
+        assert!(read_until(&mut conn, &format!("{}", RPL_SUCLOGIN)));
+
+        server.shutdown().unwrap();
+        // The reactor drops every connection on its way out
+        let mut closed = conn.read_line();
+        while let Ok(Some(_)) = closed {
+            closed = conn.read_line();
+        }
+        assert!(matches!(closed, Ok(None)));
+    }
+
+    /// Cliente del server que ya es operador, para mandar REHASH.
+    fn operator(net: &TestNetwork, server: usize) -> TestClient {
+        let mut oper = net.client(server, "oper");
+        oper.send(&format!("OPER oper {}", INFO_PASSWORD));
+        oper.expect_numeric(RPL_YOUREOPER);
+        oper
+    }
+
+    #[test]
+    fn test_rehash_keeps_the_limits_given_to_the_builder() {
+        let mut net = TestNetwork::new();
+        let limits = ConnectionLimits {
+            max_per_ip: 1,
+            ..ConnectionLimits::default()
+        };
+        let server = net.start(ServerBuilder::new().limits(limits));
+        let mut oper = operator(&net, server);
+
+        oper.send("REHASH");
+        oper.expect_containing("Rehash failed");
+        // Whatever server.conf the working directory has, the limit is still the builder's
+        let mut second = TestClient::unregistered(net.address(server), "second");
+        second.expect_containing("Too many connections");
+    }
+
+    #[test]
+    fn test_rehash_reloads_the_config_file_given_to_the_builder() {
+        let dir = env::temp_dir().join(format!("irc-rehash-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("embedded.conf");
+        fs::write(&config, "max_per_ip = 3\n").unwrap();
+
+        let mut net = TestNetwork::new();
+        let builder = ServerBuilder::new().config_file(&config).unwrap();
+        let server = net.start(builder.storage(&dir));
+        let mut oper = operator(&net, server);
+        let _second = net.client(server, "second");
+
+        fs::write(&config, "max_per_ip = 2\n").unwrap();
+        oper.send("REHASH");
+        assert_eq!(
+            oper.expect_numeric(RPL_REHASHING),
+            format!("{} :Rehashing", config.display())
+        );
+        let mut third = TestClient::unregistered(net.address(server), "third");
+        third.expect_containing("Too many connections");
+
+        let port = net.address(server).port();
+        assert!(dir.join(format!("audit-{}.log", port)).exists());
+        drop(net);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
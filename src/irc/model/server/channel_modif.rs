@@ -1,8 +1,16 @@
 //! Modulo que se centra en las funcionalidades referentes a la modificacion de canales por parte del server.
 use crate::{
     irc::{
-        constants::{ERR_CHANOPRIVSNEEDED, ERR_NOSUCHCHANNEL, ERR_SERVERERR},
-        model::{channel::Channel, utils::mt, MTChannel, MTClient, ServerError},
+        constants::{
+            ERR_CANNOTKNOCK, ERR_CHANOPEN, ERR_CHANOPRIVSNEEDED, ERR_KNOCKONCHAN,
+            ERR_NOSUCHCHANNEL, ERR_SERVERERR, ERR_TOOMANYKNOCK, RPL_KNOCK,
+        },
+        model::{
+            channel::{Channel, ChannelList},
+            irc_key::IrcKey,
+            utils::{mt, unix_time},
+            MTChannel, MTClient, ServerError,
+        },
     },
     try_lock,
 };
@@ -12,7 +20,7 @@ use super::Server;
 impl Server {
     pub fn get_channel_by_name(&self, channel_name: &str) -> Option<MTChannel> {
         let locked_channels = try_lock!(self.channels);
-        Some(locked_channels.get(channel_name)?.clone())
+        Some(locked_channels.get(&IrcKey::from(channel_name))?.clone())
     }
 
     fn add_creator(
@@ -45,7 +53,7 @@ impl Server {
     ) -> MTChannel {
         let channel = {
             let new_channel = mt(Channel::create_from(channel_name.to_owned(), pwd));
-            try_lock!(self.channels).insert(channel_name.to_owned(), new_channel.clone());
+            try_lock!(self.channels).insert(IrcKey::from(&channel_name), new_channel.clone());
             new_channel
         };
 
@@ -53,7 +61,7 @@ impl Server {
     }
 
     pub fn remove_channel(&self, channel_name: &str) {
-        try_lock!(self.channels).remove(channel_name);
+        try_lock!(self.channels).remove(&IrcKey::from(channel_name));
     }
 
     pub fn get_clients_for_channel(&self, _channel: MTChannel, client: MTClient) -> Vec<String> {
@@ -73,6 +81,19 @@ impl Server {
         try_lock!(channel).secret = to;
     }
 
+    pub fn set_channel_mask(&self, channel: MTChannel, list: ChannelList, mask: &str, to: bool) {
+        let mut ch = try_lock!(channel);
+        if to {
+            ch.add_mask(list, mask);
+        } else {
+            ch.remove_mask(list, mask);
+        }
+    }
+
+    pub fn get_channel_masks(&self, channel: MTChannel, list: ChannelList) -> Vec<String> {
+        try_lock!(channel).get_list(list).clone()
+    }
+
     pub fn set_channel_invite_only(&self, channel: MTChannel, to: bool) {
         try_lock!(channel).invite_only = to;
     }
@@ -172,4 +193,83 @@ impl Server {
             }),
         }
     }
+
+    /// Valida el KNOCK del cliente al canal y lo registra para limitar la frecuencia.
+    /// Devuelve el hostmask del cliente para avisarle a los operadores del canal.
+    pub fn knock_channel(
+        &self,
+        client: MTClient,
+        channel_name: &str,
+    ) -> Result<String, ServerError> {
+        let no_such_channel = ServerError {
+            code: ERR_NOSUCHCHANNEL,
+            msg: format!("{} :No such channel", channel_name),
+        };
+        let channel = match self.get_channel_by_name(channel_name) {
+            Some(ch) => ch,
+            None => return Err(no_such_channel),
+        };
+
+        let lchannel = try_lock!(channel);
+        let mut lclient = try_lock!(client);
+        let hostmask = lclient.hostmask();
+        let cannot_knock = |reason: &str| ServerError {
+            code: ERR_CANNOTKNOCK,
+            msg: format!("Cannot knock on {} ({})", channel_name, reason),
+        };
+
+        // Secret channels must not be revealed to outsiders
+        if lchannel.secret {
+            return Err(no_such_channel);
+        }
+        if lclient.is_in_channel(channel_name) {
+            return Err(ServerError {
+                code: ERR_KNOCKONCHAN,
+                msg: format!("{} :You're already on that channel", channel_name),
+            });
+        }
+        if !lchannel.invite_only {
+            return Err(ServerError {
+                code: ERR_CHANOPEN,
+                msg: format!("{} :Channel is open", channel_name),
+            });
+        }
+        if lchannel.private {
+            return Err(cannot_knock("Channel is private"));
+        }
+        if lchannel.is_banned(&lclient.ban_subject()) {
+            return Err(cannot_knock("You are banned"));
+        }
+        if !lclient.try_knock(channel_name, unix_time()) {
+            return Err(ServerError {
+                code: ERR_TOOMANYKNOCK,
+                msg: format!("{} :Too many KNOCKs (user)", channel_name),
+            });
+        }
+
+        Ok(hostmask)
+    }
+
+    /// Avisa del KNOCK a los operadores del canal conectados a este server.
+    pub fn notify_knock(&self, channel_name: &str, hostmask: &str, reason: Option<String>) {
+        let channel = match self.get_channel_by_name(channel_name) {
+            Some(ch) => ch,
+            None => return,
+        };
+        let line = format!(
+            "{}: {} {} :{}",
+            RPL_KNOCK,
+            channel_name,
+            hostmask,
+            reason.unwrap_or_else(|| "has asked for an invite".to_owned())
+        );
+
+        let lchannel = try_lock!(channel);
+        for client in lchannel.clients.values() {
+            let mut c = try_lock!(client);
+            if c.servername == self.host && c.is_channel_operator(channel_name) {
+                self.send_message_to_local_client(&mut c, &line);
+            }
+        }
+    }
 }
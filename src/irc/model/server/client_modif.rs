@@ -6,11 +6,12 @@ use crate::{
         constants::{
             ERR_CHANOPRIVSNEEDED, ERR_INVITEONLYCHAN, ERR_NICKNAMEINUSE, ERR_NOSUCHCHANNEL,
             ERR_NOSUCHNICK, ERR_PASSWDMISMATCH, ERR_USERONCHANNEL, INFO_PASSWORD, RPL_NICKCHANGE,
+            RPL_NICKOUT,
         },
         message::utils::no_such_nick,
         model::{
-            client::Client, client_account::ClientAccount, connection::Connection, utils::mt,
-            MTChannel, MTClient, ServerError,
+            client::Client, client_account::ClientAccount, connection::Connection, irc_key::IrcKey,
+            utils::mt, MTChannel, MTClient, ServerError,
         },
     },
     try_lock,
@@ -23,33 +24,39 @@ impl Server {
         self.push_client(client.clone());
         self.register_client(client.clone());
 
-        let u = try_lock!(client);
-        let nick_message = u.nick_message();
-        let user_message = u.user_message();
+        let (nick_message, user_message) = {
+            let u = try_lock!(client);
+            (u.nick_message(), u.user_message())
+        };
 
         self.replicate_to_all_servers(&nick_message);
         self.replicate_to_all_servers(&user_message);
+        self.notify_monitors_online(client);
     }
 
     pub fn register_client(&self, client: MTClient) {
-        let client = try_lock!(client);
+        let mut client = try_lock!(client);
         let mut accounts = try_lock!(self.accounts);
 
-        accounts.remove(&client.nickname);
+        client.account = Some(client.nickname.to_owned());
+        let nick = IrcKey::from(&client.nickname);
+        accounts.remove(&nick);
         let acc = mt(ClientAccount::for_client(&client));
-        accounts.insert(client.nickname.to_owned(), acc);
+        accounts.insert(nick, acc);
     }
 
-    fn re_register_client(&self, client: &Client, old_nick: &str) {
+    fn re_register_client(&self, client: &mut Client, old_nick: &str) {
         let mut accounts = try_lock!(self.accounts);
 
-        accounts.remove(old_nick);
+        // The account is moved to the new nick, so it is renamed along with the client
+        client.account = Some(client.nickname.to_owned());
+        accounts.remove(&IrcKey::from(old_nick));
         let acc = mt(ClientAccount::for_client(client));
-        accounts.insert(client.nickname.to_owned(), acc);
+        accounts.insert(IrcKey::from(&client.nickname), acc);
     }
 
     pub fn push_client(&self, client: MTClient) {
-        let nick = { try_lock!(client).nickname.to_owned() };
+        let nick = { IrcKey::from(&try_lock!(client).nickname) };
         let clients = &mut try_lock!(self.clients);
         clients.insert(nick, client);
     }
@@ -57,7 +64,7 @@ impl Server {
     fn remove_client(&self, client: MTClient) {
         let mut locked_clients = try_lock!(self.clients);
         let lclient = try_lock!(client);
-        locked_clients.remove(&lclient.nickname);
+        locked_clients.remove(&IrcKey::from(&lclient.nickname));
     }
 
     pub fn is_channel_operator(&self, client: MTClient, channel_name: &str) -> bool {
@@ -67,7 +74,7 @@ impl Server {
 
     fn can_change_nickname(
         &self,
-        clients: &HashMap<String, MTClient>,
+        clients: &HashMap<IrcKey, MTClient>,
         client: &Client,
         new_nickname: &str,
     ) -> Result<(), ServerError> {
@@ -90,11 +97,17 @@ impl Server {
             });
         }
 
-        if clients.contains_key(new_nickname) {
+        // Changing only the case of the own nickname keeps the same key
+        let new_key = IrcKey::from(new_nickname);
+        if new_key == IrcKey::from(&client.nickname) {
+            return Ok(());
+        }
+
+        if clients.contains_key(&new_key) {
             return Err(nick_in_use);
         }
 
-        if accounts.contains_key(new_nickname) {
+        if accounts.contains_key(&new_key) {
             return Err(nick_in_use);
         }
 
@@ -112,20 +125,29 @@ impl Server {
         client: MTClient,
         new_nickname: String,
     ) -> Result<(), ServerError> {
-        let mut clients = try_lock!(self.clients);
-        let mut lclient = try_lock!(client);
+        let old_nick = {
+            let mut clients = try_lock!(self.clients);
+            let mut lclient = try_lock!(client);
 
-        self.can_change_nickname(&clients, &lclient, &new_nickname)?;
-        let _ = clients.remove(&lclient.nickname);
+            self.can_change_nickname(&clients, &lclient, &new_nickname)?;
+            let _ = clients.remove(&IrcKey::from(&lclient.nickname));
+            self.record_whowas(&lclient);
 
-        let old_nick = lclient.set_nickname(&new_nickname);
+            let old_nick = lclient.set_nickname(&new_nickname);
+            clients.insert(IrcKey::from(&new_nickname), client.clone());
+            self.re_register_client(&mut lclient, &old_nick);
+            old_nick
+        };
+        // Both locks must be released, notifying goes through every client
         self.change_nickname_all_channels(&old_nick, &new_nickname);
-        clients.insert(new_nickname.clone(), client.clone());
-        self.re_register_client(&lclient, &old_nick);
         self.server_action_notify(&format!(
             "{}: {} {}",
             RPL_NICKCHANGE, old_nick, &new_nickname
         ));
+        if IrcKey::from(&old_nick) != IrcKey::from(&new_nickname) {
+            self.notify_monitors(&old_nick, None);
+            self.notify_monitors_online(client);
+        }
         Ok(())
     }
 
@@ -142,7 +164,7 @@ impl Server {
         };
 
         // Check that the login is being done with the same nick
-        if let Some(acc) = accounts.get(conn_nickname) {
+        if let Some(acc) = accounts.get(&IrcKey::from(conn_nickname)) {
             let account = try_lock!(acc);
             if let Some(account_pwd) = &account.pwd {
                 // Does this server know a password for this client? If so check that the user is who they say it is.
@@ -168,7 +190,7 @@ impl Server {
     pub fn get_client_by_nickname(&self, nickname: &str) -> Option<MTClient> {
         let clients = try_lock!(self.clients);
 
-        clients.get(nickname).cloned()
+        clients.get(&IrcKey::from(nickname)).cloned()
     }
 
     pub fn invite_to_channel(
@@ -230,20 +252,25 @@ impl Server {
             Some(ch) => {
                 {
                     let mut channel = try_lock!(ch);
-                    let (is_invited, is_registered_operator) = {
+                    let (is_invited, is_registered_operator, subject) = {
                         let mut c = try_lock!(client);
 
-                        let reg_oper = channel.registered_operators.contains_key(&c.nickname);
+                        let reg_oper = channel
+                            .registered_operators
+                            .contains_key(&IrcKey::from(&c.nickname));
 
                         if reg_oper {
                             c.set_channel_operator(channel_name.to_owned(), ch.clone())
                         }
 
-                        (c.is_invited(channel_name), reg_oper)
+                        (c.is_invited(channel_name), reg_oper, c.ban_subject())
                     };
-                    if is_invited || is_registered_operator {
+                    if is_registered_operator {
+                        channel.add_client(client.clone())?;
+                    } else if is_invited {
+                        channel.check_ban(&subject)?;
                         channel.add_client(client.clone())?;
-                    } else if channel.invite_only {
+                    } else if channel.invite_only && !channel.is_invite_exempt(&subject) {
                         return Err(ServerError {
                             code: ERR_INVITEONLYCHAN,
                             msg: format!("{} :Cannot join channel", channel_name),
@@ -292,13 +319,15 @@ impl Server {
             }
         };
 
-        {
-            let mut lclient = try_lock!(client);
-            try_lock!(channel).remove_client_by_nickname(&lclient.nickname)?;
-            lclient.remove_channel(channel_name);
+        let nickname = try_lock!(client).nickname.to_owned();
+        let empty = {
+            let mut lchannel = try_lock!(channel);
+            lchannel.remove_client_by_nickname(&nickname)?;
+            lchannel.client_amount() == 0
         };
+        try_lock!(client).remove_channel(channel_name);
 
-        if try_lock!(channel).client_amount() == 0 {
+        if empty {
             self.remove_channel(channel_name);
         }
 
@@ -332,21 +361,48 @@ impl Server {
     }
 
     pub fn quit_client(&self, msg: String, client: MTClient) {
+        let nick = self.disconnect_client(client);
+
+        let message = format!("{} :{}", nick, msg);
+        self.server_broadcast(&message, false);
+    }
+
+    /// Desconecta a un cliente local por decision del server: le avisa el motivo,
+    /// lo saca de la red y cierra su conexion.
+    pub fn kill_local_client(&self, client: MTClient, reason: &str) {
         let nick = {
-            let mut lclient = try_lock!(client);
+            let mut c = try_lock!(client);
+            self.send_message_to_local_client(&mut c, &format!("ERROR :Closing Link: {}", reason));
+            c.nickname.to_owned()
+        };
 
-            for (_, channel) in lclient.channels.iter() {
-                let _ = try_lock!(channel).remove_client_by_nickname(&lclient.nickname);
-            }
+        self.quit_client(reason.to_owned(), client.clone());
+        self.server_action_notify(&format!("{}: {}", RPL_NICKOUT, nick));
+        self.replicate_to_all_servers(&format!(":{} QUIT :{}", nick, reason));
 
-            lclient.channels.clear();
+        let _ = try_lock!(client).tcp_destroy();
+    }
 
-            &lclient.nickname.to_owned()
+    /// Saca al cliente de sus canales y del server sin notificar a nadie.
+    /// Devuelve el nickname que tenia el cliente.
+    pub fn disconnect_client(&self, client: MTClient) -> String {
+        let (nick, channels) = {
+            let mut lclient = try_lock!(client);
+            self.record_whowas(&lclient);
+            let channels = lclient
+                .channels
+                .drain()
+                .map(|(_, ch)| ch)
+                .collect::<Vec<_>>();
+            (lclient.nickname.to_owned(), channels)
         };
+        // Channels come before clients in the lock order, they're left once the client is released
+        for channel in channels {
+            let _ = try_lock!(channel).remove_client_by_nickname(&nick);
+        }
         self.remove_client(client);
-
-        let message = format!("{} :{}", nick, msg);
-        self.server_broadcast(&message, false);
+        self.notify_monitors(&nick, None);
+        nick
     }
 
     pub fn set_client_invisible(&self, client: MTClient, to: bool) {
@@ -359,6 +415,11 @@ impl Server {
         lclient.rec_sv_notices = to;
     }
 
+    pub fn set_client_wallops(&self, client: MTClient, to: bool) {
+        let mut lclient = try_lock!(client);
+        lclient.wallops = to;
+    }
+
     pub fn set_client_sv_operator(&self, client: MTClient, to: bool) {
         if !to {
             let mut lclient = try_lock!(client);
@@ -377,14 +438,16 @@ impl Server {
         lclient.set_channel_operator(lchannel.name.to_owned(), channel.clone());
         lchannel
             .registered_operators
-            .insert(lclient.nickname.to_owned(), false);
+            .insert(IrcKey::from(&lclient.nickname), false);
     }
 
     pub fn del_client_channel_operator(&self, client: MTClient, channel: MTChannel) {
         let mut lchannel = try_lock!(channel);
         let mut lclient = try_lock!(client);
         lclient.del_channel_operator(&lchannel.name);
-        lchannel.registered_operators.remove(&lclient.nickname);
+        lchannel
+            .registered_operators
+            .remove(&IrcKey::from(&lclient.nickname));
     }
 
     pub fn client_speak_in_moderated_channel(
@@ -406,7 +469,7 @@ impl Server {
     pub fn add_data_client_by_nick(&self, new_nickname: String) {
         let client = mt(Client::for_data(new_nickname.clone()));
 
-        try_lock!(self.clients).insert(new_nickname, client);
+        try_lock!(self.clients).insert(IrcKey::from(new_nickname), client);
     }
 
     pub fn add_data_client_user_info(
@@ -426,7 +489,8 @@ impl Server {
             lclient.realname = realname;
         }
 
-        self.register_client(client);
+        self.register_client(client.clone());
+        self.notify_monitors_online(client);
     }
 
     pub fn set_client_away(&self, client: MTClient, away_message: String) {
@@ -0,0 +1,59 @@
+//! Modulo que se centra en las funcionalidades referentes al archivo de configuracion del server.
+use std::{fs, path::Path, time::Duration};
+
+use super::ConnectionLimits;
+
+impl ConnectionLimits {
+    /// Lee los limites de un archivo con lineas "clave = valor".
+    /// Las claves que no aparecen conservan su valor por defecto.
+    pub fn load(path: &Path) -> Result<ConnectionLimits, String> {
+        let content = match fs::read_to_string(path) {
+            Ok(v) => v,
+            Err(e) => return Err(format!("Can't read {}: {}", path.display(), e)),
+        };
+        ConnectionLimits::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<ConnectionLimits, String> {
+        let mut limits = ConnectionLimits::default();
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((k, v)) => (k.trim(), v.trim()),
+                None => return Err(format!("Line {}: expected key = value", number + 1)),
+            };
+            // Masks are the only values that aren't numbers, each line adds one
+            if key == "flood_exempt" {
+                limits.flood_exempt.push(value.to_owned());
+                continue;
+            }
+
+            let value = match value.parse::<usize>() {
+                Ok(v) => v,
+                Err(_) => return Err(format!("Line {}: {} is not a number", number + 1, value)),
+            };
+
+            match key {
+                "max_per_ip" => limits.max_per_ip = value,
+                "max_rate" => limits.max_rate = value,
+                "rate_window" => limits.rate_window = Duration::from_secs(value as u64),
+                "flood_rate" => limits.flood_rate = value,
+                "flood_burst" => limits.flood_burst = value,
+                "flood_delay" => limits.flood_delay = value,
+                "flood_drops" => limits.flood_drops = value,
+                "flood_exempt_opers" => limits.flood_exempt_opers = value != 0,
+                "sendq_client" => limits.sendq_client = value,
+                "sendq_server" => limits.sendq_server = value,
+                "link_timeout" => limits.link_timeout = Duration::from_secs(value as u64),
+                _ => return Err(format!("Line {}: unknown key {}", number + 1, key)),
+            }
+        }
+
+        Ok(limits)
+    }
+}
@@ -0,0 +1,89 @@
+//! Modulo que se centra en las funcionalidades referentes a los filtros de mensajes del server.
+use std::{fs, io::ErrorKind, path::Path};
+
+use crate::{
+    irc::model::{
+        filter::{parse_filters, FilterAction, FilterRule},
+        server_ban::ServerBan,
+        MTClient,
+    },
+    try_lock,
+};
+
+use super::Server;
+
+/// Lee las reglas del archivo de filtros. Si no existe el archivo no hay reglas.
+pub fn load_filters(path: &Path) -> Result<Vec<FilterRule>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => parse_filters(&content),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(format!("Can't read {}: {}", path.display(), e)),
+    }
+}
+
+impl Server {
+    /// Reglas que coinciden con el mensaje, junto a su posicion en el archivo de filtros.
+    pub fn matching_filters(
+        &self,
+        source: &str,
+        target: &str,
+        text: &str,
+    ) -> Vec<(usize, FilterRule)> {
+        try_lock!(self.filters)
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| rule.matches(source, target, text))
+            .map(|(i, rule)| (i + 1, rule.clone()))
+            .collect()
+    }
+
+    /// Aplica la primera regla que coincide con el mensaje que el cliente local manda al destino.
+    /// Avisa a los operadores y desconecta o banea al cliente segun la accion de la regla,
+    /// queda a cargo del mensaje avisarle al cliente y no entregarlo si corresponde.
+    pub fn filter_message(&self, client: MTClient, target: &str, text: &str) -> Option<FilterRule> {
+        let (hostmask, address) = {
+            let c = try_lock!(client);
+            (c.hostmask(), c.address)
+        };
+
+        let rule = try_lock!(self.filters)
+            .iter()
+            .find(|rule| rule.matches(&hostmask, target, text))
+            .cloned()?;
+
+        if rule.action.notifies_operators() {
+            self.notify_operators(&format!(
+                "Filter {} matched {} to {}: {}",
+                rule.action, hostmask, target, rule.reason
+            ));
+        }
+
+        let reason = format!("Filtered: {}", rule.reason);
+        match (&rule.action, address) {
+            (FilterAction::Ban(minutes), Some(ip)) => self.add_server_ban(ServerBan::create(
+                format!("*@{}", ip),
+                reason,
+                self.host.to_owned(),
+                *minutes,
+                false,
+            )),
+            (FilterAction::Ban(_), None) | (FilterAction::Kill, _) => {
+                self.kill_local_client(client, &reason)
+            }
+            _ => {}
+        }
+
+        Some(rule)
+    }
+
+    /// Manda un NOTICE del server a los operadores conectados a este server.
+    pub fn notify_operators(&self, msg: &str) {
+        for client in try_lock!(self.clients).values() {
+            let mut c = try_lock!(client);
+            if c.server_operator && c.servername == self.host {
+                let line = format!(":{} NOTICE {} :{}", self.host, c.nickname, msg);
+                self.send_message_to_local_client(&mut c, &line);
+            }
+        }
+    }
+}
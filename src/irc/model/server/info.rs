@@ -0,0 +1,81 @@
+//! Modulo que se centra en las funcionalidades referentes a la informacion que el server da de si mismo.
+use std::{fs, path::PathBuf};
+
+use crate::{
+    irc::constants::{CASEMAPPING, MONITOR_LIMIT},
+    try_lock,
+};
+
+use super::{LusersInfo, Server};
+
+impl Server {
+    fn read_lines(path: Option<&PathBuf>) -> Option<Vec<String>> {
+        let content = fs::read_to_string(path?).ok()?;
+        Some(content.lines().map(|l| l.to_owned()).collect())
+    }
+
+    /// Mensaje del dia, se lee de disco en cada pedido para poder cambiarlo sin reiniciar.
+    pub fn motd(&self) -> Option<Vec<String>> {
+        Self::read_lines(self.files.motd.as_ref())
+    }
+
+    /// Ubicacion, institucion y mail del administrador, una linea para cada uno.
+    pub fn admin_info(&self) -> Option<Vec<String>> {
+        let lines = Self::read_lines(self.files.admin.as_ref())?;
+        if lines.len() < 3 {
+            return None;
+        }
+        Some(lines)
+    }
+
+    /// Funcionalidades que se le anuncian al cliente al registrarse (RPL_ISUPPORT).
+    pub fn isupport(&self) -> String {
+        let tokens = [
+            format!("CASEMAPPING={}", CASEMAPPING),
+            "CHANTYPES=#&".to_owned(),
+            "CHANMODES=beIq,k,l,imnpst".to_owned(),
+            "EXTBAN=$,ar".to_owned(),
+            "PREFIX=(ov)@+".to_owned(),
+            format!("MONITOR={}", MONITOR_LIMIT),
+            "KNOCK".to_owned(),
+        ];
+        format!("{} :are supported by this server", tokens.join(" "))
+    }
+
+    pub fn lusers(&self) -> LusersInfo {
+        let (mut users, mut invisible, mut operators, mut local_clients) = (0, 0, 0, 0);
+        for client in try_lock!(self.clients).values() {
+            let c = try_lock!(client);
+            if c.invisible {
+                invisible += 1;
+            } else {
+                users += 1;
+            }
+            if c.server_operator {
+                operators += 1;
+            }
+            if c.servername == self.host {
+                local_clients += 1;
+            }
+        }
+
+        let (servers, local_servers) = {
+            let sv_connections = try_lock!(self.sv_connections);
+            let local = sv_connections
+                .values()
+                .filter(|s| try_lock!(s).hopcount == 1)
+                .count();
+            (sv_connections.len() + 1, local)
+        };
+
+        LusersInfo {
+            users,
+            invisible,
+            operators,
+            channels: try_lock!(self.channels).len(),
+            servers,
+            local_clients,
+            local_servers,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Modulo que se centra en las funcionalidades referentes a los limites de conexiones por IP y de flood.
+use std::{net::IpAddr, time::Instant};
+
+use crate::{
+    irc::{
+        message::{utils::mask_matches, Command},
+        model::{
+            flood::{command_cost, FloodVerdict},
+            MTClient,
+        },
+    },
+    try_lock,
+};
+
+use super::{ConnectionLimits, Server};
+
+impl Server {
+    /// Registra una conexion entrante. Falla si la IP ya tiene demasiadas conexiones abiertas
+    /// o si se conecto demasiadas veces en la ventana de tiempo configurada.
+    pub fn accept_connection(&self, address: IpAddr) -> Result<(), String> {
+        let now = Instant::now();
+        let limits = self.limits();
+        let window = limits.rate_window;
+        let mut connections = try_lock!(self.ip_connections);
+
+        for c in connections.values_mut() {
+            c.recent.retain(|t| now.duration_since(*t) < window);
+        }
+        connections.retain(|_, c| c.open > 0 || !c.recent.is_empty());
+
+        let entry = connections.entry(address).or_default();
+        entry.recent.push(now);
+
+        if entry.recent.len() > limits.max_rate {
+            return Err("Connection rate exceeded, try again later".to_owned());
+        }
+
+        if entry.open >= limits.max_per_ip {
+            return Err("Too many connections from your host".to_owned());
+        }
+
+        entry.open += 1;
+        Ok(())
+    }
+
+    pub fn release_connection(&self, address: IpAddr) {
+        let mut connections = try_lock!(self.ip_connections);
+        if let Some(entry) = connections.get_mut(&address) {
+            entry.open = entry.open.saturating_sub(1);
+        }
+    }
+
+    /// Copia de los limites vigentes, que pueden cambiar con REHASH.
+    pub fn limits(&self) -> ConnectionLimits {
+        try_lock!(self.limits).clone()
+    }
+
+    /// Cobra el comando, llegado en arrived, a la penalizacion del cliente. Los operadores (si la
+    /// configuracion lo permite) y los clientes que coinciden con alguna mascara exenta no tienen limite.
+    pub fn check_flood(
+        &self,
+        client: MTClient,
+        command: &Command,
+        arrived: Instant,
+    ) -> FloodVerdict {
+        let limits = self.limits();
+        let mut c = try_lock!(client);
+
+        if limits.flood_exempt_opers && c.server_operator {
+            return FloodVerdict::Accept;
+        }
+
+        let hostmask = c.hostmask();
+        if limits
+            .flood_exempt
+            .iter()
+            .any(|mask| mask_matches(mask, &hostmask))
+        {
+            return FloodVerdict::Accept;
+        }
+
+        c.flood.charge(command_cost(command), &limits, arrived)
+    }
+}
@@ -1,22 +1,161 @@
-use std::{collections::HashMap, sync::Mutex};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    net::IpAddr,
+    path::PathBuf,
+    sync::{mpsc::Sender, Mutex},
+    time::{Duration, Instant},
+};
 
-use super::{MTChannel, MTClient, MTClientAccount, MTServerConnection};
+use super::{
+    filter::FilterRule, irc_key::IrcKey, server_ban::ServerBan, workers::ServerCommand, MTChannel,
+    MTClient, MTClientAccount, MTServerConnection,
+};
 
+pub mod builder;
 pub mod channel_modif;
 pub mod client_modif;
+pub mod config;
+pub mod filters;
+pub mod info;
+pub mod limits;
+pub mod operator;
 pub mod persist;
+pub mod presence;
 pub mod repr;
 pub mod runtime;
 pub mod send_to_client;
+pub mod server_bans;
 pub mod server_connection;
+pub mod stats;
+pub mod whowas;
 
+/// Estado del server. Lo usa un unico hilo, el del reactor: ServerBuilder se lo pasa por valor
+/// y server_run lo atiende en el mismo hilo que lo creo. Ahi corren los mensajes, la persistencia
+/// periodica y el apagado, y como los campos son privados ningun otro hilo puede tomar sus locks.
+/// Los Mutex solo permiten compartir clientes, canales y links entre los mapas.
+///
+/// Como no son reentrantes, cuando se necesita mas de un lock se toman en este orden y nunca
+/// dos del mismo nivel a la vez:
+///
+/// 1. `clients`
+/// 2. `channels`
+/// 3. un canal
+/// 4. un cliente
+/// 5. `accounts`
+/// 6. una cuenta
+/// 7. `sv_connections`
+/// 8. `netjoins`
+/// 9. un link con otro server
+/// 10. el resto de los campos, durante los cuales no se toma ningun otro lock
+///
+/// Un metodo que toma un lock no se llama mientras se tiene ese lock o uno posterior: se copia
+/// lo necesario, se suelta el guard y recien ahi se llama.
 #[derive(Debug)]
 pub struct Server {
-    pub host: String,
-    pub clients: Mutex<HashMap<String, MTClient>>,
-    pub channels: Mutex<HashMap<String, MTChannel>>,
-    pub accounts: Mutex<HashMap<String, MTClientAccount>>,
-    pub sv_connections: Mutex<HashMap<String, MTServerConnection>>,
+    pub(crate) host: String,
+    clients: Mutex<HashMap<IrcKey, MTClient>>,
+    channels: Mutex<HashMap<IrcKey, MTChannel>>,
+    accounts: Mutex<HashMap<IrcKey, MTClientAccount>>,
+    sv_connections: Mutex<HashMap<String, MTServerConnection>>,
+    /// Clientes introducidos durante el burst de cada link, pendientes de notificar como netjoin.
+    netjoins: Mutex<HashMap<String, Netjoin>>,
+    /// K-lines y G-lines vigentes, indexadas por mascara.
+    server_bans: Mutex<HashMap<String, ServerBan>>,
+    /// Se recargan desde el archivo de configuracion con REHASH.
+    limits: Mutex<ConnectionLimits>,
+    /// Reglas de filtrado de PRIVMSG y NOTICE, tambien se recargan con REHASH.
+    filters: Mutex<Vec<FilterRule>>,
+    ip_connections: Mutex<HashMap<IpAddr, IpConnections>>,
+    /// Momento de arranque en segundos desde epoch.
+    pub(crate) started_at: u64,
+    /// Uso de cada comando desde el arranque, indexado por nombre de comando.
+    command_usage: Mutex<HashMap<String, CommandUsage>>,
+    /// Canal hacia el loop principal para que los operadores puedan apagar el server.
+    commands: Mutex<Option<Sender<ServerCommand>>>,
+    restarting: Mutex<bool>,
+    /// Directorio donde se guardan las cuentas, los canales, los bans y el log de auditoria.
+    /// Sin directorio el estado solo vive mientras corre el server.
+    pub(crate) storage: Option<PathBuf>,
+    files: ServerFiles,
+    /// Identidades de los clientes que se fueron o cambiaron de nick, la mas reciente al final.
+    whowas: Mutex<VecDeque<WhowasEntry>>,
+}
+
+/// Archivos que el server lee mientras corre: REHASH recarga los limites y los filtros,
+/// MOTD y ADMIN se leen en cada pedido. Sin archivo no se lee nada.
+#[derive(Debug, Clone, Default)]
+pub struct ServerFiles {
+    pub config: Option<PathBuf>,
+    pub filters: Option<PathBuf>,
+    pub motd: Option<PathBuf>,
+    pub admin: Option<PathBuf>,
+}
+
+/// Limites de conexiones aceptadas por cada IP y de comandos por cliente.
+#[derive(Debug, Clone)]
+pub struct ConnectionLimits {
+    pub max_per_ip: usize,
+    pub max_rate: usize,
+    pub rate_window: Duration,
+    /// Costo de comandos que se recupera por segundo, 0 desactiva el control de flood.
+    pub flood_rate: usize,
+    /// Costo que un cliente puede gastar de golpe sin que se lo frene.
+    pub flood_burst: usize,
+    /// Segundos que puede esperar un comando antes de descartarlo.
+    pub flood_delay: usize,
+    /// Comandos descartados seguidos antes de desconectar al cliente por Excess Flood.
+    pub flood_drops: usize,
+    pub flood_exempt_opers: bool,
+    /// Mascaras nick!user@host de los clientes (bots de confianza) sin control de flood.
+    pub flood_exempt: Vec<String>,
+    /// Bytes pendientes de envio a un cliente antes de desconectarlo por Max SendQ exceeded.
+    pub sendq_client: usize,
+    /// Lo mismo para los links con otros servers, que reciben el trafico de toda la red.
+    pub sendq_server: usize,
+    /// Tiempo para conectarse con otro server y que acepte el registro del link.
+    pub link_timeout: Duration,
+}
+
+impl Default for ConnectionLimits {
+    fn default() -> Self {
+        ConnectionLimits {
+            max_per_ip: 10,
+            max_rate: 8,
+            rate_window: Duration::from_secs(10),
+            flood_rate: 2,
+            flood_burst: 10,
+            flood_delay: 5,
+            flood_drops: 10,
+            flood_exempt_opers: true,
+            flood_exempt: vec![],
+            sendq_client: 256 * 1024,
+            sendq_server: 4 * 1024 * 1024,
+            link_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Burst de un link en curso: cuando empezo y los clientes que llegaron por el.
+#[derive(Debug)]
+pub struct Netjoin {
+    pub opened: Instant,
+    pub nicknames: HashSet<IrcKey>,
+}
+
+/// Conexiones de una IP: cuantas siguen abiertas y cuando se abrieron las ultimas.
+#[derive(Debug, Default)]
+pub struct IpConnections {
+    pub open: usize,
+    pub recent: Vec<Instant>,
+}
+
+/// Veces que se recibio un comando y cuantos bytes sumaron.
+/// Las remotas son las que llegaron desde otro server.
+#[derive(Debug, Default)]
+pub struct CommandUsage {
+    pub count: u64,
+    pub bytes: u64,
+    pub remote_count: u64,
 }
 
 pub struct UserInfo {
@@ -25,3 +164,39 @@ pub struct UserInfo {
     pub end: String,
     pub channels: Vec<String>,
 }
+
+/// Datos de un cliente para responder a WHO, cada campo es una columna de la respuesta.
+pub struct WhoInfo {
+    pub channel: String,
+    pub username: String,
+    pub hostname: String,
+    pub servername: String,
+    pub nickname: String,
+    pub flags: String,
+    pub hopcount: u32,
+    pub account: String,
+    pub realname: String,
+}
+
+/// Identidad de un cliente al momento de irse o cambiar de nick.
+#[derive(Debug, Clone)]
+pub struct WhowasEntry {
+    pub nickname: String,
+    pub username: String,
+    pub hostname: String,
+    pub realname: String,
+    pub servername: String,
+    /// Momento de salida en segundos desde epoch.
+    pub signoff: u64,
+}
+
+/// Contadores de la red para responder a LUSERS.
+pub struct LusersInfo {
+    pub users: usize,
+    pub invisible: usize,
+    pub operators: usize,
+    pub channels: usize,
+    pub servers: usize,
+    pub local_clients: usize,
+    pub local_servers: usize,
+}
@@ -0,0 +1,98 @@
+//! Modulo que se centra en las funcionalidades referentes a las acciones de los operadores sobre el server.
+use std::{fs::OpenOptions, io::Write, path::PathBuf};
+
+use crate::{
+    irc::{
+        constants::AUDIT_FILE_PREFIX,
+        model::{
+            utils::{format_unix_time, unix_time},
+            workers::ServerCommand,
+            MTClient,
+        },
+    },
+    try_lock,
+};
+
+use super::{filters::load_filters, ConnectionLimits, Server};
+
+impl Server {
+    fn audit_file(&self) -> Option<PathBuf> {
+        let port = self.host.split(':').next_back().unwrap_or_default();
+        let dir = self.storage.as_ref()?;
+        Some(dir.join(format!("{}-{}.log", AUDIT_FILE_PREFIX, port)))
+    }
+
+    /// Deja registrada una accion de un operador en el log de auditoria, dentro del directorio
+    /// de persistencia. Un server sin ese directorio no escribe el log.
+    pub fn audit(&self, nickname: &str, action: &str) {
+        let path = match self.audit_file() {
+            Some(p) => p,
+            None => return,
+        };
+        let line = format!(
+            "[{}] {} {}\n",
+            format_unix_time(unix_time()),
+            nickname,
+            action
+        );
+        let file = OpenOptions::new().create(true).append(true).open(path);
+
+        match file {
+            Ok(mut f) => {
+                if let Err(e) = f.write_all(line.as_bytes()) {
+                    println!("[SERVER - AUDIT] Can't write audit log\n{}", e);
+                }
+            }
+            Err(e) => println!("[SERVER - AUDIT] Can't open audit log\n{}", e),
+        }
+    }
+
+    /// Recarga los limites de conexion y los filtros de mensajes desde los archivos con los que
+    /// se creo el server; lo que no vino de un archivo se mantiene. Devuelve el primer archivo
+    /// recargado, si alguno no se puede leer se mantiene todo como estaba.
+    pub fn rehash(&self) -> Result<String, String> {
+        let (config, filters) = (self.files.config.as_ref(), self.files.filters.as_ref());
+        let reloaded = match config.or(filters) {
+            Some(path) => path.display().to_string(),
+            None => return Err("The server has no configuration files".to_owned()),
+        };
+
+        let limits = config
+            .map(|path| ConnectionLimits::load(path))
+            .transpose()?;
+        let filters = filters.map(|path| load_filters(path)).transpose()?;
+        if let Some(limits) = limits {
+            *try_lock!(self.limits) = limits;
+        }
+        if let Some(filters) = filters {
+            *try_lock!(self.filters) = filters;
+        }
+        Ok(reloaded)
+    }
+
+    /// Corta a todos los clientes locales y le pide al loop principal que apague el server.
+    pub fn terminate(&self, reason: &str, restart: bool) {
+        let local: Vec<MTClient> = try_lock!(self.clients)
+            .values()
+            .filter(|c| try_lock!(c).servername == self.host)
+            .cloned()
+            .collect();
+
+        for client in local {
+            let mut c = try_lock!(client);
+            self.send_message_to_local_client(&mut c, &format!("ERROR :Closing Link: {}", reason));
+            let _ = c.tcp_destroy();
+        }
+
+        let command = if restart {
+            ServerCommand::Restart
+        } else {
+            ServerCommand::Die
+        };
+        if let Some(tx) = try_lock!(self.commands).as_ref() {
+            if tx.send(command).is_err() {
+                println!("[SERVER] The shutdown order couldn't be delivered");
+            }
+        }
+    }
+}
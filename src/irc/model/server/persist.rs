@@ -1,9 +1,28 @@
 //! Modulo que se centra en las funcionalidades referentes a la persistencia por parte del server.
-use crate::try_lock;
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+    irc::model::{irc_key::IrcKey, server_ban::ServerBan, MTChannel, MTClientAccount},
+    try_lock,
+};
 
 use super::Server;
 
 impl Server {
+    /// Reemplazan el estado por el cargado del disco. Piden el server en exclusiva,
+    /// asi que solo se pueden usar antes de pasarselo al reactor.
+    pub(crate) fn restore_accounts(&mut self, accounts: HashMap<IrcKey, MTClientAccount>) {
+        self.accounts = Mutex::new(accounts);
+    }
+
+    pub(crate) fn restore_channels(&mut self, channels: HashMap<IrcKey, MTChannel>) {
+        self.channels = Mutex::new(channels);
+    }
+
+    pub(crate) fn restore_server_bans(&mut self, bans: HashMap<String, ServerBan>) {
+        self.server_bans = Mutex::new(bans);
+    }
+
     pub fn persit_registered_users(&self) -> Vec<Vec<String>> {
         let accounts = try_lock!(self.accounts);
         accounts
@@ -29,4 +48,12 @@ impl Server {
             })
             .collect()
     }
+
+    pub fn persist_server_bans(&self) -> Vec<Vec<String>> {
+        try_lock!(self.server_bans)
+            .values()
+            .filter(|ban| !ban.is_expired())
+            .map(|ban| ban.serialize())
+            .collect()
+    }
 }
@@ -0,0 +1,124 @@
+//! Modulo que se centra en las funcionalidades referentes a la presencia de clientes (ISON, USERHOST y MONITOR).
+use crate::{
+    irc::{
+        constants::{MONITOR_LIMIT, RPL_MONOFFLINE, RPL_MONONLINE},
+        model::{irc_key::IrcKey, MTClient},
+    },
+    try_lock,
+};
+
+use super::Server;
+
+impl Server {
+    /// Nicknames del pedido que estan conectados en alguna parte de la red.
+    pub fn online_nicknames(&self, nicknames: &[String]) -> Vec<String> {
+        let clients = try_lock!(self.clients);
+        nicknames
+            .iter()
+            .filter_map(|nick| clients.get(&IrcKey::from(nick)))
+            .map(|c| try_lock!(c).nickname.to_owned())
+            .collect()
+    }
+
+    /// Entrada de USERHOST del cliente: nick[*]=(+|-)user@host.
+    /// El * marca a los operadores y el - a los clientes away.
+    pub fn describe_userhost(&self, nickname: &str) -> Option<String> {
+        let client = self.get_client_by_nickname(nickname)?;
+        let c = try_lock!(client);
+        Some(format!(
+            "{}{}={}{}@{}",
+            c.nickname,
+            if c.server_operator { "*" } else { "" },
+            if c.away_message.is_some() { "-" } else { "+" },
+            c.username,
+            c.hostname
+        ))
+    }
+
+    /// Separa los nicknames entre conectados (como hostmask) y desconectados.
+    pub fn monitor_status(&self, nicknames: &[String]) -> (Vec<String>, Vec<String>) {
+        let clients = try_lock!(self.clients);
+        let mut online = vec![];
+        let mut offline = vec![];
+
+        for nick in nicknames {
+            match clients.get(&IrcKey::from(nick)) {
+                Some(c) => online.push(try_lock!(c).hostmask()),
+                None => offline.push(nick.to_owned()),
+            }
+        }
+
+        (online, offline)
+    }
+
+    /// Agrega los nicknames a la lista de MONITOR del cliente hasta llegar al limite.
+    /// Devuelve los que se agregaron y los que no entraron.
+    pub fn add_monitors(
+        &self,
+        client: MTClient,
+        nicknames: &[String],
+    ) -> (Vec<String>, Vec<String>) {
+        let mut c = try_lock!(client);
+        let mut added = vec![];
+        let mut rejected = vec![];
+
+        for nick in nicknames {
+            let key = IrcKey::from(nick);
+            if c.monitoring.contains(&key) {
+                added.push(nick.to_owned());
+            } else if c.monitoring.len() < MONITOR_LIMIT {
+                c.monitoring.push(key);
+                added.push(nick.to_owned());
+            } else {
+                rejected.push(nick.to_owned());
+            }
+        }
+
+        (added, rejected)
+    }
+
+    pub fn remove_monitors(&self, client: MTClient, nicknames: &[String]) {
+        let keys: Vec<IrcKey> = nicknames.iter().map(IrcKey::from).collect();
+        try_lock!(client)
+            .monitoring
+            .retain(|nick| !keys.contains(nick));
+    }
+
+    pub fn clear_monitors(&self, client: MTClient) {
+        try_lock!(client).monitoring.clear();
+    }
+
+    pub fn monitor_list(&self, client: MTClient) -> Vec<String> {
+        try_lock!(client)
+            .monitoring
+            .iter()
+            .map(|nick| nick.to_string())
+            .collect()
+    }
+
+    /// Avisa a los clientes locales que siguen al nickname que se conecto (con su hostmask)
+    /// o que se desconecto. No se puede tener tomado ningun cliente al llamarla.
+    pub fn notify_monitors(&self, nickname: &str, hostmask: Option<&str>) {
+        let line = match hostmask {
+            Some(h) => format!("{}: :{}", RPL_MONONLINE, h),
+            None => format!("{}: :{}", RPL_MONOFFLINE, nickname),
+        };
+
+        let key = IrcKey::from(nickname);
+        for client in try_lock!(self.clients).values() {
+            let mut c = try_lock!(client);
+            if c.servername == self.host && c.monitoring.contains(&key) {
+                self.send_message_to_local_client(&mut c, &line);
+            }
+        }
+    }
+
+    /// Igual que notify_monitors, tomando la identidad actual del cliente.
+    pub fn notify_monitors_online(&self, client: MTClient) {
+        let (nickname, hostmask) = {
+            let c = try_lock!(client);
+            (c.nickname.to_owned(), c.hostmask())
+        };
+        self.notify_monitors(&nickname, Some(&hostmask));
+    }
+}
@@ -1,46 +1,257 @@
 //! Modulo que se centra en las funcionalidades referentes a la representacion del server.
-use std::sync::MutexGuard;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     irc::{
         constants::ERR_NOSUCHNICK,
-        message::utils::no_such_nick,
-        model::{channel::Channel, MTChannel, MTClient, ServerError},
+        message::utils::{mask_matches, no_such_nick},
+        model::{channel::Channel, irc_key::IrcKey, MTChannel, MTClient, ServerError},
     },
     try_lock,
 };
 
-use super::{Server, UserInfo};
+use super::{Server, UserInfo, WhoInfo};
 
 impl Server {
-    pub fn describe_connected_clients(&self, asker: MTClient) -> Vec<String> {
-        let channels = try_lock!(self.channels);
-        let mut results = Vec::new();
-        for (_, channel) in channels.iter() {
-            let lchannel = try_lock!(channel);
-            results.extend(lchannel.describe_clients(asker.clone()))
+    /// Devuelve (nombre, hopcount, uplink) de todos los servers conocidos, ordenados por cercania.
+    fn known_servers(&self) -> Vec<(String, u32, String)> {
+        let mut servers: Vec<(String, u32, String)> = try_lock!(self.sv_connections)
+            .values()
+            .map(|sv| {
+                let lsv = try_lock!(sv);
+                let uplink = match &lsv.uplink {
+                    Some(u) => u.to_owned(),
+                    None => self.host.to_owned(),
+                };
+                (lsv.servername.to_owned(), lsv.hopcount, uplink)
+            })
+            .collect();
+        servers.sort_by(|a, b| (a.1, &a.0).cmp(&(b.1, &b.0)));
+        servers
+    }
+
+    pub fn describe_links(&self, mask: &str) -> Vec<String> {
+        let mut links = vec![(self.host.to_owned(), 0, self.host.to_owned())];
+        links.extend(self.known_servers());
+
+        links
+            .into_iter()
+            .filter(|(name, _, _)| mask_matches(mask, name))
+            .map(|(name, hop, uplink)| format!("{} {} :{} {} Server", name, uplink, hop, name))
+            .collect()
+    }
+
+    pub fn describe_map(&self) -> Vec<String> {
+        let mut children: HashMap<String, Vec<(String, u32)>> = HashMap::new();
+        for (name, hop, uplink) in self.known_servers() {
+            children.entry(uplink).or_default().push((name, hop));
+        }
+
+        let mut lines = vec![self.host.to_owned()];
+        Self::describe_map_branch(&self.host, "", &children, &mut lines);
+        lines
+    }
+
+    fn describe_map_branch(
+        server: &str,
+        indent: &str,
+        children: &HashMap<String, Vec<(String, u32)>>,
+        lines: &mut Vec<String>,
+    ) {
+        let branch = match children.get(server) {
+            Some(v) => v,
+            None => return,
+        };
+        for (i, (name, hop)) in branch.iter().enumerate() {
+            let last = i == branch.len() - 1;
+            let (node, next_indent) = match last {
+                true => ("`- ", "   "),
+                false => ("|- ", "|  "),
+            };
+            lines.push(format!("{}{}{} [{} hops]", indent, node, name, hop));
+            let indent = format!("{}{}", indent, next_indent);
+            Self::describe_map_branch(name, &indent, children, lines);
+        }
+    }
+
+    fn hopcount_to(&self, servername: &str) -> u32 {
+        if servername == self.host {
+            return 0;
+        }
+        match try_lock!(self.sv_connections).get(servername) {
+            Some(sv) => try_lock!(sv).hopcount,
+            None => 0,
+        }
+    }
+
+    /// Canal que se muestra en la respuesta de WHO, el primero visible para quien pregunta.
+    fn visible_channel_for(
+        &self,
+        channels: &[(IrcKey, MTChannel)],
+        asker_channels: &[IrcKey],
+    ) -> String {
+        for (name, channel) in channels {
+            if asker_channels.contains(name) {
+                return name.to_string();
+            }
+            let lch = try_lock!(channel);
+            if !lch.secret && !lch.private {
+                return name.to_string();
+            }
+        }
+        "*".to_owned()
+    }
+
+    fn who_info(
+        &self,
+        client: &MTClient,
+        channel: Option<&str>,
+        asker_channels: &[IrcKey],
+    ) -> WhoInfo {
+        let (mut info, channels) = {
+            let lclient = try_lock!(client);
+            let mut flags = match lclient.away_message {
+                Some(_) => "G".to_owned(),
+                None => "H".to_owned(),
+            };
+            if lclient.server_operator {
+                flags.push('*');
+            }
+            if let Some(ch) = channel {
+                if lclient.is_channel_operator(ch) {
+                    flags.push('@');
+                }
+            }
+            let channels: Vec<(IrcKey, MTChannel)> = lclient
+                .channels
+                .iter()
+                .map(|(name, ch)| (name.to_owned(), ch.clone()))
+                .collect();
+            let info = WhoInfo {
+                channel: String::new(),
+                username: lclient.username.to_owned(),
+                hostname: lclient.hostname.to_owned(),
+                servername: lclient.servername.to_owned(),
+                nickname: lclient.nickname.to_owned(),
+                flags,
+                hopcount: 0,
+                account: "0".to_owned(),
+                realname: lclient.realname.to_owned(),
+            };
+            (info, channels)
+        };
+
+        info.channel = match channel {
+            Some(ch) => ch.to_owned(),
+            None => self.visible_channel_for(&channels, asker_channels),
+        };
+        if let Some(ch) = channel {
+            if let Some(mch) = self.get_channel_by_name(ch) {
+                if !info.flags.contains('@') && try_lock!(mch).is_allowed_for_moderated(client) {
+                    info.flags.push('+');
+                }
+            }
         }
+        info.hopcount = self.hopcount_to(&info.servername);
+        if try_lock!(self.accounts).contains_key(&IrcKey::from(&info.nickname)) {
+            info.account = info.nickname.to_owned();
+        }
+        info
+    }
+
+    fn who_matches(info: &WhoInfo, mask: &str) -> bool {
+        [
+            &info.nickname,
+            &info.username,
+            &info.hostname,
+            &info.servername,
+            &info.realname,
+        ]
+        .iter()
+        .any(|field| mask_matches(mask, field))
+    }
+
+    /// Busca los clientes que responden a un WHO. La mascara puede ser un canal
+    /// o un glob que se compara contra nick, user, host, server y nombre real.
+    /// Los clientes invisibles solo se muestran a quienes comparten un canal con ellos.
+    pub fn who(&self, asker: MTClient, mask: Option<&str>, only_operators: bool) -> Vec<WhoInfo> {
+        let (asker_nick, asker_channels) = {
+            let lasker = try_lock!(asker);
+            let channels: Vec<IrcKey> = lasker.channels.keys().cloned().collect();
+            (lasker.nickname.to_owned(), channels)
+        };
+        let mask = match mask {
+            Some("0") | None => "*",
+            Some(m) => m,
+        };
+
+        let is_channel = mask.starts_with('#') || mask.starts_with('&');
+        let candidates: Vec<MTClient> = match is_channel {
+            true => {
+                let channel = match self.get_channel_by_name(mask) {
+                    Some(ch) => ch,
+                    None => return vec![],
+                };
+                let lch = try_lock!(channel);
+                if (lch.secret || lch.private) && !asker_channels.contains(&IrcKey::from(&lch.name))
+                {
+                    return vec![];
+                }
+                lch.clients.values().cloned().collect()
+            }
+            false => try_lock!(self.clients).values().cloned().collect(),
+        };
+
+        let mut results = vec![];
+        for client in candidates {
+            let (visible, is_operator) = {
+                let lclient = try_lock!(client);
+                let shares_channel = lclient.channels.keys().any(|c| asker_channels.contains(c));
+                (
+                    !lclient.invisible || shares_channel || lclient.nickname == asker_nick,
+                    lclient.server_operator,
+                )
+            };
+            if !visible || (only_operators && !is_operator) {
+                continue;
+            }
+            let channel = match is_channel {
+                true => Some(mask),
+                false => None,
+            };
+            let info = self.who_info(&client, channel, &asker_channels);
+            if is_channel || Self::who_matches(&info, mask) {
+                results.push(info);
+            }
+        }
+        results.sort_by(|a, b| a.nickname.cmp(&b.nickname));
         results
     }
 
+    /// Toma un lock por vez: primero los canales del cliente, despues el mapa
+    /// y recien ahi cada canal, sin volver a bloquear al cliente ni al mapa.
     fn get_channel_string(
         &self,
         client: MTClient,
-        func: &dyn Fn(MutexGuard<Channel>) -> bool,
+        func: &dyn Fn(&Channel) -> bool,
     ) -> Vec<Option<String>> {
-        let channels = try_lock!(self.channels);
+        let joined: HashSet<IrcKey> = try_lock!(client).channels.keys().cloned().collect();
+        let channels: Vec<(IrcKey, MTChannel)> = try_lock!(self.channels)
+            .iter()
+            .map(|(name, ch)| (name.clone(), ch.clone()))
+            .collect();
+
         let mut vec: Vec<Option<String>> = channels
-            .values()
-            .filter(|ch| {
-                let lch = try_lock!(ch);
-                func(lch)
-            })
-            .map(|ch| {
+            .into_iter()
+            .filter_map(|(name, ch)| {
                 let lch = try_lock!(ch);
-                if try_lock!(client).is_in_channel(&lch.name) {
-                    return Some(lch.channel_to_string());
+                if !func(&lch) {
+                    return None;
+                }
+                if joined.contains(&name) {
+                    return Some(Some(lch.channel_to_string()));
                 }
-                lch.channel_to_string_outsider()
+                Some(lch.channel_to_string_outsider())
             })
             .collect();
         vec.sort();
@@ -52,13 +263,13 @@ impl Server {
         client: MTClient,
         client_names: Vec<String>,
     ) -> Vec<Option<String>> {
-        self.get_channel_string(client, &|channel: MutexGuard<Channel>| {
+        self.get_channel_string(client, &|channel: &Channel| {
             client_names.contains(&channel.name)
         })
     }
 
     pub fn get_all_channels_string(&self, client: MTClient) -> Vec<Option<String>> {
-        self.get_channel_string(client, &|_: MutexGuard<Channel>| true)
+        self.get_channel_string(client, &|_: &Channel| true)
     }
 
     pub fn describe_full_user_info(
@@ -122,14 +333,15 @@ impl Server {
         channel: MTChannel,
         client: MTClient,
     ) -> Option<Vec<String>> {
+        // The channel is released before locking the client, channels go first in the lock order
+        let visible = {
+            let ch = try_lock!(channel);
+            !ch.private && !ch.secret
+        };
         let is_oper = {
             let c = try_lock!(client);
             let belongs = c.is_in_channel(channel_name);
             let is_oper = c.is_channel_operator(channel_name) || c.server_operator;
-            let visible = {
-                let ch = try_lock!(channel);
-                !ch.private && !ch.secret
-            };
 
             if !belongs && !is_oper && !visible {
                 return None;
@@ -196,6 +408,7 @@ impl Server {
             (c.invisible, "i"),
             (c.server_operator, "o"),
             (c.rec_sv_notices, "s"),
+            (c.wallops, "w"),
         ];
 
         self.describe_modes(&c.nickname, &conds)
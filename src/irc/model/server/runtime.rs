@@ -1,41 +1,51 @@
 //! Modulo que se centra en las funcionalidades referentes a la modificacion de canales por parte del server.
 use std::{
-    collections::HashMap,
-    io::{self, Write},
-    net::{TcpListener, TcpStream},
+    collections::{HashMap, VecDeque},
+    env,
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    os::unix::process::CommandExt,
+    path::{Path, PathBuf},
+    process::Command,
     sync::{
-        mpsc::{channel, Sender},
-        Arc, Condvar, Mutex,
+        mpsc::{channel, Receiver, Sender},
+        Mutex,
     },
-    thread::{self, JoinHandle},
-    time::Duration,
+    thread,
+    time::{Duration, Instant},
 };
 
 use crate::{
     irc::{
+        constants::{
+            ADMIN_FILE, CONFIG_FILE, DEFAULT_SERVERNAME, FILTER_FILE, MOTD_FILE, PERSIST_DIR,
+            RPL_PWDSET, RPL_REGISTERED,
+        },
         message::{
             generic_message::GenericMessage, password::Password, server::Sv, FromGeneric,
             Serializable,
         },
         model::{
-            connection::Connection,
+            filter::FilterRule,
+            reactor::Reactor,
             server_connection::ServerConnection,
-            utils::mt,
+            utils::{mt, unix_time},
             workers::{
-                client_management::thread_manager,
-                persistency::{load, persist, persist_notice},
+                persistency::{load, persist},
                 server_command::listen_commands,
-                ServerCommand, ThreadManagement,
+                ServerCommand,
             },
             ConnectionError, SERVER_ARGS, SERVER_CONNECT_ARGS, SERVER_CONNECT_IP_POS,
             SERVER_CONNECT_PASSWORD_POS, SERVER_CONNECT_PORT_POS, SERVER_PORT_POS,
         },
-        responses::ResponseType,
     },
     try_lock,
 };
 
-use super::Server;
+use super::{filters::load_filters, ConnectionLimits, Server, ServerFiles};
+
+/// Cada cuanto se guardan en disco las cuentas, los canales y los bans.
+const PERSIST_INTERVAL: Duration = Duration::from_secs(60 * 15);
 
 impl Server {
     pub fn create(argv: &[String]) -> Result<Server, ConnectionError> {
@@ -43,41 +53,93 @@ impl Server {
             return Err(ConnectionError::InvalidArguments);
         }
 
-        Ok(Server {
-            host: format!("127.0.0.1:{}", &argv[SERVER_PORT_POS]),
+        // The command line server takes its files from the working directory
+        let files = ServerFiles {
+            config: Some(PathBuf::from(CONFIG_FILE)),
+            filters: Some(PathBuf::from(FILTER_FILE)),
+            motd: Some(PathBuf::from(MOTD_FILE)),
+            admin: Some(PathBuf::from(ADMIN_FILE)),
+        };
+        Ok(Server::new(
+            format!("127.0.0.1:{}", &argv[SERVER_PORT_POS]),
+            ConnectionLimits::load(Path::new(CONFIG_FILE)).unwrap_or_default(),
+            load_filters(Path::new(FILTER_FILE)).unwrap_or_default(),
+            files,
+            Some(PathBuf::from(PERSIST_DIR)),
+        ))
+    }
+
+    /// Server sin clientes ni estado cargado, con el nombre host:puerto con el que se presenta.
+    pub fn new(
+        host: String,
+        limits: ConnectionLimits,
+        filters: Vec<FilterRule>,
+        files: ServerFiles,
+        storage: Option<PathBuf>,
+    ) -> Server {
+        Server {
+            host,
             clients: Mutex::new(HashMap::new()),
             channels: Mutex::new(HashMap::new()),
             accounts: Mutex::new(HashMap::new()),
             sv_connections: Mutex::new(HashMap::new()),
-        })
+            netjoins: Mutex::new(HashMap::new()),
+            server_bans: Mutex::new(HashMap::new()),
+            limits: Mutex::new(limits),
+            filters: Mutex::new(filters),
+            ip_connections: Mutex::new(HashMap::new()),
+            started_at: unix_time(),
+            command_usage: Mutex::new(HashMap::new()),
+            commands: Mutex::new(None),
+            restarting: Mutex::new(false),
+            whowas: Mutex::new(VecDeque::new()),
+            storage,
+            files,
+        }
     }
 
     pub fn load_file(&mut self) -> Result<(), String> {
         load(self)
     }
 
+    /// Se conecta y se registra con otro server. Devuelve el link para que lo atienda el reactor.
     pub fn server_connect(
-        server: Arc<Server>,
+        server: &Server,
         argv: &[String],
-    ) -> Result<(Sender<ResponseType>, JoinHandle<()>), ConnectionError> {
+    ) -> Result<(ServerConnection, TcpStream), ConnectionError> {
         if argv.len() != SERVER_CONNECT_ARGS {
             return Err(ConnectionError::InvalidArguments);
         }
 
-        let pass_msg =
-            match GenericMessage::parse(&format!("PASS {}\r\n", argv[SERVER_CONNECT_PASSWORD_POS]))
-            {
-                Ok(g) => match Password::from_generic(g) {
-                    Ok(p) => format!("{}\r\n", p.serialize()),
-                    Err(_) => return Err(ConnectionError::InvalidArguments),
-                },
+        Self::connect_uplink(
+            &server.host,
+            server.limits().link_timeout,
+            &format!(
+                "{}:{}",
+                argv[SERVER_CONNECT_IP_POS], argv[SERVER_CONNECT_PORT_POS]
+            ),
+            &argv[SERVER_CONNECT_PASSWORD_POS],
+        )
+    }
+
+    /// Se conecta y se registra con el server en la direccion dada, usando la contraseña del link.
+    /// Falla si el otro server no acepta el registro antes de que pase timeout.
+    pub fn connect_uplink(
+        host: &str,
+        timeout: Duration,
+        address: &str,
+        password: &str,
+    ) -> Result<(ServerConnection, TcpStream), ConnectionError> {
+        let pass_msg = match GenericMessage::parse(&format!("PASS {}", password)) {
+            Ok(g) => match Password::from_generic(g) {
+                Ok(p) => format!("{}\r\n", p.serialize()),
                 Err(_) => return Err(ConnectionError::InvalidArguments),
-            };
+            },
+            Err(_) => return Err(ConnectionError::InvalidArguments),
+        };
 
-        let server_msg = match GenericMessage::parse(&format!(
-            "SERVER {} 1 :{} Server\r\n",
-            server.host, server.host
-        )) {
+        let server_msg = match GenericMessage::parse(&format!("SERVER {} 1 :{} Server", host, host))
+        {
             Ok(g) => match Sv::from_generic(g) {
                 Ok(p) => format!("{}\r\n", p.serialize()),
                 Err(_) => return Err(ConnectionError::InvalidArguments),
@@ -85,13 +147,14 @@ impl Server {
             Err(_) => return Err(ConnectionError::InvalidArguments),
         };
 
-        let mut stream = match TcpStream::connect(format!(
-            "{}:{}",
-            argv[SERVER_CONNECT_IP_POS], argv[SERVER_CONNECT_PORT_POS]
-        )) {
-            Ok(v) => v,
-            Err(_) => return Err(ConnectionError::InvalidArguments),
+        let deadline = Instant::now() + timeout;
+        let mut stream = match Self::connect_with_timeout(address, timeout) {
+            Some(v) => v,
+            None => return Err(ConnectionError::InvalidArguments),
         };
+        if stream.set_write_timeout(Some(timeout)).is_err() {
+            return Err(ConnectionError::InternalServerError);
+        }
 
         if stream.write(pass_msg.as_bytes()).is_err() {
             let _ = stream.shutdown(std::net::Shutdown::Both);
@@ -103,151 +166,193 @@ impl Server {
             return Err(ConnectionError::InternalServerError);
         };
 
-        let (sv_connection, stream) = ServerConnection::create(
-            "Unknown".to_owned(),
-            argv[SERVER_CONNECT_PASSWORD_POS].to_owned(),
-            stream,
-        )?;
-
-        let (tx, rx) = channel();
+        // The link is only handed to the reactor once the other end accepted us,
+        // a refused registration fails here instead of as a dead link.
+        if !Self::wait_server_registration(&mut stream, deadline) {
+            let _ = stream.shutdown(std::net::Shutdown::Both);
+            return Err(ConnectionError::InternalServerError);
+        }
+        if stream.set_read_timeout(None).is_err() || stream.set_write_timeout(None).is_err() {
+            return Err(ConnectionError::InternalServerError);
+        }
 
-        let th = thread::spawn(move || {
-            let _ = stream.set_nonblocking(true);
-            let _ =
-                Connection::handle_server_connection(server, mt(sv_connection), &stream, Some(rx));
-        });
+        ServerConnection::create(DEFAULT_SERVERNAME.to_owned(), password.to_owned(), stream)
+    }
 
-        Ok((tx, th))
+    /// Prueba cada direccion a la que resuelve address hasta que una acepta la conexion.
+    fn connect_with_timeout(address: &str, timeout: Duration) -> Option<TcpStream> {
+        address
+            .to_socket_addrs()
+            .ok()?
+            .find_map(|a| TcpStream::connect_timeout(&a, timeout).ok())
     }
 
-    pub fn server_run(server: Arc<Server>) -> std::io::Result<()> {
-        let listener = TcpListener::bind(&server.host)?;
-        listener
-            .set_nonblocking(true)
-            .expect("[SERVER] FATAL: Can't set nonblocking in TCP Listener");
+    /// Lee las respuestas al registro byte a byte, sin bufferear,
+    /// para no consumir el burst que el otro server manda a continuacion.
+    /// Un server que no termina de responder antes de deadline se da por caido.
+    fn wait_server_registration(stream: &mut TcpStream, deadline: Instant) -> bool {
+        let mut line = vec![];
+        let mut byte = [0; 1];
+        loop {
+            // The deadline bounds the whole handshake, not each read
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() || stream.set_read_timeout(Some(left)).is_err() {
+                println!("[SERVER] No answer to the registration");
+                return false;
+            }
+            match stream.read(&mut byte) {
+                Ok(1) => {}
+                _ => return false,
+            }
+            if byte[0] != b'\n' {
+                line.push(byte[0]);
+                continue;
+            }
 
-        let (tx, rx) = channel();
+            let response = String::from_utf8_lossy(&line).to_string();
+            line.clear();
+            if response.starts_with(&format!("{}:", RPL_REGISTERED)) {
+                return true;
+            }
+            if !response.starts_with(&format!("{}:", RPL_PWDSET)) {
+                println!("[SERVER] Registration refused: {}", response.trim());
+                return false;
+            }
+        }
+    }
+
+    pub fn server_run(
+        server: &Server,
+        uplink: Option<(ServerConnection, TcpStream)>,
+    ) -> std::io::Result<()> {
+        let reactor = Reactor::bind(&server.host)?;
 
         let (comm_tx, comm_rx) = channel();
-        let commands = comm_tx.clone();
+        let console_tx = comm_tx.clone();
+        let waker = reactor.waker();
+        let comm = thread::spawn(move || listen_commands(console_tx, waker));
+
+        let from_console = Self::serve(server, reactor, uplink, comm_tx, comm_rx);
+        // The console thread only finishes after reading SHUTDOWN, it can't be waited for otherwise
+        if from_console {
+            let _ = comm.join();
+        }
 
-        let comm = thread::spawn(move || listen_commands(commands));
+        println!("[SERVER] Goodbye :)");
 
-        let tmt = thread::spawn(move || thread_manager(rx));
+        Ok(())
+    }
 
-        let (persistency, exited) = Server::launch_persistency_thread(server.clone(), comm_tx);
+    /// Atiende las conexiones del reactor hasta que llega una orden de apagado por el canal
+    /// de comandos. Devuelve si el apagado se pidio desde la consola.
+    pub(crate) fn serve(
+        server: &Server,
+        mut reactor: Reactor,
+        uplink: Option<(ServerConnection, TcpStream)>,
+        comm_tx: Sender<ServerCommand>,
+        comm_rx: Receiver<ServerCommand>,
+    ) -> bool {
+        let links_tx = comm_tx.clone();
+        *try_lock!(server.commands) = Some(comm_tx);
+
+        if let Some((sv_connection, stream)) = uplink {
+            reactor.add_server_link(server, mt(sv_connection), stream);
+        }
 
-        let (exit, cvar) = &*exited;
+        let mut next_persist = Instant::now() + PERSIST_INTERVAL;
+        let mut exit = false;
+        let mut from_console = false;
 
-        let mut curr_persisting = false;
+        // Everything that touches the server state runs in this thread, handlers included
+        while !exit {
+            if let Err(err) = reactor.turn(server, next_persist) {
+                println!("[SERVER] IO Error.\n[SERVER - IO ERROR]{}", err)
+            }
 
-        for stream in listener.incoming() {
-            match stream {
-                Ok(streamok) => {
-                    Server::launch_connection_thread(server.clone(), streamok, &tx);
-                }
+            if Instant::now() >= next_persist {
+                persist(server);
+                next_persist = Instant::now() + PERSIST_INTERVAL;
+            }
 
-                // Waiting for another connection, see if server tasks are to be performed
-                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
-                    while let Ok(v) = comm_rx.try_recv() {
-                        match v {
-                            ServerCommand::Shutdown => {
-                                if let Err(e) = tx.send(ThreadManagement::KillAll) {
-                                    println!("[SERVER] The shutdown order couldn't be delivered\n[SERVER] {}",e);
-                                    continue;
-                                }
-                                if curr_persisting {
-                                    println!("[SERVER] Can't shutdown while persisting operation is running");
-                                    continue;
-                                }
-                                let mut exited = try_lock!(exit);
-                                *exited = true;
-                                cvar.notify_one();
-                            }
-                            ServerCommand::Persisting => {
-                                curr_persisting = true;
-                            }
-                            ServerCommand::NormalOperation => {
-                                curr_persisting = false;
-                            }
-                        }
+            // See if server tasks are to be performed
+            while let Ok(v) = comm_rx.try_recv() {
+                match v {
+                    ServerCommand::Shutdown => from_console = true,
+                    ServerCommand::Restart => *try_lock!(server.restarting) = true,
+                    ServerCommand::Die => {}
+                    ServerCommand::Connect(address, password, result) => {
+                        Self::link(server, &reactor, address, password, result, &links_tx);
+                        continue;
+                    }
+                    ServerCommand::Linked(sv_connection, stream, result) => {
+                        reactor.add_server_link(server, mt(sv_connection), stream);
+                        let _ = result.send(Ok(()));
+                        continue;
                     }
                 }
-
-                // IO error, can't do much about it
-                Err(err) => {
-                    println!("[SERVER] IO Error.\n[SERVER - IO ERROR]{}", err)
-                }
-            }
-
-            if *(try_lock!(exit)) {
-                break;
+                exit = true;
             }
         }
 
         println!("[SERVER] Starting shut down");
-        drop(tx);
-        persist(server.clone());
-        // No more listening, handle all the worker threads. The only way to get here is issuing a SHUTDOWN command into the server.
-        // This will preemptively destroy all client threads and connections
+        persist(server);
+        // No more listening. The only way to get here is issuing a SHUTDOWN command into the server.
+        // This will close all the links, client connections are dropped along with the reactor
         server.shutdown();
-        let _ = comm.join();
-        let _ = tmt.join();
-        let _ = persistency.join();
+        *try_lock!(server.commands) = None;
 
-        println!("[SERVER] Goodbye :)");
-
-        Ok(())
+        from_console
     }
 
-    fn launch_connection_thread(
-        server: Arc<Server>,
-        stream: TcpStream,
-        tx: &Sender<ThreadManagement>,
+    /// Se conecta a otro server en un hilo aparte, para que el reactor siga atendiendo mientras
+    /// espera la respuesta. El link registrado vuelve al reactor por el canal de comandos.
+    fn link(
+        server: &Server,
+        reactor: &Reactor,
+        address: String,
+        password: String,
+        result: Sender<Result<(), String>>,
+        commands: &Sender<ServerCommand>,
     ) {
-        let transmiter = tx.clone();
-
-        let thread = thread::spawn(move || {
-            let _ = Connection::handle_connection(server, stream);
-            match transmiter.send(ThreadManagement::Clean(thread::current().id())) {
-                Ok(_) => {}
-                Err(_) => {
-                    println!("[SERVER-CONNECTION] Can't properly cleanup thread");
+        let host = server.host.to_owned();
+        let timeout = server.limits().link_timeout;
+        let commands = commands.clone();
+        let waker = reactor.waker();
+
+        thread::spawn(
+            move || match Self::connect_uplink(&host, timeout, &address, &password) {
+                Ok((sv_connection, stream)) => {
+                    // If the server stopped meanwhile, dropping result tells the caller
+                    let linked = ServerCommand::Linked(sv_connection, stream, result);
+                    if commands.send(linked).is_ok() {
+                        let _ = waker.wake();
+                    }
                 }
-            }
-        });
+                Err(e) => {
+                    let _ = result.send(Err(format!("Can't link to {}: {:?}", address, e)));
+                }
+            },
+        );
+    }
 
-        let _ = tx.send(ThreadManagement::KeepTrack(thread));
+    pub fn is_restarting(&self) -> bool {
+        *try_lock!(self.restarting)
     }
 
-    fn launch_persistency_thread(
-        server: Arc<Server>,
-        tx: Sender<ServerCommand>,
-    ) -> (JoinHandle<()>, Arc<(Mutex<bool>, Condvar)>) {
-        let exited = Arc::new((Mutex::new(false), Condvar::new()));
-        let pair = exited.clone();
-        (
-            thread::spawn(move || {
-                let (lock, cvar) = &*exited;
-
-                let mut exited = try_lock!(lock);
-                loop {
-                    match cvar.wait_timeout(exited, Duration::from_secs(60 * 15)) {
-                        Ok((v, _)) => {
-                            exited = v;
-                            if *exited {
-                                break;
-                            }
-                            persist_notice(server.clone(), tx.clone());
-                        }
-                        Err(e) => {
-                            exited = e.into_inner().0;
-                        }
-                    }
-                }
-            }),
-            pair,
-        )
+    /// Reemplaza el proceso actual por uno nuevo con los mismos argumentos.
+    /// Solo vuelve si no se pudo ejecutar el nuevo proceso.
+    pub fn relaunch(argv: &[String]) {
+        let executable = match env::current_exe() {
+            Ok(v) => v,
+            Err(e) => {
+                println!("[SERVER] Can't find the executable to restart\n{}", e);
+                return;
+            }
+        };
+
+        println!("[SERVER] Restarting");
+        let error = Command::new(executable).args(&argv[1..]).exec();
+        println!("[SERVER] Can't restart\n{}", error);
     }
 
     fn shutdown_clients(&self) -> Vec<String> {
@@ -264,10 +369,11 @@ impl Server {
     }
 
     fn shutdown(&self) {
+        // Worked out before taking the links, the clients map goes first in the lock order
+        let mut msgs = self.shutdown_clients();
+        msgs.push(format!("SQUIT {} :Shutting down server", self.host));
+
         for server_conn in try_lock!(self.sv_connections).values() {
-            let mut msgs = vec![];
-            msgs.append(&mut self.shutdown_clients());
-            msgs.push(format!("SQUIT {} :Shutting down server", self.host));
             let mut server = try_lock!(server_conn);
             if server.hopcount == 1 {
                 for msg in &msgs {
@@ -279,3 +385,93 @@ impl Server {
         }
     }
 }
+
+#[cfg(test)]
+mod runtime_stress_tests {
+    use super::*;
+    use crate::irc::model::server::builder::ServerBuilder;
+    use std::sync::{mpsc::RecvTimeoutError, Arc, Barrier};
+
+    const CLIENTS: usize = 200;
+    const ROUNDS: usize = 5;
+    const CHANNELS: usize = 4;
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    fn start_server() -> u16 {
+        let limits = ConnectionLimits {
+            max_per_ip: CLIENTS * 2,
+            max_rate: CLIENTS * 2,
+            flood_rate: 0,
+            ..ConnectionLimits::default()
+        };
+
+        let server = ServerBuilder::new().limits(limits).spawn().unwrap();
+        server.address().port()
+    }
+
+    /// Se registra, espera a que esten todos registrados y recien ahi entra y sale de canales
+    /// cambiandose el nick, para que el server tenga que intercalar a todos los clientes.
+    fn run_client(port: u16, i: usize, ready: Arc<Barrier>) -> Result<(), String> {
+        let registration = format!(
+            "PASS pass\r\nNICK st{}\r\nUSER st{} host server :Stress {}\r\n",
+            i, i, i
+        );
+        let stream = TcpStream::connect(("127.0.0.1", port)).and_then(|mut s| {
+            s.set_read_timeout(Some(TIMEOUT))?;
+            s.write_all(registration.as_bytes())?;
+            Ok(s)
+        });
+        ready.wait();
+        let mut stream = stream.map_err(|e| e.to_string())?;
+
+        let mut script = String::new();
+        for round in 0..ROUNDS {
+            let channel = format!("#stress{}", (i + round) % CHANNELS);
+            script += &format!(
+                "JOIN {}\r\nPRIVMSG {} :round {}\r\nNICK s{}r{}\r\nPART {}\r\n",
+                channel, channel, round, i, round, channel
+            );
+        }
+        script += "QUIT :done\r\n";
+        stream
+            .write_all(script.as_bytes())
+            .map_err(|e| e.to_string())?;
+
+        // The server only closes the connection once it went through the whole script
+        let mut received = vec![];
+        stream
+            .read_to_end(&mut received)
+            .map_err(|e| e.to_string())?;
+
+        let last_nick = format!("s{}r{} :You have a new nick", i, ROUNDS - 1);
+        if !String::from_utf8_lossy(&received).contains(&last_nick) {
+            return Err("the last rename never went through".to_owned());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrent_joins_parts_and_renames_finish() {
+        let port = start_server();
+
+        let (tx, rx) = channel();
+        let ready = Arc::new(Barrier::new(CLIENTS));
+        for i in 0..CLIENTS {
+            let tx = tx.clone();
+            let ready = ready.clone();
+            thread::spawn(move || {
+                let result = run_client(port, i, ready);
+                let _ = tx.send(result.map_err(|e| format!("client {}: {}", i, e)));
+            });
+        }
+
+        let deadline = Instant::now() + TIMEOUT;
+        for _ in 0..CLIENTS {
+            match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(result) => result.unwrap(),
+                Err(RecvTimeoutError::Timeout) => panic!("The server stopped answering"),
+                Err(RecvTimeoutError::Disconnected) => panic!("A client thread died"),
+            }
+        }
+    }
+}
@@ -1,9 +1,12 @@
 //! Modulo que se centra en las funcionalidades referentes a la modificacion de canales por parte del server.
 use crate::{
     irc::{
-        constants::{ERR_CANNOTSENDTOCHAN, ERR_NOSUCHCHANNEL, ERR_NOSUCHNICK, RPL_AWAY},
+        constants::{
+            BATCH_CAPABILITY, ERR_CANNOTSENDTOCHAN, ERR_NOSUCHCHANNEL, ERR_NOSUCHNICK, RPL_AWAY,
+            RPL_NICKIN, RPL_NICKOUT,
+        },
         message::utils::no_such_nick,
-        model::{client::Client, MTChannel, MTClient, ServerError},
+        model::{client::Client, irc_key::IrcKey, MTChannel, MTClient, ServerError},
     },
     try_lock,
 };
@@ -18,6 +21,35 @@ impl Server {
         };
     }
 
+    /// Manda lineas de respuesta a un cliente. Si el cliente es de otro server
+    /// se las encamina con REPLY hacia el server al que esta conectado.
+    pub fn send_lines_to_client(&self, nickname: &str, lines: &[String]) {
+        let client = match self.get_client_by_nickname(nickname) {
+            Some(c) => c,
+            None => return,
+        };
+
+        let servername = { try_lock!(client).servername.to_owned() };
+        if servername == self.host {
+            let mut c = try_lock!(client);
+            for line in lines {
+                self.send_message_to_local_client(&mut c, line);
+            }
+            return;
+        }
+
+        for line in lines {
+            let reply = format!(":{} REPLY {} :{}", self.host, nickname, line);
+            if self
+                .replicate_to_servername(&reply, &servername, None)
+                .is_err()
+            {
+                println!("[SERVER] Can't route reply to {}", nickname);
+                return;
+            }
+        }
+    }
+
     fn send_message_to_client(
         &self,
         client: &mut Client,
@@ -64,6 +96,17 @@ impl Server {
         }
     }
 
+    /// Entrega un WALLOPS a los clientes locales con el modo +w.
+    pub fn send_wallops(&self, msg: &str) {
+        let lclients = try_lock!(self.clients);
+        for (_, c) in lclients.iter() {
+            let mut locked_c = try_lock!(c);
+            if locked_c.wallops && locked_c.servername == self.host {
+                self.send_message_to_local_client(&mut locked_c, msg);
+            }
+        }
+    }
+
     pub fn server_action_notify(&self, msg: &str) {
         let lclients = try_lock!(self.clients);
         for (_, c) in lclients.iter() {
@@ -72,6 +115,91 @@ impl Server {
         }
     }
 
+    /// Igual que server_action_notify, pero si el cliente afectado esta entrando en un netjoin
+    /// los clientes que soportan batch no lo reciben: se les avisa al terminar el burst.
+    pub fn server_action_notify_for(&self, nickname: &str, msg: &str) {
+        let netjoining = self.is_netjoining(nickname);
+        let lclients = try_lock!(self.clients);
+        for (_, c) in lclients.iter() {
+            let mut locked_c = try_lock!(c);
+            if netjoining && locked_c.has_capability(BATCH_CAPABILITY) {
+                continue;
+            }
+            self.send_message_to_local_client(&mut locked_c, msg);
+        }
+    }
+
+    fn send_batch_to_local_client(&self, client: &mut Client, batch: &str, lines: &[String]) {
+        let reference = format!("{:x}", rand::random::<u32>());
+        self.send_message_to_local_client(client, &format!("BATCH +{} {}", reference, batch));
+        for line in lines {
+            self.send_message_to_local_client(client, &format!("@batch={} {}", reference, line));
+        }
+        self.send_message_to_local_client(client, &format!("BATCH -{}", reference));
+    }
+
+    /// Notifica la salida de los clientes perdidos en un netsplit.
+    /// El motivo es el par "<server1> <server2>" del link que se corto.
+    pub fn notify_netsplit(&self, reason: &str, nicknames: &[String]) {
+        if nicknames.is_empty() {
+            return;
+        }
+        let quits: Vec<String> = nicknames
+            .iter()
+            .map(|nick| format!(":{} QUIT :{}", nick, reason))
+            .collect();
+
+        let lclients = try_lock!(self.clients);
+        for (_, c) in lclients.iter() {
+            let mut locked_c = try_lock!(c);
+            if locked_c.has_capability(BATCH_CAPABILITY) {
+                let batch = format!("netsplit {}", reason);
+                self.send_batch_to_local_client(&mut locked_c, &batch, &quits);
+                continue;
+            }
+            for nick in nicknames {
+                if locked_c.rec_sv_notices {
+                    self.send_message_to_local_client(
+                        &mut locked_c,
+                        &format!("{} :{}", nick, reason),
+                    );
+                }
+                self.send_message_to_local_client(
+                    &mut locked_c,
+                    &format!("{}: {}", RPL_NICKOUT, nick),
+                );
+            }
+        }
+    }
+
+    /// Notifica en un unico batch a los clientes que lo soportan
+    /// los clientes que volvieron a la red al terminar un burst: cada uno con su aviso de
+    /// presencia, que no recibieron durante el burst, y los canales en los que esta.
+    pub fn notify_netjoin(&self, servers: &str, nicknames: &[String]) {
+        let lclients = try_lock!(self.clients);
+        let mut joins = vec![];
+        for nick in nicknames {
+            if let Some(c) = lclients.get(&IrcKey::from(nick)) {
+                let client = try_lock!(c);
+                joins.push(format!("{}: {}", RPL_NICKIN, nick));
+                for channel in client.channels.keys() {
+                    joins.push(format!(":{} JOIN {}", nick, channel));
+                }
+            }
+        }
+        if joins.is_empty() {
+            return;
+        }
+
+        for (_, c) in lclients.iter() {
+            let mut locked_c = try_lock!(c);
+            if locked_c.has_capability(BATCH_CAPABILITY) {
+                let batch = format!("netjoin {}", servers);
+                self.send_batch_to_local_client(&mut locked_c, &batch, &joins);
+            }
+        }
+    }
+
     pub fn try_send_message_to_client(
         &self,
         nickname: &str,
@@ -80,7 +208,7 @@ impl Server {
         auto_replicate: bool,
     ) -> Result<(), ServerError> {
         let clients = try_lock!(self.clients);
-        match clients.get(nickname) {
+        match clients.get(&IrcKey::from(nickname)) {
             Some(cl) => {
                 let mut client = try_lock!(cl);
                 self.send_message_to_client(
@@ -131,11 +259,12 @@ impl Server {
             code: ERR_CANNOTSENDTOCHAN,
             msg: format!("{} :Cannot send to channel", channel_name),
         };
-        let (is_oper, belongs) = {
+        let (is_oper, belongs, subject) = {
             let c = try_lock!(client);
             (
                 c.is_channel_operator(channel_name),
                 c.is_in_channel(channel_name),
+                c.ban_subject(),
             )
         };
         let ch = try_lock!(channel);
@@ -151,6 +280,10 @@ impl Server {
             return Err(err);
         }
 
+        if !allowed_to && (ch.is_banned(&subject) || ch.is_quieted(&subject)) {
+            return Err(err);
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,72 @@
+//! Modulo que se centra en las funcionalidades referentes a los baneos de server (K-lines y G-lines).
+use std::net::IpAddr;
+
+use crate::{
+    irc::model::{server_ban::ServerBan, MTClient},
+    try_lock,
+};
+
+use super::Server;
+
+impl Server {
+    fn purge_expired_bans(&self) {
+        try_lock!(self.server_bans).retain(|_, ban| !ban.is_expired());
+    }
+
+    /// Agrega el ban y desconecta a los clientes locales alcanzados por el.
+    pub fn add_server_ban(&self, ban: ServerBan) {
+        let reason = format!("{}-Lined: {}", ban.kind(), ban.reason);
+        let affected: Vec<MTClient> = try_lock!(self.clients)
+            .values()
+            .filter(|c| {
+                let c = try_lock!(c);
+                ban.matches(&c.username, c.address.as_ref())
+            })
+            .cloned()
+            .collect();
+
+        try_lock!(self.server_bans).insert(ban.mask.to_owned(), ban);
+
+        for client in affected {
+            self.kill_local_client(client, &reason);
+        }
+    }
+
+    /// Saca el ban de la mascara si es del tipo pedido. Devuelve si existia.
+    pub fn remove_server_ban(&self, mask: &str, global: bool) -> bool {
+        let mut bans = try_lock!(self.server_bans);
+        match bans.get(mask) {
+            Some(ban) if ban.global == global => bans.remove(mask).is_some(),
+            _ => false,
+        }
+    }
+
+    pub fn find_server_ban(&self, username: &str, address: Option<&IpAddr>) -> Option<ServerBan> {
+        self.purge_expired_bans();
+        try_lock!(self.server_bans)
+            .values()
+            .find(|ban| ban.matches(username, address))
+            .cloned()
+    }
+
+    pub fn describe_server_bans(&self, global: bool) -> Vec<String> {
+        self.purge_expired_bans();
+        let mut bans: Vec<String> = try_lock!(self.server_bans)
+            .values()
+            .filter(|ban| ban.global == global)
+            .map(|ban| ban.describe())
+            .collect();
+        bans.sort();
+        bans
+    }
+
+    /// Mensajes para sincronizar las G-lines con un server recien conectado.
+    pub fn gline_messages(&self) -> Vec<String> {
+        self.purge_expired_bans();
+        try_lock!(self.server_bans)
+            .values()
+            .filter(|ban| ban.global)
+            .map(|ban| ban.gline_message(&self.host))
+            .collect()
+    }
+}
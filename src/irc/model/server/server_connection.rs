@@ -1,15 +1,24 @@
 //! Modulo que se centra en las funcionalidades referentes a la coneccion por parte del server.
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crate::{
     irc::{
         constants::{DEFAULT_SERVERNAME, ERR_ALREADYREGISTRED, ERR_NOSUCHSERVER},
-        model::{MTServerConnection, ServerError},
+        message::utils::mask_matches,
+        model::{irc_key::IrcKey, MTServerConnection, ServerError},
     },
     try_lock,
 };
 
-use super::Server;
+use super::{Netjoin, Server};
+
+/// Lo que se espera el EOB de un link antes de dar por terminado su burst,
+/// por si el otro server no lo manda.
+const NETJOIN_TIMEOUT: Duration = Duration::from_secs(30);
 
 impl Server {
     fn server_messages<'a, T>(&self, server_conns: T) -> Vec<String>
@@ -34,7 +43,7 @@ impl Server {
                 ));
             } else if let Some(uplink) = &server.uplink {
                 commands.push(format!(
-                    "{} SERVER {} {} :{} Server",
+                    ":{} SERVER {} {} :{} Server",
                     uplink,
                     server.servername,
                     server.hopcount + 1,
@@ -54,7 +63,7 @@ impl Server {
         {
             let sv_name = { try_lock!(sv_conn).servername.to_owned() };
             let mut servers = try_lock!(self.sv_connections);
-            if servers.contains_key(&sv_name) {
+            if servers.contains_key(&sv_name) || sv_name == self.host {
                 return Err(ServerError {
                     code: ERR_ALREADYREGISTRED,
                     msg: "You may not register".to_owned(),
@@ -63,6 +72,7 @@ impl Server {
 
             commands.append(&mut self.server_messages(servers.values()));
 
+            self.open_netjoin(&sv_name);
             servers.insert(sv_name, sv_conn.clone());
         }
 
@@ -77,6 +87,7 @@ impl Server {
             commands.push(channel.moderated_message());
             commands.push(channel.limit_message());
             commands.push(channel.key_message());
+            commands.append(&mut channel.masks_messages());
             if let Some(v) = channel.topic_message() {
                 commands.push(v);
             }
@@ -95,6 +106,8 @@ impl Server {
             commands.append(&mut client.channels_operator_messages());
             commands.append(&mut client.channels_messages());
         }
+        commands.append(&mut self.gline_messages());
+        commands.push(format!(":{} EOB", self.host));
         {
             let mut c = try_lock!(sv_conn);
             for com in commands {
@@ -133,7 +146,7 @@ impl Server {
         svs_to_remove
     }
 
-    fn remove_affected_clients(&self, svs_to_delete: &[String]) {
+    fn remove_affected_clients(&self, svs_to_delete: &[String], reason: &str) {
         let affected = {
             let clients = try_lock!(self.clients);
             let mut affected = vec![];
@@ -151,24 +164,198 @@ impl Server {
             affected
         };
 
+        let mut nicknames = vec![];
         for client in affected {
-            self.quit_client("Connection to server lost".to_owned(), client);
+            nicknames.push(self.disconnect_client(client));
         }
+
+        self.notify_netsplit(reason, &nicknames);
+    }
+
+    fn uplink_of(&self, svname: &str, sv_conns: &HashMap<String, MTServerConnection>) -> String {
+        let uplink = match sv_conns.get(svname) {
+            Some(sv) => try_lock!(sv).uplink.to_owned(),
+            None => None,
+        };
+        // Directly connected servers have no uplink, I'm the other end of the link
+        uplink.unwrap_or_else(|| self.host.to_owned())
     }
 
     pub fn delete_server_by_name(&self, svname: &str) {
-        let mut sv_conns = try_lock!(self.sv_connections);
-        let rem = self.servers_to_remove(svname, &sv_conns);
+        let (rem, reason) = {
+            let sv_conns = try_lock!(self.sv_connections);
+            let rem = self.servers_to_remove(svname, &sv_conns);
+            let reason = format!("{} {}", self.uplink_of(svname, &sv_conns), svname);
+            (rem, reason)
+        };
 
         println!("to delete: {:?}", rem);
 
-        self.remove_affected_clients(&rem);
+        // The links are released meanwhile, clients come before them in the lock order
+        self.remove_affected_clients(&rem, &reason);
 
+        let mut sv_conns = try_lock!(self.sv_connections);
+        let mut netjoins = try_lock!(self.netjoins);
         for sv_name in rem {
+            let _ = netjoins.remove(&sv_name);
             let _ = sv_conns.remove(&sv_name);
         }
     }
 
+    /// Procesa un SQUIT pedido por un operador. Si el server esta conectado directamente
+    /// se corta el link, si no el pedido se enruta hacia el server objetivo.
+    pub fn squit_server(
+        &self,
+        servername: &str,
+        comment: &str,
+        requester: &str,
+    ) -> Result<(), ServerError> {
+        if servername == self.host {
+            return Err(ServerError {
+                code: ERR_NOSUCHSERVER,
+                msg: format!("{} :Can't SQUIT the local server", servername),
+            });
+        }
+        let hopcount = match try_lock!(self.sv_connections).get(servername) {
+            Some(sv) => try_lock!(sv).hopcount,
+            None => {
+                return Err(ServerError {
+                    code: ERR_NOSUCHSERVER,
+                    msg: format!("{} :No such server", servername),
+                })
+            }
+        };
+
+        if hopcount == 1 {
+            self.close_server_link(servername, comment);
+            return Ok(());
+        }
+
+        let message = format!(":{} SQUIT {} :{}", requester, servername, comment);
+        self.replicate_to_servername(&message, servername, None)
+    }
+
+    /// Indica si el server ya forma parte de la red, incluyendome.
+    /// Resuelve el destino de una consulta: una mascara de server o el nickname
+    /// de un cliente, en cuyo caso se usa el server al que esta conectado.
+    pub fn resolve_target_server(&self, target: &str) -> Option<String> {
+        if mask_matches(target, &self.host) {
+            return Some(self.host.to_owned());
+        }
+
+        let mut servers: Vec<String> = try_lock!(self.sv_connections)
+            .keys()
+            .filter(|name| mask_matches(target, name))
+            .cloned()
+            .collect();
+        servers.sort();
+        if let Some(name) = servers.into_iter().next() {
+            return Some(name);
+        }
+
+        let client = self.get_client_by_nickname(target)?;
+        let servername = try_lock!(client).servername.to_owned();
+        Some(servername)
+    }
+
+    pub fn is_known_server(&self, servername: &str) -> bool {
+        servername == self.host || try_lock!(self.sv_connections).contains_key(servername)
+    }
+
+    pub fn close_server_link(&self, servername: &str, comment: &str) {
+        let sv_conn = { try_lock!(self.sv_connections).get(servername).cloned() };
+        if let Some(sv) = sv_conn {
+            let mut sv = try_lock!(sv);
+            sv.write_line(&format!("SQUIT {} :{}", self.host, comment));
+            sv.shutdown();
+        }
+
+        let message = format!(":{} SQUIT {} :{}", self.host, servername, comment);
+        self.replicate_to_all_servers_sans_origin(&message, servername);
+        self.delete_server_by_name(servername);
+    }
+
+    /// Da de baja un link que se cerro sin SQUIT, porque el otro server se cayo o se corto la red:
+    /// para el resto de la red es un netsplit. Si el link ya se habia dado de baja no hace nada.
+    pub fn lose_server_link(&self, sv_conn: &MTServerConnection, comment: &str) {
+        let servername = { try_lock!(sv_conn).servername.to_owned() };
+        let registered = { try_lock!(self.sv_connections).get(&servername).cloned() };
+        if registered.is_some_and(|sv| Arc::ptr_eq(&sv, sv_conn)) {
+            self.close_server_link(&servername, comment);
+        }
+    }
+
+    /// Corta un link que todavia no se registro. El resto de la red no lo conoce,
+    /// asi que no hay SQUIT que replicar: solo se cierra la conexion local.
+    pub fn drop_unregistered_link(&self, sv_conn: &MTServerConnection, comment: &str) {
+        {
+            let mut sv = try_lock!(sv_conn);
+            sv.write_line(&format!("SQUIT {} :{}", self.host, comment));
+            sv.shutdown();
+        }
+
+        let mut sv_conns = try_lock!(self.sv_connections);
+        if sv_conns
+            .get(DEFAULT_SERVERNAME)
+            .is_some_and(|sv| Arc::ptr_eq(sv, sv_conn))
+        {
+            sv_conns.remove(DEFAULT_SERVERNAME);
+            try_lock!(self.netjoins).remove(DEFAULT_SERVERNAME);
+        }
+    }
+
+    /// Empieza a acumular los clientes que llegan por el link durante su burst.
+    pub fn open_netjoin(&self, link: &str) {
+        try_lock!(self.netjoins)
+            .entry(link.to_owned())
+            .or_insert_with(|| Netjoin {
+                opened: Instant::now(),
+                nicknames: HashSet::new(),
+            });
+    }
+
+    pub fn add_to_netjoin(&self, link: &str, nickname: &str) {
+        if let Some(netjoin) = try_lock!(self.netjoins).get_mut(link) {
+            netjoin.nicknames.insert(IrcKey::from(nickname));
+        }
+    }
+
+    pub fn is_netjoining(&self, nickname: &str) -> bool {
+        self.expire_netjoins();
+        let nickname = IrcKey::from(nickname);
+        try_lock!(self.netjoins)
+            .values()
+            .any(|netjoin| netjoin.nicknames.contains(&nickname))
+    }
+
+    /// Da por terminados los bursts de los links que no mandaron EOB a tiempo,
+    /// para que sus clientes no se sigan ocultando a los que soportan batch.
+    fn expire_netjoins(&self) {
+        let expired: Vec<String> = try_lock!(self.netjoins)
+            .iter()
+            .filter(|(_, netjoin)| netjoin.opened.elapsed() >= NETJOIN_TIMEOUT)
+            .map(|(link, _)| link.to_owned())
+            .collect();
+        for link in expired {
+            self.finish_netjoin(&link, &link);
+        }
+    }
+
+    /// Termina el burst del link y notifica el netjoin de los clientes acumulados.
+    pub fn finish_netjoin(&self, link: &str, servername: &str) {
+        let mut nicknames: Vec<String> = match try_lock!(self.netjoins).remove(link) {
+            Some(netjoin) => netjoin.nicknames.iter().map(|n| n.to_string()).collect(),
+            None => return,
+        };
+        nicknames.sort();
+        let uplink = {
+            let sv_conns = try_lock!(self.sv_connections);
+            self.uplink_of(servername, &sv_conns)
+        };
+
+        self.notify_netjoin(&format!("{} {}", uplink, servername), &nicknames);
+    }
+
     pub fn write_to_server(&self, sv_conn: MTServerConnection, msg: &str) {
         let mut sv = try_lock!(sv_conn);
 
@@ -186,7 +373,11 @@ impl Server {
             {
                 try_lock!(sv).servername = new_servername.to_owned();
             }
-            svconns.insert(new_servername, sv);
+            svconns.insert(new_servername.to_owned(), sv);
+        }
+        let mut netjoins = try_lock!(self.netjoins);
+        if let Some(nicknames) = netjoins.remove(DEFAULT_SERVERNAME) {
+            netjoins.insert(new_servername, nicknames);
         }
     }
 
@@ -234,12 +425,13 @@ impl Server {
                     server_connection.write_line(message);
                     return Ok(());
                 }
-                match &server_connection.uplink {
-                    Some(uplink) => {
-                        self.replicate_to_servername(message, target_server, Some(uplink))
-                    }
-                    None => Err(nosuchserver),
-                }
+                let uplink = match &server_connection.uplink {
+                    Some(uplink) => uplink.to_owned(),
+                    None => return Err(nosuchserver),
+                };
+                drop(server_connection);
+                // The map is already locked, so recurse without locking it again
+                self._replicate_to_servername(message, target_server, Some(&uplink), sv_conns)
             }
             None => Err(nosuchserver),
         }
@@ -269,3 +461,154 @@ impl Server {
         self.replicate_to_all_servers_sans_origin(&msg, &origin);
     }
 }
+
+#[cfg(test)]
+mod server_connection_tests {
+    use std::{
+        io::{BufRead, BufReader, ErrorKind},
+        net::{TcpListener, TcpStream},
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::irc::{
+        message::{generic_message::GenericMessage, server::Sv, FromGeneric, ServerExecutable},
+        model::{
+            server::{ConnectionLimits, ServerFiles},
+            server_connection::ServerConnection,
+            utils::mt,
+        },
+    };
+
+    /// Link directo con el socket del otro extremo, para leer lo que le manda el server.
+    fn link(servername: &str) -> (MTServerConnection, BufReader<TcpStream>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (remote, _) = listener.accept().unwrap();
+        remote
+            .set_read_timeout(Some(Duration::from_millis(200)))
+            .unwrap();
+        let (sv_conn, _) =
+            ServerConnection::create(servername.to_owned(), "pass".to_owned(), stream).unwrap();
+        (mt(sv_conn), BufReader::new(remote))
+    }
+
+    fn next_line(remote: &mut BufReader<TcpStream>) -> Option<String> {
+        let mut line = String::new();
+        match remote.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_) => Some(line.trim_end().to_owned()),
+            Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {
+                Some("<nothing>".to_owned())
+            }
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    fn introduce(server: &Server, line: &str, origin: &MTServerConnection) {
+        let generic = GenericMessage::parse(line).unwrap();
+        Sv::from_generic(generic)
+            .unwrap()
+            .execute_for_server(server, origin.clone());
+    }
+
+    #[test]
+    fn test_loop_on_an_unregistered_link_only_closes_it() {
+        let server = Server::new(
+            "a.net".to_owned(),
+            ConnectionLimits::default(),
+            vec![],
+            ServerFiles::default(),
+            None,
+        );
+        let (registered, mut registered_remote) = link("c.net");
+        server.add_data_server_connection(registered);
+        server.add_data_server_connection(mt(ServerConnection::for_data(
+            "b.net".to_owned(),
+            2,
+            Some("c.net".to_owned()),
+        )));
+        let (unknown, mut unknown_remote) = link(DEFAULT_SERVERNAME);
+        server.add_data_server_connection(unknown.clone());
+
+        introduce(&server, "SERVER b.net 1 :b.net Server", &unknown);
+
+        assert_eq!(
+            next_line(&mut unknown_remote).as_deref(),
+            Some("SQUIT a.net :Server b.net already exists")
+        );
+        assert_eq!(next_line(&mut unknown_remote), None);
+        assert_eq!(
+            next_line(&mut registered_remote).as_deref(),
+            Some("<nothing>")
+        );
+        assert!(!server.is_known_server(DEFAULT_SERVERNAME));
+        assert!(server.is_known_server("b.net"));
+    }
+
+    #[test]
+    fn test_netjoining_nicknames_use_the_casemapping() {
+        let server = Server::new(
+            "a.net".to_owned(),
+            ConnectionLimits::default(),
+            vec![],
+            ServerFiles::default(),
+            None,
+        );
+        server.open_netjoin("b.net");
+        server.add_to_netjoin("b.net", "Valen[]");
+
+        assert!(server.is_netjoining("valen{}"));
+        assert!(server.is_netjoining("VALEN[]"));
+        assert!(!server.is_netjoining("valen"));
+    }
+
+    #[test]
+    fn test_netjoin_without_eob_expires() {
+        let server = Server::new(
+            "a.net".to_owned(),
+            ConnectionLimits::default(),
+            vec![],
+            ServerFiles::default(),
+            None,
+        );
+        server.open_netjoin("b.net");
+        server.add_to_netjoin("b.net", "valen");
+        assert!(server.is_netjoining("valen"));
+
+        // The link never sent EOB
+        if let Some(netjoin) = try_lock!(server.netjoins).get_mut("b.net") {
+            netjoin.opened -= NETJOIN_TIMEOUT;
+        }
+
+        assert!(!server.is_netjoining("valen"));
+        assert!(try_lock!(server.netjoins).is_empty());
+    }
+
+    #[test]
+    fn test_loop_on_a_registered_link_squits_it_across_the_net() {
+        let server = Server::new(
+            "a.net".to_owned(),
+            ConnectionLimits::default(),
+            vec![],
+            ServerFiles::default(),
+            None,
+        );
+        let (other, mut other_remote) = link("c.net");
+        server.add_data_server_connection(other);
+        let (origin, mut origin_remote) = link("d.net");
+        server.add_data_server_connection(origin.clone());
+
+        introduce(&server, ":d.net SERVER c.net 2 :c.net Server", &origin);
+
+        assert_eq!(
+            next_line(&mut origin_remote).as_deref(),
+            Some("SQUIT a.net :Server c.net already exists")
+        );
+        assert_eq!(
+            next_line(&mut other_remote).as_deref(),
+            Some(":a.net SQUIT d.net :Server c.net already exists")
+        );
+        assert!(!server.is_known_server("d.net"));
+    }
+}
@@ -0,0 +1,156 @@
+//! Modulo que se centra en las funcionalidades referentes a las estadisticas y el trazado del server.
+use std::collections::HashMap;
+
+use crate::{
+    irc::model::{utils::unix_time, MTServerConnection},
+    try_lock,
+};
+
+use super::Server;
+
+impl Server {
+    pub fn record_command(&self, command: &str, bytes: usize, remote: bool) {
+        let mut usage = try_lock!(self.command_usage);
+        let entry = usage.entry(command.to_owned()).or_default();
+        entry.count += 1;
+        entry.bytes += bytes as u64;
+        if remote {
+            entry.remote_count += 1;
+        }
+    }
+
+    /// Trafico de cada cliente local y cada server conectado directamente.
+    pub fn describe_link_stats(&self) -> Vec<String> {
+        let mut links = vec![];
+        for client in try_lock!(self.clients).values() {
+            let c = try_lock!(client);
+            if c.servername != self.host {
+                continue;
+            }
+            links.push(format!(
+                "{}[{}@{}] {}",
+                c.nickname,
+                c.username,
+                c.hostname,
+                c.stats.describe(c.sendq())
+            ));
+        }
+
+        for sv_connection in try_lock!(self.sv_connections).values() {
+            let sv = try_lock!(sv_connection);
+            if sv.hopcount == 1 {
+                links.push(format!(
+                    "{} {}",
+                    sv.servername,
+                    sv.stats.describe(sv.sendq())
+                ));
+            }
+        }
+
+        links.sort();
+        links
+    }
+
+    pub fn describe_command_usage(&self) -> Vec<String> {
+        let mut commands: Vec<String> = try_lock!(self.command_usage)
+            .iter()
+            .map(|(command, usage)| {
+                format!(
+                    "{} {} {} {}",
+                    command, usage.count, usage.bytes, usage.remote_count
+                )
+            })
+            .collect();
+        commands.sort();
+        commands
+    }
+
+    pub fn describe_uptime(&self) -> String {
+        let uptime = unix_time().saturating_sub(self.started_at);
+        format!(
+            "Server Up {} days {}:{:02}:{:02}",
+            uptime / 86400,
+            (uptime % 86400) / 3600,
+            (uptime % 3600) / 60,
+            uptime % 60
+        )
+    }
+
+    /// El unico bloque de operadores: cualquier usuario, desde cualquier host,
+    /// que se identifique con su username y la contraseña del server.
+    pub fn describe_oper_blocks(&self) -> Vec<String> {
+        vec!["O *@* * *".to_owned()]
+    }
+
+    fn _next_hop(
+        &self,
+        servername: &str,
+        sv_conns: &HashMap<String, MTServerConnection>,
+    ) -> Option<String> {
+        let mut current = servername.to_owned();
+        // Each step goes one hop closer, a longer walk means the uplinks loop
+        for _ in 0..=sv_conns.len() {
+            let sv = try_lock!(sv_conns.get(&current)?);
+            if sv.hopcount == 1 {
+                return Some(current);
+            }
+            current = sv.uplink.to_owned()?;
+        }
+        None
+    }
+
+    /// Server conectado directamente por el que se llega a servername.
+    pub fn next_hop(&self, servername: &str) -> Option<String> {
+        let sv_conns = try_lock!(self.sv_connections);
+        self._next_hop(servername, &sv_conns)
+    }
+
+    /// Clientes locales a informar en TRACE como (nickname, es operador).
+    /// Si se pide un nickname solo se informa ese cliente.
+    pub fn trace_clients(&self, only: Option<&str>) -> Vec<(String, bool)> {
+        let mut clients: Vec<(String, bool)> = try_lock!(self.clients)
+            .values()
+            .filter_map(|client| {
+                let c = try_lock!(client);
+                if c.servername != self.host || only.is_some_and(|n| n != c.nickname) {
+                    return None;
+                }
+                Some((c.nickname.to_owned(), c.server_operator))
+            })
+            .collect();
+        clients.sort();
+        clients
+    }
+
+    /// Servers conectados directamente a informar en TRACE como
+    /// (servername, servers alcanzables por ese link, clientes detras de ese link).
+    pub fn trace_servers(&self) -> Vec<(String, usize, usize)> {
+        let client_servers: Vec<String> = try_lock!(self.clients)
+            .values()
+            .map(|c| try_lock!(c).servername.to_owned())
+            .collect();
+
+        let sv_conns = try_lock!(self.sv_connections);
+        let mut links: HashMap<String, (usize, usize)> = HashMap::new();
+        for servername in sv_conns.keys() {
+            if let Some(hop) = self._next_hop(servername, &sv_conns) {
+                links.entry(hop).or_default().0 += 1;
+            }
+        }
+        for servername in client_servers {
+            if servername == self.host {
+                continue;
+            }
+            if let Some(hop) = self._next_hop(&servername, &sv_conns) {
+                links.entry(hop).or_default().1 += 1;
+            }
+        }
+
+        let mut servers: Vec<(String, usize, usize)> = links
+            .into_iter()
+            .map(|(servername, (servers, clients))| (servername, servers, clients))
+            .collect();
+        servers.sort();
+        servers
+    }
+}
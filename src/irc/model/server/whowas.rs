@@ -0,0 +1,41 @@
+//! Modulo que se centra en las funcionalidades referentes al historial de nicknames del server.
+use crate::{
+    irc::{
+        constants::WHOWAS_HISTORY_LEN,
+        message::utils::mask_matches,
+        model::{client::Client, utils::unix_time},
+    },
+    try_lock,
+};
+
+use super::{Server, WhowasEntry};
+
+impl Server {
+    /// Guarda la identidad actual del cliente, descartando la entrada mas vieja si no hay lugar.
+    pub fn record_whowas(&self, client: &Client) {
+        let mut history = try_lock!(self.whowas);
+        if history.len() >= WHOWAS_HISTORY_LEN {
+            history.pop_front();
+        }
+
+        history.push_back(WhowasEntry {
+            nickname: client.nickname.to_owned(),
+            username: client.username.to_owned(),
+            hostname: client.hostname.to_owned(),
+            realname: client.realname.to_owned(),
+            servername: client.servername.to_owned(),
+            signoff: unix_time(),
+        });
+    }
+
+    /// Entradas del nickname de la mas reciente a la mas vieja. Con count se limita la cantidad.
+    pub fn whowas(&self, nickname: &str, count: Option<usize>) -> Vec<WhowasEntry> {
+        try_lock!(self.whowas)
+            .iter()
+            .rev()
+            .filter(|entry| mask_matches(nickname, &entry.nickname))
+            .take(count.unwrap_or(WHOWAS_HISTORY_LEN))
+            .cloned()
+            .collect()
+    }
+}
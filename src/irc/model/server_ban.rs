@@ -0,0 +1,153 @@
+//! Modulo que se centra en las funcionalidades referentes a los baneos de server (K-lines y G-lines).
+use std::net::IpAddr;
+
+use crate::irc::message::utils::{cidr_matches, mask_matches, validate_server_ban_mask};
+
+use super::utils::{deserialize_bool, deserialize_err, deserialize_num, serialize_bool, unix_time};
+
+#[derive(Debug, Clone)]
+pub struct ServerBan {
+    pub mask: String,
+    pub reason: String,
+    pub setter: String,
+    /// Momento de expiracion en segundos desde epoch, None si es permanente.
+    pub expires: Option<u64>,
+    /// Las G-lines se replican a toda la red, las K-lines son locales.
+    pub global: bool,
+}
+
+enum Serialize {
+    Mask = 0,
+    Global,
+    Expires,
+    Setter,
+    Reason,
+}
+
+pub const SERVER_BAN_FIELDS: usize = 5;
+
+// Creation
+impl ServerBan {
+    /// La duracion es en minutos, 0 significa permanente.
+    pub fn create(
+        mask: String,
+        reason: String,
+        setter: String,
+        duration: u64,
+        global: bool,
+    ) -> Self {
+        let expires = match duration {
+            0 => None,
+            d => Some(unix_time() + d * 60),
+        };
+
+        ServerBan {
+            mask,
+            reason,
+            setter,
+            expires,
+            global,
+        }
+    }
+
+    pub fn deserialize(data: &[&str]) -> Result<ServerBan, String> {
+        if data.len() != SERVER_BAN_FIELDS {
+            return Err(deserialize_err("Invalid format"));
+        }
+
+        let mask = data[Serialize::Mask as usize];
+        if validate_server_ban_mask(Some(mask.as_bytes())).is_err() {
+            return Err(deserialize_err("Invalid ban mask"));
+        }
+
+        let mut expires = None;
+        if !data[Serialize::Expires as usize].is_empty() {
+            expires = Some(deserialize_num(data[Serialize::Expires as usize])?);
+        }
+
+        Ok(ServerBan {
+            mask: mask.to_owned(),
+            reason: data[Serialize::Reason as usize].to_owned(),
+            setter: data[Serialize::Setter as usize].to_owned(),
+            expires,
+            global: deserialize_bool(data[Serialize::Global as usize])?,
+        })
+    }
+
+    pub fn serialize(&self) -> Vec<String> {
+        let mut r: Vec<String> = vec![String::new(); SERVER_BAN_FIELDS];
+        r[Serialize::Mask as usize] = self.mask.to_owned();
+        r[Serialize::Global as usize] = serialize_bool(self.global);
+        r[Serialize::Expires as usize] = self.expires.map(|e| e.to_string()).unwrap_or_default();
+        r[Serialize::Setter as usize] = self.setter.to_owned();
+        r[Serialize::Reason as usize] = self.reason.to_owned();
+        r
+    }
+}
+
+// Matching
+impl ServerBan {
+    pub fn is_expired(&self) -> bool {
+        match self.expires {
+            Some(e) => e <= unix_time(),
+            None => false,
+        }
+    }
+
+    /// Minutos que le quedan al ban, redondeando para arriba. 0 si es permanente.
+    pub fn remaining_minutes(&self) -> u64 {
+        match self.expires {
+            Some(e) => e.saturating_sub(unix_time()).div_ceil(60).max(1),
+            None => 0,
+        }
+    }
+
+    pub fn matches(&self, username: &str, address: Option<&IpAddr>) -> bool {
+        let (user, host) = match self.mask.split_once('@') {
+            Some(v) => v,
+            None => return false,
+        };
+
+        if !mask_matches(user, username) {
+            return false;
+        }
+
+        match address {
+            Some(a) if host.contains('/') => cidr_matches(host, a),
+            Some(a) => mask_matches(host, &a.to_string()),
+            None => false,
+        }
+    }
+}
+
+// String representation
+impl ServerBan {
+    pub fn kind(&self) -> &str {
+        if self.global {
+            "G"
+        } else {
+            "K"
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "{} {} {} {} :{}",
+            self.kind(),
+            self.mask,
+            self.remaining_minutes(),
+            self.setter,
+            self.reason
+        )
+    }
+
+    pub fn gline_message(&self, prefix: &str) -> String {
+        format!(
+            ":{} GLINE {} {} :{}",
+            prefix,
+            self.remaining_minutes(),
+            self.mask,
+            self.reason
+        )
+    }
+}
@@ -1,24 +1,24 @@
 //! Modulo que se centra en las funcionalidades referentes a la representacion de conecciones de servers.
-use std::{
-    io::Write,
-    net::{Shutdown, TcpStream},
-};
+use std::net::{Shutdown, TcpStream};
 
 use crate::unwrap;
 
-use super::{connection::Connection, ConnectionError};
+use super::{
+    connection::Connection, link_stats::LinkStats, writer::ConnectionWriter, ConnectionError,
+};
 
 #[derive(Debug)]
 pub struct ServerConnection {
     pub servername: String,
     pub password: Option<String>,
     pub hopcount: u32,
-    pub write_stream: Option<TcpStream>,
+    pub write_stream: Option<ConnectionWriter>,
     pub uplink: Option<String>,
+    pub stats: LinkStats,
 }
 
 impl ServerConnection {
-    pub fn from_connection(connection: Connection) -> Result<ServerConnection, ()> {
+    pub(crate) fn from_connection(connection: Connection) -> Result<ServerConnection, ()> {
         let servername = unwrap!(connection.servername);
         let password = unwrap!(connection.password); // This has to exist
         let hopcount = unwrap!(connection.hopcount);
@@ -28,6 +28,7 @@ impl ServerConnection {
             hopcount,
             write_stream: Some(connection.write_stream),
             uplink: connection.uplink,
+            stats: LinkStats::default(),
         })
     }
 
@@ -46,8 +47,9 @@ impl ServerConnection {
                 servername,
                 password: Some(password),
                 hopcount: 1,
-                write_stream: Some(stream),
+                write_stream: Some(ConnectionWriter::new(stream)),
                 uplink: None,
+                stats: LinkStats::default(),
             },
             ss,
         ))
@@ -60,6 +62,7 @@ impl ServerConnection {
             hopcount,
             write_stream: None,
             uplink,
+            stats: LinkStats::default(),
         }
     }
 }
@@ -67,12 +70,22 @@ impl ServerConnection {
 impl ServerConnection {
     pub fn write_line(&mut self, msg: &str) {
         if let Some(s) = &mut self.write_stream {
-            if let Err(e) = s.write(format!("{}\r\n", msg).as_bytes()) {
-                println!("[TO SERVER] Write failed.\n{}", e);
+            match s.write_line(msg) {
+                Ok(written) => self.stats.record_sent(written),
+                Err(e) => println!("[TO SERVER] Write failed.\n{}", e),
             };
         }
     }
 
+    /// Bytes que todavia no se le pudieron mandar al server.
+    pub fn sendq(&self) -> usize {
+        self.write_stream.as_ref().map_or(0, |s| s.pending())
+    }
+
+    pub fn sendq_exceeded(&self) -> bool {
+        self.write_stream.as_ref().is_some_and(|s| s.exceeded())
+    }
+
     pub fn shutdown(&self) {
         if let Some(stream) = &self.write_stream {
             let _ = stream.shutdown(Shutdown::Both);
@@ -0,0 +1,296 @@
+//! Modulo que se centra en las funcionalidades referentes a los comandos que manda una sesion.
+use std::io;
+
+use crate::irc::message::{monitor::MonitorAction, serializer::MessageSerializer, Command};
+
+use super::SessionSender;
+
+fn bytes<'a>(items: &[&'a str]) -> Vec<&'a [u8]> {
+    items.iter().map(|i| i.as_bytes()).collect()
+}
+
+/// Serializa el comando con los parametros y, si hay, el texto final.
+fn serializer(command: Command, parameters: &[&str], trailing: Option<&str>) -> MessageSerializer {
+    let mut s = MessageSerializer::new(None, command);
+    for p in parameters {
+        s = s.add_parameter(p.as_bytes());
+    }
+    if let Some(t) = trailing {
+        s = s.add_trailing_params(&[t.as_bytes()]);
+    }
+    s
+}
+
+/// Un metodo por cada comando que un cliente le puede mandar al server. SERVER, EOB y REPLY
+/// no estan porque solo los usan los links entre servers.
+/// Los parametros con CR, LF o NUL se rechazan con InvalidInput, no se puede meter otro comando.
+impl SessionSender {
+    /// Manda la linea tal cual, sin validar el comando ni los parametros.
+    /// Como cualquier linea, se rechaza si tiene CR, LF o NUL.
+    pub fn send_raw(&mut self, line: &str) -> io::Result<()> {
+        self.connection.send(line)
+    }
+
+    fn send(&mut self, serializer: MessageSerializer) -> io::Result<()> {
+        self.connection.send(&serializer.serialize())
+    }
+
+    /// Otra mitad de escritura sobre la misma conexion, por ejemplo para otro hilo.
+    pub fn try_clone(&self) -> io::Result<SessionSender> {
+        Ok(SessionSender {
+            connection: self.connection.try_clone()?,
+        })
+    }
+
+    pub fn pass(&mut self, password: &str) -> io::Result<()> {
+        self.send(serializer(Command::Password, &[password], None))
+    }
+
+    pub fn nick(&mut self, nickname: &str) -> io::Result<()> {
+        self.send(serializer(Command::Nick, &[nickname], None))
+    }
+
+    pub fn user(&mut self, username: &str, realname: &str) -> io::Result<()> {
+        self.send(serializer(
+            Command::User,
+            &[username, username, username],
+            Some(realname),
+        ))
+    }
+
+    pub fn oper(&mut self, user: &str, password: &str) -> io::Result<()> {
+        self.send(serializer(Command::Oper, &[user, password], None))
+    }
+
+    pub fn quit(&mut self, message: Option<&str>) -> io::Result<()> {
+        self.send(serializer(Command::Quit, &[], message))
+    }
+
+    /// Sale de la red el server indicado, solo para operadores.
+    pub fn squit(&mut self, server: &str, message: &str) -> io::Result<()> {
+        self.send(serializer(Command::ServerQuit, &[server], Some(message)))
+    }
+
+    /// Entra a los canales, con las claves en el mismo orden si tienen.
+    pub fn join(&mut self, channels: &[&str], keys: &[&str]) -> io::Result<()> {
+        let mut s = MessageSerializer::new(None, Command::Join).add_csl_params(&bytes(channels));
+        if !keys.is_empty() {
+            s = s.add_csl_params(&bytes(keys));
+        }
+        self.send(s)
+    }
+
+    pub fn part(&mut self, channels: &[&str]) -> io::Result<()> {
+        self.send(MessageSerializer::new(None, Command::Part).add_csl_params(&bytes(channels)))
+    }
+
+    /// Cambia o consulta los modos de un canal o del propio usuario, por ejemplo
+    /// mode("#canal", "+o", &["nick"]) o mode("#canal", "", &[]) para consultarlos.
+    pub fn mode(&mut self, target: &str, modes: &str, arguments: &[&str]) -> io::Result<()> {
+        let mut s = serializer(Command::Mode, &[target], None);
+        if !modes.is_empty() {
+            s = s.add_parameter(modes.as_bytes());
+        }
+        for a in arguments {
+            s = s.add_parameter(a.as_bytes());
+        }
+        self.send(s)
+    }
+
+    /// Cambia el tema del canal, o lo consulta si no se pasa uno.
+    pub fn topic(&mut self, channel: &str, topic: Option<&str>) -> io::Result<()> {
+        self.send(serializer(Command::Topic, &[channel], topic))
+    }
+
+    pub fn names(&mut self, channels: &[&str]) -> io::Result<()> {
+        let mut s = MessageSerializer::new(None, Command::Names);
+        if !channels.is_empty() {
+            s = s.add_csl_params(&bytes(channels));
+        }
+        self.send(s)
+    }
+
+    pub fn list(&mut self, channels: &[&str]) -> io::Result<()> {
+        let mut s = MessageSerializer::new(None, Command::List);
+        if !channels.is_empty() {
+            s = s.add_csl_params(&bytes(channels));
+        }
+        self.send(s)
+    }
+
+    pub fn invite(&mut self, nickname: &str, channel: &str) -> io::Result<()> {
+        self.send(serializer(Command::Invite, &[nickname, channel], None))
+    }
+
+    pub fn kick(&mut self, channel: &str, nickname: &str, comment: Option<&str>) -> io::Result<()> {
+        self.send(serializer(Command::Kick, &[channel, nickname], comment))
+    }
+
+    /// Mensaje a un canal o a un nick.
+    pub fn privmsg(&mut self, target: &str, text: &str) -> io::Result<()> {
+        self.send(serializer(Command::PrivateMessage, &[target], Some(text)))
+    }
+
+    pub fn notice(&mut self, nickname: &str, text: &str) -> io::Result<()> {
+        self.send(serializer(Command::Notice, &[nickname], Some(text)))
+    }
+
+    /// Busca usuarios por mascara, las opciones son las de WHO (ej "o" o "%nuhr").
+    pub fn who(&mut self, mask: Option<&str>, options: Option<&str>) -> io::Result<()> {
+        let parameters: Vec<&str> = mask.into_iter().chain(options).collect();
+        self.send(serializer(Command::Who, &parameters, None))
+    }
+
+    pub fn whois(&mut self, nickmasks: &[&str]) -> io::Result<()> {
+        self.send(MessageSerializer::new(None, Command::WhoIs).add_csl_params(&bytes(nickmasks)))
+    }
+
+    pub fn whowas(&mut self, nickname: &str, count: Option<u32>) -> io::Result<()> {
+        let mut s = serializer(Command::Whowas, &[nickname], None);
+        if let Some(c) = count {
+            s = s.add_number(c);
+        }
+        self.send(s)
+    }
+
+    /// Se marca como ausente con el mensaje, o vuelve si no se pasa uno.
+    pub fn away(&mut self, message: Option<&str>) -> io::Result<()> {
+        self.send(serializer(Command::Away, &[], message))
+    }
+
+    /// Negocia capacidades, por ejemplo cap("REQ", &["batch"]).
+    pub fn cap(&mut self, subcommand: &str, capabilities: &[&str]) -> io::Result<()> {
+        self.send(
+            serializer(Command::Cap, &[subcommand], None).add_trailing_params(&bytes(capabilities)),
+        )
+    }
+
+    pub fn links(&mut self, mask: Option<&str>) -> io::Result<()> {
+        let parameters: Vec<&str> = mask.into_iter().collect();
+        self.send(serializer(Command::Links, &parameters, None))
+    }
+
+    pub fn map(&mut self) -> io::Result<()> {
+        self.send(serializer(Command::Map, &[], None))
+    }
+
+    /// Banea la mascara user@host de este server, por los minutos indicados o sin vencimiento.
+    pub fn kline(&mut self, minutes: Option<u64>, mask: &str, reason: &str) -> io::Result<()> {
+        self.send_ban(Command::Kline, minutes, mask, reason)
+    }
+
+    /// Como kline, pero el ban se replica en toda la red.
+    pub fn gline(&mut self, minutes: Option<u64>, mask: &str, reason: &str) -> io::Result<()> {
+        self.send_ban(Command::Gline, minutes, mask, reason)
+    }
+
+    fn send_ban(
+        &mut self,
+        command: Command,
+        minutes: Option<u64>,
+        mask: &str,
+        reason: &str,
+    ) -> io::Result<()> {
+        let minutes = minutes.map(|m| m.to_string());
+        let parameters: Vec<&str> = minutes.as_deref().into_iter().chain([mask]).collect();
+        self.send(serializer(command, &parameters, Some(reason)))
+    }
+
+    pub fn unkline(&mut self, mask: &str) -> io::Result<()> {
+        self.send(serializer(Command::Unkline, &[mask], None))
+    }
+
+    pub fn ungline(&mut self, mask: &str) -> io::Result<()> {
+        self.send(serializer(Command::Ungline, &[mask], None))
+    }
+
+    pub fn motd(&mut self, server: Option<&str>) -> io::Result<()> {
+        self.send_query(Command::Motd, server)
+    }
+
+    pub fn version(&mut self, server: Option<&str>) -> io::Result<()> {
+        self.send_query(Command::Version, server)
+    }
+
+    pub fn time(&mut self, server: Option<&str>) -> io::Result<()> {
+        self.send_query(Command::Time, server)
+    }
+
+    pub fn admin(&mut self, server: Option<&str>) -> io::Result<()> {
+        self.send_query(Command::Admin, server)
+    }
+
+    pub fn info(&mut self, server: Option<&str>) -> io::Result<()> {
+        self.send_query(Command::Info, server)
+    }
+
+    pub fn lusers(&mut self, server: Option<&str>) -> io::Result<()> {
+        self.send_query(Command::Lusers, server)
+    }
+
+    pub fn trace(&mut self, target: Option<&str>) -> io::Result<()> {
+        self.send_query(Command::Trace, target)
+    }
+
+    /// Consulta a este server o al indicado.
+    fn send_query(&mut self, command: Command, target: Option<&str>) -> io::Result<()> {
+        let parameters: Vec<&str> = target.into_iter().collect();
+        self.send(serializer(command, &parameters, None))
+    }
+
+    pub fn stats(&mut self, query: &str, server: Option<&str>) -> io::Result<()> {
+        let parameters: Vec<&str> = [query].into_iter().chain(server).collect();
+        self.send(serializer(Command::Stats, &parameters, None))
+    }
+
+    pub fn kill(&mut self, nickname: &str, reason: &str) -> io::Result<()> {
+        self.send(serializer(Command::Kill, &[nickname], Some(reason)))
+    }
+
+    pub fn wallops(&mut self, text: &str) -> io::Result<()> {
+        self.send(serializer(Command::Wallops, &[], Some(text)))
+    }
+
+    pub fn rehash(&mut self) -> io::Result<()> {
+        self.send(serializer(Command::Rehash, &[], None))
+    }
+
+    pub fn restart(&mut self) -> io::Result<()> {
+        self.send(serializer(Command::Restart, &[], None))
+    }
+
+    pub fn die(&mut self) -> io::Result<()> {
+        self.send(serializer(Command::Die, &[], None))
+    }
+
+    pub fn ison(&mut self, nicknames: &[&str]) -> io::Result<()> {
+        self.send(
+            MessageSerializer::new(None, Command::Ison).add_trailing_params(&bytes(nicknames)),
+        )
+    }
+
+    pub fn userhost(&mut self, nicknames: &[&str]) -> io::Result<()> {
+        self.send(serializer(Command::Userhost, nicknames, None))
+    }
+
+    pub fn monitor(&mut self, action: MonitorAction, targets: &[&str]) -> io::Result<()> {
+        let mut s = serializer(Command::Monitor, &[action.to_str()], None);
+        if !targets.is_empty() {
+            s = s.add_csl_params(&bytes(targets));
+        }
+        self.send(s)
+    }
+
+    pub fn knock(&mut self, channel: &str, reason: Option<&str>) -> io::Result<()> {
+        self.send(serializer(Command::Knock, &[channel], reason))
+    }
+
+    /// Muestra que filtros coinciden con el mensaje, solo para operadores.
+    pub fn testfilter(&mut self, source: &str, target: &str, text: &str) -> io::Result<()> {
+        self.send(serializer(
+            Command::TestFilter,
+            &[source, target],
+            Some(text),
+        ))
+    }
+}
@@ -0,0 +1,246 @@
+//! Modulo que se centra en las funcionalidades referentes a los eventos que recibe una sesion.
+use crate::irc::{
+    constants::{
+        RPL_CHANNELMODEIS, RPL_CHANNELOUT, RPL_NAMREPLY, RPL_NICKCHANGE, RPL_TOPIC, RPL_UMODEIS,
+    },
+    ctcp::utils::{get_ctcp_command, get_ctcp_message, get_dcc_command, upgrade_dcc_command},
+    ctcp::DccMessage,
+};
+
+/// Lo que el server le avisa a un cliente, interpretado desde cada linea que manda.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// Mensaje (PRIVMSG o NOTICE) a un canal, o privado si no tiene canal.
+    Message {
+        channel: Option<String>,
+        sender: String,
+        text: String,
+    },
+    /// El nick esta en el canal: llega cuando alguien entra y, al entrar uno, por cada miembro.
+    Join {
+        channel: String,
+        nickname: String,
+    },
+    /// El nick salio del canal, por PART o por KICK.
+    Part {
+        channel: String,
+        nickname: String,
+    },
+    Nick {
+        old: String,
+        new: String,
+    },
+    /// Modos de un canal o del propio usuario, como los describe el server (ej "+tn -psimlk").
+    Mode {
+        target: String,
+        modes: String,
+    },
+    Topic {
+        channel: String,
+        topic: String,
+    },
+    /// Respuesta numerica que no tiene un evento propio.
+    Numeric {
+        code: usize,
+        content: String,
+    },
+    /// Pedido de DCC (CHAT, SEND, RESUME o CLOSE) de otro cliente, sin el envoltorio CTCP.
+    DccOffer {
+        sender: String,
+        command: String,
+    },
+    /// Linea que no corresponde a ningun evento.
+    Other(String),
+}
+
+impl Event {
+    pub fn parse(line: &str) -> Event {
+        // Lines inside a batch carry its tag, the content is the same as outside
+        let line = match line.strip_prefix("@batch=") {
+            Some(tagged) => tagged.split_once(' ').map_or("", |(_, l)| l),
+            None => line,
+        };
+
+        if let Some(event) = Self::parse_numeric(line) {
+            return event;
+        }
+        if let Some(event) = Self::parse_message(line) {
+            return event;
+        }
+
+        Event::Other(line.to_owned())
+    }
+
+    /// El comando DCC listo para ejecutar, si el evento es un pedido de DCC.
+    pub fn dcc_message(&self) -> Option<Box<dyn DccMessage>> {
+        match self {
+            Event::DccOffer { command, .. } => upgrade_dcc_command(command),
+            _ => None,
+        }
+    }
+
+    fn parse_numeric(line: &str) -> Option<Event> {
+        let (code, content) = match line.split_once(':') {
+            Some((code, content)) => (code, content.strip_prefix(' ').unwrap_or(content)),
+            None => (line, ""),
+        };
+        let code = code.trim_end().parse::<usize>().ok()?;
+
+        let pair = || {
+            let (first, second) = content.split_once(' ')?;
+            Some((first.to_owned(), second.trim().to_owned()))
+        };
+        let event = match code {
+            RPL_NAMREPLY => pair().map(|(channel, nickname)| Event::Join { channel, nickname }),
+            RPL_CHANNELOUT => pair().map(|(channel, nickname)| Event::Part { channel, nickname }),
+            RPL_NICKCHANGE => pair().map(|(old, new)| Event::Nick { old, new }),
+            RPL_TOPIC => content
+                .split_once(" :")
+                .map(|(channel, topic)| Event::Topic {
+                    channel: channel.to_owned(),
+                    topic: topic.to_owned(),
+                }),
+            RPL_CHANNELMODEIS | RPL_UMODEIS => {
+                content.split_once(" :").map(|(target, modes)| Event::Mode {
+                    target: target.to_owned(),
+                    modes: modes.trim().to_owned(),
+                })
+            }
+            _ => None,
+        };
+
+        Some(event.unwrap_or(Event::Numeric {
+            code,
+            content: content.to_owned(),
+        }))
+    }
+
+    fn parse_message(line: &str) -> Option<Event> {
+        let (origin, text) = line.split_once(": ")?;
+        let tokens: Vec<&str> = origin.split(' ').collect();
+
+        let (channel, sender) = match tokens[..] {
+            [sender] => (None, sender),
+            [channel, sender] if channel.starts_with(['#', '&']) => (Some(channel), sender),
+            _ => return None,
+        };
+
+        if channel.is_none() {
+            let dcc = get_ctcp_message(line)
+                .and_then(get_ctcp_command)
+                .and_then(get_dcc_command);
+            if let Some(command) = dcc {
+                return Some(Event::DccOffer {
+                    sender: sender.to_owned(),
+                    command: command.trim().to_owned(),
+                });
+            }
+        }
+
+        Some(Event::Message {
+            channel: channel.map(|c| c.to_owned()),
+            sender: sender.to_owned(),
+            text: text.to_owned(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod event_parse_tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_and_private_messages() {
+        assert_eq!(
+            Event::parse("#rust alice: hello: world"),
+            Event::Message {
+                channel: Some("#rust".to_owned()),
+                sender: "alice".to_owned(),
+                text: "hello: world".to_owned(),
+            }
+        );
+        assert_eq!(
+            Event::parse("bob: psst"),
+            Event::Message {
+                channel: None,
+                sender: "bob".to_owned(),
+                text: "psst".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_presence_numerics() {
+        assert_eq!(
+            Event::parse("353: #rust alice"),
+            Event::Join {
+                channel: "#rust".to_owned(),
+                nickname: "alice".to_owned(),
+            }
+        );
+        assert_eq!(
+            Event::parse("1354: #rust alice"),
+            Event::Part {
+                channel: "#rust".to_owned(),
+                nickname: "alice".to_owned(),
+            }
+        );
+        assert_eq!(
+            Event::parse("2001: alice alicia"),
+            Event::Nick {
+                old: "alice".to_owned(),
+                new: "alicia".to_owned(),
+            }
+        );
+        assert_eq!(
+            Event::parse("@batch=1f 353: #rust carol"),
+            Event::Join {
+                channel: "#rust".to_owned(),
+                nickname: "carol".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_topic_mode_and_other_numerics() {
+        assert_eq!(
+            Event::parse("332: #rust :new topic: here"),
+            Event::Topic {
+                channel: "#rust".to_owned(),
+                topic: "new topic: here".to_owned(),
+            }
+        );
+        assert_eq!(
+            Event::parse("324: #rust : +tnm -psilk"),
+            Event::Mode {
+                target: "#rust".to_owned(),
+                modes: "+tnm -psilk".to_owned(),
+            }
+        );
+        assert_eq!(
+            Event::parse("433: alice :Nickname is already in use"),
+            Event::Numeric {
+                code: 433,
+                content: "alice :Nickname is already in use".to_owned(),
+            }
+        );
+        assert_eq!(
+            Event::parse("BATCH +1f netjoin"),
+            Event::Other("BATCH +1f netjoin".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_dcc_offer() {
+        let event = Event::parse("alice: \u{1}CTCP DCC CHAT CHAT 127.0.0.1 5000\u{1}");
+
+        assert_eq!(
+            event,
+            Event::DccOffer {
+                sender: "alice".to_owned(),
+                command: "CHAT CHAT 127.0.0.1 5000".to_owned(),
+            }
+        );
+        assert!(event.dcc_message().is_some());
+    }
+}
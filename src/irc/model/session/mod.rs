@@ -0,0 +1,266 @@
+//! Modulo que se centra en las funcionalidades referentes a la sesion de un cliente sin interfaz.
+use std::{
+    io,
+    net::ToSocketAddrs,
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
+
+use crate::irc::{
+    constants::{ERR_SERVERERR, RPL_SUCLOGIN},
+    model::client_connection::ClientConnection,
+};
+
+use self::event::Event;
+
+pub mod commands;
+pub mod event;
+
+#[derive(Debug)]
+pub enum SessionError {
+    Io(io::Error),
+    /// El server rechazo el registro con esta respuesta numerica.
+    Refused(usize, String),
+    /// El server cerro la conexion antes de terminar el registro.
+    Closed,
+}
+
+impl From<io::Error> for SessionError {
+    fn from(e: io::Error) -> Self {
+        SessionError::Io(e)
+    }
+}
+
+/// Mitad de escritura de una sesion, con un metodo por cada comando que puede mandar un cliente.
+/// Se puede clonar para mandar comandos desde otro hilo mientras la sesion lee eventos.
+#[derive(Debug)]
+pub struct SessionSender {
+    connection: ClientConnection,
+}
+
+/// Cliente registrado en un server, pensado para bots y programas sin la interfaz grafica.
+/// Los eventos se leen de a uno, como iterador o con un callback; los comandos se mandan
+/// con los metodos de SessionSender, que la sesion expone directamente.
+#[derive(Debug)]
+pub struct Session {
+    reader: ClientConnection,
+    sender: SessionSender,
+    nickname: String,
+}
+
+impl Session {
+    /// Se conecta y se registra con PASS, NICK y USER. Vuelve una vez que el server acepto
+    /// el registro, o con la respuesta con la que lo rechazo.
+    pub fn connect(
+        address: impl ToSocketAddrs,
+        password: &str,
+        nickname: &str,
+        username: &str,
+        realname: &str,
+    ) -> Result<Session, SessionError> {
+        let mut reader = ClientConnection::connect(address)?;
+        let sender = SessionSender {
+            connection: reader.try_clone()?,
+        };
+        reader.register(password, nickname, username, realname)?;
+
+        let mut session = Session {
+            reader,
+            sender,
+            nickname: nickname.to_owned(),
+        };
+        loop {
+            match session.next_event()? {
+                Some(Event::Numeric { code, .. }) if code == RPL_SUCLOGIN => return Ok(session),
+                Some(Event::Numeric { code, content }) if is_error(code) => {
+                    return Err(SessionError::Refused(code, content))
+                }
+                Some(_) => {}
+                None => return Err(SessionError::Closed),
+            }
+        }
+    }
+
+    /// Nick actual, se actualiza cuando el server avisa que cambio.
+    pub fn nickname(&self) -> &str {
+        &self.nickname
+    }
+
+    /// Tiempo maximo que espera next_event, None espera indefinidamente.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.reader.set_read_timeout(timeout)
+    }
+
+    /// Proximo evento. Devuelve None cuando el server cerro la conexion.
+    pub fn next_event(&mut self) -> io::Result<Option<Event>> {
+        let line = match self.reader.read_line()? {
+            Some(l) => l,
+            None => return Ok(None),
+        };
+
+        let event = Event::parse(&line);
+        if let Event::Nick { old, new } = &event {
+            if old.eq_ignore_ascii_case(&self.nickname) {
+                self.nickname = new.to_owned();
+            }
+        }
+        Ok(Some(event))
+    }
+
+    /// Eventos hasta que se cierra la conexion o falla la lectura.
+    pub fn events(&mut self) -> Events<'_> {
+        Events { session: self }
+    }
+
+    /// Llama al callback con cada evento hasta que se cierra la conexion.
+    /// El callback recibe la mitad de escritura de la sesion para poder responder.
+    pub fn run<F>(&mut self, mut callback: F) -> io::Result<()>
+    where
+        F: FnMut(&mut SessionSender, &Event),
+    {
+        while let Some(event) = self.next_event()? {
+            callback(&mut self.sender, &event);
+        }
+        Ok(())
+    }
+}
+
+impl Deref for Session {
+    type Target = SessionSender;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sender
+    }
+}
+
+impl DerefMut for Session {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.sender
+    }
+}
+
+/// Iterador de los eventos de una sesion, devuelto por Session::events.
+pub struct Events<'a> {
+    session: &'a mut Session,
+}
+
+impl Iterator for Events<'_> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.session.next_event().ok().flatten()
+    }
+}
+
+/// Respuestas de error del protocolo y las propias del server (14xx).
+fn is_error(code: usize) -> bool {
+    (400..600).contains(&code) || (1400..1500).contains(&code) || code == ERR_SERVERERR
+}
+
+#[cfg(test)]
+mod session_tests {
+    use super::*;
+    use crate::irc::{constants::ERR_ALREADYREGISTRED, model::server::builder::ServerBuilder};
+
+    fn connect(address: std::net::SocketAddr, nickname: &str) -> Session {
+        let session = Session::connect(address, "pass", nickname, nickname, "Bot").unwrap();
+        session
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        session
+    }
+
+    fn wait_for(session: &mut Session, expected: &Event) -> bool {
+        session.events().any(|event| &event == expected)
+    }
+
+    #[test]
+    fn test_sessions_exchange_channel_events() {
+        let server = ServerBuilder::new().spawn().unwrap();
+        let mut alice = connect(server.address(), "sessalice");
+        let mut bob = connect(server.address(), "sessbob");
+
+        alice.join(&["#session"], &[]).unwrap();
+        alice.topic("#session", Some("bots: welcome")).unwrap();
+        assert!(wait_for(
+            &mut alice,
+            &Event::Topic {
+                channel: "#session".to_owned(),
+                topic: "bots: welcome".to_owned(),
+            }
+        ));
+
+        bob.join(&["#session"], &[]).unwrap();
+        assert!(wait_for(
+            &mut alice,
+            &Event::Join {
+                channel: "#session".to_owned(),
+                nickname: "sessbob".to_owned(),
+            }
+        ));
+
+        bob.privmsg("#session", "hello there").unwrap();
+        bob.nick("sessrobert").unwrap();
+        assert!(wait_for(
+            &mut alice,
+            &Event::Message {
+                channel: Some("#session".to_owned()),
+                sender: "sessbob".to_owned(),
+                text: "hello there".to_owned(),
+            }
+        ));
+
+        let renamed = Event::Nick {
+            old: "sessbob".to_owned(),
+            new: "sessrobert".to_owned(),
+        };
+        assert!(wait_for(&mut bob, &renamed));
+        assert_eq!(bob.nickname(), "sessrobert");
+
+        server.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_registration_with_taken_nickname_is_refused() {
+        let server = ServerBuilder::new().spawn().unwrap();
+        let _first = connect(server.address(), "sesstaken");
+
+        let refused = Session::connect(server.address(), "pass", "sesstaken", "other", "Bot");
+
+        assert!(matches!(
+            refused,
+            Err(SessionError::Refused(ERR_ALREADYREGISTRED, _))
+        ));
+        server.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_parameters_cant_inject_another_command() {
+        let server = ServerBuilder::new().spawn().unwrap();
+        let mut alice = connect(server.address(), "sessinjalice");
+        let mut bob = connect(server.address(), "sessinjbob");
+
+        let rejected = [
+            bob.privmsg("sessinjalice", "x\r\nQUIT :injected"),
+            bob.topic("#inject", Some("x\nQUIT")),
+            bob.nick("sess\0bob"),
+            bob.send_raw("PING\r\nQUIT"),
+        ];
+        for result in rejected {
+            assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        }
+
+        // Bob is still connected and the server got none of the halves
+        bob.privmsg("sessinjalice", "still here").unwrap();
+        let event = alice.events().find(|e| matches!(e, Event::Message { .. }));
+        assert_eq!(
+            event,
+            Some(Event::Message {
+                channel: None,
+                sender: "sessinjbob".to_owned(),
+                text: "still here".to_owned(),
+            })
+        );
+        server.shutdown().unwrap();
+    }
+}
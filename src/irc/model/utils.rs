@@ -4,9 +4,12 @@ use std::{
     fmt::Display,
     str::FromStr,
     sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use crate::irc::message::utils::validate_name_invalid_none;
+use crate::irc::message::utils::{validate_ban_mask, validate_name_invalid_none};
+
+use super::irc_key::IrcKey;
 
 pub fn deserialize_bool(b: &str) -> Result<bool, String> {
     match b {
@@ -47,12 +50,12 @@ pub fn mt<T>(c: T) -> Arc<Mutex<T>> {
     Arc::new(Mutex::new(c))
 }
 
-pub fn deseriaze_usernames(l: &str) -> Result<HashMap<String, bool>, String> {
+pub fn deseriaze_usernames(l: &str) -> Result<HashMap<IrcKey, bool>, String> {
     let nicks = deserialize_list(l, &|e| {
         if validate_name_invalid_none(Some(e.as_bytes())).is_err() {
             return Err(deserialize_err("Invalid nicks"));
         }
-        Ok(e.to_owned())
+        Ok(IrcKey::from(e))
     })?;
     let mut r = HashMap::new();
     for nick in nicks {
@@ -61,6 +64,19 @@ pub fn deseriaze_usernames(l: &str) -> Result<HashMap<String, bool>, String> {
     Ok(r)
 }
 
+pub fn deserialize_masks(l: &str) -> Result<Vec<String>, String> {
+    if l.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    deserialize_list(l, &|e| {
+        if validate_ban_mask(Some(e.as_bytes())).is_err() {
+            return Err(deserialize_err("Invalid mask"));
+        }
+        Ok(e.to_owned())
+    })
+}
+
 pub fn serialize_option<T>(o: &Option<T>) -> String
 where
     T: Display,
@@ -89,3 +105,37 @@ where
         .collect::<Vec<String>>()
         .join(";")
 }
+
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Formatea segundos desde epoch como fecha y hora UTC (`2022-11-24 18:30:00 UTC`).
+pub fn format_unix_time(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+
+    // Conversion de dias a fecha civil (algoritmo de Howard Hinnant)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
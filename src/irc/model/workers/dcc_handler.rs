@@ -1,5 +1,7 @@
+#[cfg(feature = "gui")]
 use std::{collections::HashMap, sync::mpsc::Sender, thread::JoinHandle};
 
+#[cfg(feature = "gui")]
 use crate::{
     gui::{DccCommands, GuiMessage, IncomingMessage},
     irc::ctcp::{
@@ -16,6 +18,7 @@ pub enum ExecutedAction {
     Destroyed,
 }
 
+#[cfg(feature = "gui")]
 #[derive(PartialEq, Eq)]
 pub enum ConnectionTypeWrapper {
     Invalid,
@@ -23,6 +26,7 @@ pub enum ConnectionTypeWrapper {
     Outgoing(ExecutedAction),
 }
 
+#[cfg(feature = "gui")]
 pub struct DccMessageHandler {
     dcc_connections: HashMap<usize, DCCHandler>,
     finished_conns: Vec<JoinHandle<()>>,
@@ -36,6 +40,7 @@ pub struct DccMessageHandler {
 /// CLOSE       |NoAction           |Destroyed          |NoAction           |Destroyed          |
 /// *: Depending on if it the file exists
 /// NoAction does not neccesarily means no internal operation is done, though no operation is the norm it should not be expected.
+#[cfg(feature = "gui")]
 impl DccMessageHandler {
     pub fn init() -> Self {
         Self {
@@ -1,24 +1,22 @@
-use std::{
-    collections::HashMap,
-    thread::{JoinHandle, ThreadId},
-};
-
-pub mod client_management;
 pub mod dcc_handler;
 pub mod persistency;
 pub mod server_command;
 
-pub type ThreadMap = HashMap<ThreadId, JoinHandle<()>>;
-pub enum ThreadManagement {
-    KeepTrack(JoinHandle<()>),
-    Clean(ThreadId),
-    KillAll,
-}
+use std::{net::TcpStream, sync::mpsc::Sender};
+
+use super::server_connection::ServerConnection;
 
 pub enum ServerCommand {
     Shutdown,
-    Persisting,
-    NormalOperation,
+    /// Apagado pedido por un operador con DIE.
+    Die,
+    /// Apagado pedido por un operador con RESTART, el proceso vuelve a ejecutarse.
+    Restart,
+    /// Link con otro server de la red (direccion y contraseña) mientras el server corre.
+    /// El resultado se avisa por el canal.
+    Connect(String, String, Sender<Result<(), String>>),
+    /// Link ya registrado por el hilo que se conecto, para que lo atienda el reactor.
+    Linked(ServerConnection, TcpStream, Sender<Result<(), String>>),
 }
 
 impl ServerCommand {
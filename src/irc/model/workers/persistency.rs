@@ -3,31 +3,44 @@ use std::{
     collections::HashMap,
     fs::{self, File},
     io::{BufRead, BufReader, LineWriter, Write},
-    sync::{mpsc::Sender, Arc, Mutex},
+    path::Path,
 };
 
 use crate::irc::model::{
     channel::Channel,
     client_account::ClientAccount,
+    irc_key::IrcKey,
     server::Server,
+    server_ban::{ServerBan, SERVER_BAN_FIELDS},
     utils::{deserialize_err, mt},
+    MTChannel, MTClientAccount,
 };
 
-use super::ServerCommand;
+use crate::try_lock;
 
+/// Carga el estado guardado en el directorio del server, si tiene uno.
 pub fn load(server: &mut Server) -> Result<(), String> {
+    let dir = match server.storage.clone() {
+        Some(d) => d,
+        None => return Ok(()),
+    };
     let sv_port = match server.host.split(':').last() {
         Some(sp) => sp.to_owned(),
         None => {
             return Err(deserialize_err("Can't read files files"));
         }
     };
-    load_users(server, &sv_port)?;
-    load_channels(server, &sv_port)?;
+    load_server_bans(server, &dir, &sv_port)?;
+    load_users(server, &dir, &sv_port)?;
+    load_channels(server, &dir, &sv_port)?;
     Ok(())
 }
 
-pub fn persist(server: Arc<Server>) {
+pub fn persist(server: &Server) {
+    let dir = match &server.storage {
+        Some(d) => d,
+        None => return,
+    };
     println!("[SERVER - PERSISTENCY] Starting");
     let sv_port = match server.host.split(':').last() {
         Some(sp) => sp,
@@ -40,35 +53,36 @@ pub fn persist(server: Arc<Server>) {
 
     let channels = to_csv(server.persist_channels());
 
-    if let Err(e) = fs::create_dir_all("./persist") {
+    let server_bans = to_csv(server.persist_server_bans());
+
+    if let Err(e) = fs::create_dir_all(dir) {
         println!("Can't create path\n{}", e);
         return;
     };
 
-    if let Err(e) = persist_csv(&format!("user_accounts-{}", sv_port), reg_users) {
+    if let Err(e) = persist_csv(dir, &format!("user_accounts-{}", sv_port), reg_users) {
         println!("{}", e);
         return;
     }
 
-    if let Err(e) = persist_csv(&format!("channels-{}", sv_port), channels) {
+    if let Err(e) = persist_csv(dir, &format!("channels-{}", sv_port), channels) {
         println!("{}", e);
         return;
     };
-    println!("[SERVER - PERSISTENCY] Finished");
-}
 
-pub fn persist_notice(server: Arc<Server>, tx: Sender<ServerCommand>) {
-    let _ = tx.send(ServerCommand::Persisting);
-    persist(server);
-    let _ = tx.send(ServerCommand::NormalOperation);
+    if let Err(e) = persist_csv(dir, &format!("server_bans-{}", sv_port), server_bans) {
+        println!("{}", e);
+        return;
+    };
+    println!("[SERVER - PERSISTENCY] Finished");
 }
 
 fn to_csv(l: Vec<Vec<String>>) -> Vec<String> {
     l.into_iter().map(|e| e.join(",")).collect()
 }
 
-fn persist_csv(filename: &str, entries: Vec<String>) -> Result<(), String> {
-    let f = match File::create(format!("./persist/{}", filename)) {
+fn persist_csv(dir: &Path, filename: &str, entries: Vec<String>) -> Result<(), String> {
+    let f = match File::create(dir.join(filename)) {
         Ok(f) => f,
         Err(e) => {
             println!("{}", e);
@@ -91,15 +105,15 @@ fn persist_csv(filename: &str, entries: Vec<String>) -> Result<(), String> {
     Ok(())
 }
 
-fn load_users(server: &mut Server, postfix: &str) -> Result<(), String> {
-    let user_files = File::open(format!("./persist/user_accounts-{}", postfix));
+fn load_users(server: &mut Server, dir: &Path, postfix: &str) -> Result<(), String> {
+    let user_files = File::open(dir.join(format!("user_accounts-{}", postfix)));
     if user_files.is_err() {
         return Err(deserialize_err("Can't open user accounts file"));
     }
 
     let reader = BufReader::new(user_files.unwrap());
 
-    let mut accounts = HashMap::new();
+    let mut accounts: HashMap<IrcKey, MTClientAccount> = HashMap::new();
 
     for l in reader.lines() {
         match l {
@@ -108,30 +122,36 @@ fn load_users(server: &mut Server, postfix: &str) -> Result<(), String> {
 
                 let acc = ClientAccount::deserialize(&split)?;
 
-                if accounts.contains_key(split[0]) {
-                    return Err(deserialize_err("Duplicated nickname. Corrupted file"));
+                let nickname = IrcKey::from(&acc.nickname);
+                if let Some(other) = accounts.get(&nickname) {
+                    if try_lock!(other).nickname == acc.nickname {
+                        return Err(deserialize_err("Duplicated nickname. Corrupted file"));
+                    }
+                    // Saved before nicknames were case insensitive, the first one keeps the nick
+                    println!("[SERVER - PERSISTENCY] Skipping account {}", acc.nickname);
+                    continue;
                 }
 
-                accounts.insert(acc.nickname.to_owned(), mt(acc));
+                accounts.insert(nickname, mt(acc));
             }
             Err(_) => return Err(deserialize_err("Can't read from user accounts file")),
         }
     }
 
-    server.accounts = Mutex::new(accounts);
+    server.restore_accounts(accounts);
 
     Ok(())
 }
 
-fn load_channels(server: &mut Server, postfix: &str) -> Result<(), String> {
-    let user_files = File::open(format!("./persist/channels-{}", postfix));
+fn load_channels(server: &mut Server, dir: &Path, postfix: &str) -> Result<(), String> {
+    let user_files = File::open(dir.join(format!("channels-{}", postfix)));
     if user_files.is_err() {
         return Err(deserialize_err("Can't open user accounts file"));
     }
 
     let reader = BufReader::new(user_files.unwrap());
 
-    let mut channels = HashMap::new();
+    let mut channels: HashMap<IrcKey, MTChannel> = HashMap::new();
 
     for l in reader.lines() {
         match l {
@@ -140,17 +160,61 @@ fn load_channels(server: &mut Server, postfix: &str) -> Result<(), String> {
 
                 let channel = Channel::deserialize(&split)?;
 
-                if channels.contains_key(split[0]) {
-                    return Err(deserialize_err("Duplicated channel"));
+                let name = IrcKey::from(split[0]);
+                if let Some(other) = channels.get(&name) {
+                    if try_lock!(other).name == split[0] {
+                        return Err(deserialize_err("Duplicated channel"));
+                    }
+                    // Saved before channel names were case insensitive, the first one keeps the name
+                    println!("[SERVER - PERSISTENCY] Skipping channel {}", split[0]);
+                    continue;
                 }
 
-                channels.insert(split[0].to_owned(), mt(channel));
+                channels.insert(name, mt(channel));
             }
             Err(_) => return Err(deserialize_err("Can't read from user accounts file")),
         }
     }
 
-    server.channels = Mutex::new(channels);
+    server.restore_channels(channels);
+
+    Ok(())
+}
+
+fn load_server_bans(server: &mut Server, dir: &Path, postfix: &str) -> Result<(), String> {
+    // Los servers que todavia no banearon a nadie no tienen archivo
+    let ban_files = match File::open(dir.join(format!("server_bans-{}", postfix))) {
+        Ok(f) => f,
+        Err(_) => return Ok(()),
+    };
+
+    let reader = BufReader::new(ban_files);
+
+    let mut bans = HashMap::new();
+
+    for l in reader.lines() {
+        match l {
+            Ok(line) => {
+                // The reason is the last field and may contain commas
+                let split = line.splitn(SERVER_BAN_FIELDS, ',').collect::<Vec<&str>>();
+
+                // A ban saved with a mask that is no longer accepted is the only one dropped
+                let ban = match ServerBan::deserialize(&split) {
+                    Ok(ban) => ban,
+                    Err(e) => {
+                        println!("[SERVER - PERSISTENCY] Skipping server ban {}: {}", line, e);
+                        continue;
+                    }
+                };
+                if !ban.is_expired() {
+                    bans.insert(ban.mask.to_owned(), ban);
+                }
+            }
+            Err(_) => return Err(deserialize_err("Can't read from server bans file")),
+        }
+    }
+
+    server.restore_server_bans(bans);
 
     Ok(())
 }
@@ -1,12 +1,15 @@
 //! Modulo que se centra en las funcionalidades referentes a la modificacion de canales por parte del server.
 use std::{
     io::{stdin, BufRead, BufReader},
-    sync::mpsc::Sender,
+    sync::{mpsc::Sender, Arc},
 };
 
+use mio::Waker;
+
 use super::ServerCommand;
 
-pub fn listen_commands(tx: Sender<ServerCommand>) {
+/// Lee los comandos de la consola y despierta al reactor para que los atienda.
+pub fn listen_commands(tx: Sender<ServerCommand>, waker: Arc<Waker>) {
     let reader = BufReader::new(stdin());
     for line in reader.lines().flatten() {
         match ServerCommand::parse(&line) {
@@ -16,6 +19,7 @@ pub fn listen_commands(tx: Sender<ServerCommand>) {
                         println!("[SERVER] Error while sending the command");
                         continue;
                     }
+                    let _ = waker.wake();
                     break;
                 }
             }
@@ -0,0 +1,174 @@
+//! Modulo que se centra en las funcionalidades referentes a la escritura bufferizada de una conexion.
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    io::{self, ErrorKind, Write},
+    net::{Shutdown, TcpStream},
+};
+
+pub const SENDQ_EXCEEDED: &str = "Max SendQ exceeded";
+
+/// Escritor de una conexion. Lo que el socket no acepta en el momento (si no es bloqueante)
+/// queda en la cola de envio hasta que el reactor avisa que se puede volver a escribir.
+pub struct ConnectionWriter {
+    stream: TcpStream,
+    pending: VecDeque<u8>,
+    limit: usize,
+    exceeded: bool,
+}
+
+impl ConnectionWriter {
+    pub fn new(stream: TcpStream) -> Self {
+        Self {
+            stream,
+            pending: VecDeque::new(),
+            limit: 0,
+            exceeded: false,
+        }
+    }
+
+    /// Bytes que puede acumular la cola de envio, 0 no tiene limite.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+    }
+
+    /// Encola la linea terminada en CRLF y escribe todo lo que se pueda.
+    /// Devuelve la cantidad de bytes encolados. Si la cola supera el limite se corta la lectura
+    /// de la conexion, para que el reactor la cierre, y se descarta todo lo que se escriba despues.
+    pub fn write_line(&mut self, line: &str) -> io::Result<usize> {
+        if self.exceeded {
+            return Ok(0);
+        }
+
+        let bytes = format!("{}\r\n", line).into_bytes();
+        let queued = bytes.len();
+        self.pending.extend(bytes);
+        self.flush()?;
+
+        if self.limit > 0 && self.pending.len() > self.limit {
+            self.exceeded = true;
+            self.pending.clear();
+            let _ = self.stream.shutdown(Shutdown::Read);
+            return Err(io::Error::other(SENDQ_EXCEEDED));
+        }
+
+        Ok(queued)
+    }
+
+    /// Escribe lo pendiente hasta que no quede nada o el socket no acepte mas.
+    pub fn flush(&mut self) -> io::Result<()> {
+        while !self.pending.is_empty() {
+            let (front, _) = self.pending.as_slices();
+            match self.stream.write(front) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(written) => {
+                    self.pending.drain(..written);
+                }
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => return Ok(()),
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Bytes en la cola de envio.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn exceeded(&self) -> bool {
+        self.exceeded
+    }
+
+    pub fn try_clone(&self) -> io::Result<TcpStream> {
+        self.stream.try_clone()
+    }
+
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.stream.shutdown(how)
+    }
+}
+
+impl Debug for ConnectionWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionWriter")
+            .field("stream", &self.stream)
+            .field("pending", &self.pending.len())
+            .field("limit", &self.limit)
+            .field("exceeded", &self.exceeded)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod writer_tests {
+    use std::{
+        io::Read,
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::*;
+
+    /// Escritor no bloqueante y el otro extremo, que nunca lee si el test no lo hace.
+    fn writer(limit: usize) -> (ConnectionWriter, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let remote = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        stream.set_nonblocking(true).unwrap();
+        let mut writer = ConnectionWriter::new(stream);
+        writer.set_limit(limit);
+        (writer, remote)
+    }
+
+    #[test]
+    fn test_lines_are_written_with_crlf() {
+        let (mut writer, mut remote) = writer(1024);
+
+        assert_eq!(writer.write_line("PING a").unwrap(), 8);
+        assert_eq!(writer.pending(), 0);
+
+        let mut received = [0; 8];
+        remote.read_exact(&mut received).unwrap();
+        assert_eq!(&received, b"PING a\r\n");
+    }
+
+    #[test]
+    fn test_peer_that_never_reads_exceeds_the_sendq() {
+        let limit = 64 * 1024;
+        let (mut writer, _remote) = writer(limit);
+        let line = "A".repeat(500);
+
+        let mut result = Ok(0);
+        for _ in 0..100_000 {
+            result = writer.write_line(&line);
+            if result.is_err() {
+                break;
+            }
+            assert!(writer.pending() <= limit);
+        }
+
+        assert_eq!(result.unwrap_err().to_string(), SENDQ_EXCEEDED);
+        assert!(writer.exceeded());
+        assert_eq!(writer.pending(), 0);
+        // Anything written afterwards is dropped
+        assert_eq!(writer.write_line(&line).unwrap(), 0);
+        assert_eq!(writer.pending(), 0);
+    }
+
+    #[test]
+    fn test_exceeding_the_sendq_cuts_the_reading_side() {
+        let (mut writer, _remote) = writer(1);
+        let mut stream = writer.try_clone().unwrap();
+
+        // Nothing is left pending while the socket takes it, the limit only counts the backlog
+        let mut line = "A".repeat(1024);
+        while writer.write_line(&line).is_ok() {
+            line.push('A');
+        }
+
+        // The reactor sees the end of the connection and closes it, even with nothing received
+        let mut buf = [0; 1];
+        assert_eq!(stream.read(&mut buf).unwrap(), 0);
+    }
+}
@@ -12,6 +12,12 @@ pub struct ResponseBuilder {
     internal_response: Vec<InternalType>,
 }
 
+impl Default for ResponseBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ResponseBuilder {
     pub fn new() -> Self {
         Self {
@@ -27,6 +27,7 @@ pub fn parsing_irc_defined_error_message(error_number: usize) -> String {
         ERR_NONICKNAMEGIVEN => "No nickname given",
         ERR_NOTEXTTOSEND => "No text to send",
         ERR_NORECIPIENT => "No recipient given",
+        ERR_NOPRIVILEGES => "Permission Denied- You're not an IRC operator",
         _ => "Badly formatted message",
     }
     .to_owned()
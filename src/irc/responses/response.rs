@@ -1,5 +1,6 @@
 //! Modulo que se centra en las funcionalidades referentes a la representacion de canales.
 
+#[cfg(feature = "gui")]
 use crate::irc::constants::{
     ERR_SERVERERR, RPL_ENDOFNAMES, RPL_LIST, RPL_LISTEND, RPL_LISTSTART, RPL_NAMREPLY,
 };
@@ -20,6 +21,7 @@ impl Response {
         format!("{}: {}", self.numeric, self.content)
     }
 
+    #[cfg(feature = "gui")]
     pub fn deserialize(response: &str) -> Self {
         let r: Vec<&str> = response.split(':').collect();
 
@@ -36,6 +38,7 @@ impl Response {
         }
     }
 
+    #[cfg(feature = "gui")]
     pub fn is_printable(&self) -> bool {
         let mut non_printable_nums: Vec<usize> = Vec::new();
 
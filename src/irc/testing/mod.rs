@@ -0,0 +1,283 @@
+//! Modulo que se centra en las funcionalidades referentes a probar servers y clientes de punta a punta.
+use std::{
+    io,
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::irc::{
+    constants::RPL_SUCLOGIN,
+    model::{
+        client_connection::ClientConnection,
+        server::builder::{ServerBuilder, ServerHandle},
+    },
+};
+
+mod scenarios;
+
+/// Lo que espera cada expectativa antes de fallar.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+const LINK_PASSWORD: &str = "testlink";
+/// Lineas recientes que se muestran cuando falla una expectativa.
+const HISTORY_LEN: usize = 20;
+
+/// Red de servers corriendo en este proceso, cada uno en un puerto libre de 127.0.0.1.
+/// Los servers se identifican por el orden en que se iniciaron y se apagan al soltar la red.
+#[derive(Debug, Default)]
+pub struct TestNetwork {
+    servers: Vec<Option<ServerHandle>>,
+}
+
+impl TestNetwork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inicia un server sin links y devuelve su indice.
+    pub fn start_server(&mut self) -> usize {
+        self.start(ServerBuilder::new())
+    }
+
+    /// Inicia un server conectado al indicado.
+    pub fn start_linked(&mut self, uplink: usize) -> usize {
+        let address = self.address(uplink).to_string();
+        self.start(ServerBuilder::new().uplink(&address, LINK_PASSWORD))
+    }
+
+    /// Inicia un server armado con el builder, por ejemplo con otros limites.
+    pub fn start(&mut self, builder: ServerBuilder) -> usize {
+        let handle = builder.spawn().expect("Couldn't start the server");
+        self.servers.push(Some(handle));
+        self.servers.len() - 1
+    }
+
+    /// Conecta dos servers que ya estan corriendo, como en un netjoin.
+    pub fn link(&self, server: usize, uplink: usize) {
+        let address = self.address(uplink).to_string();
+        if let Err(e) = self.connect(server, &address) {
+            panic!("Couldn't link server {} to {}: {}", server, uplink, e);
+        }
+    }
+
+    /// Pide al server que se conecte a la direccion, que no tiene por que ser de un server de la red.
+    pub fn connect(&self, server: usize, address: &str) -> Result<(), String> {
+        self.handle(server).connect(address, LINK_PASSWORD)
+    }
+
+    /// Apaga el server; en los demas sus clientes salen con QUIT. Para un netsplit, SQUIT de un operador.
+    pub fn stop_server(&mut self, server: usize) {
+        let handle = self.servers[server]
+            .take()
+            .expect("The server is not running");
+        handle.shutdown().expect("The server panicked");
+    }
+
+    pub fn address(&self, server: usize) -> SocketAddr {
+        self.handle(server).address()
+    }
+
+    pub fn name(&self, server: usize) -> &str {
+        self.handle(server).name()
+    }
+
+    /// Cliente registrado en el server con el nick indicado.
+    pub fn client(&self, server: usize, nickname: &str) -> TestClient {
+        TestClient::connect(self.address(server), nickname)
+    }
+
+    fn handle(&self, server: usize) -> &ServerHandle {
+        match self.servers.get(server) {
+            Some(Some(handle)) => handle,
+            _ => panic!("Server {} is not running", server),
+        }
+    }
+}
+
+impl Drop for TestNetwork {
+    fn drop(&mut self) {
+        for handle in self.servers.drain(..).flatten() {
+            let _ = handle.shutdown();
+        }
+    }
+}
+
+/// Intermediario por el que pasa un link entre dos servers, para cortarlo de golpe como si se
+/// cayera la red: ninguno de los dos servers llega a mandar SQUIT.
+#[derive(Debug)]
+pub struct LinkProxy {
+    address: SocketAddr,
+    sockets: Arc<Mutex<Vec<TcpStream>>>,
+}
+
+impl LinkProxy {
+    /// Acepta una unica conexion y la reenvia al server en target.
+    pub fn start(target: SocketAddr) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Couldn't bind the proxy");
+        let address = listener.local_addr().expect("Proxy without address");
+        let sockets = Arc::new(Mutex::new(vec![]));
+
+        let shared = sockets.clone();
+        thread::spawn(move || {
+            let (inbound, _) = listener.accept().expect("Couldn't accept the link");
+            let outbound = TcpStream::connect(target).expect("Couldn't reach the server");
+            for (mut from, mut to) in [
+                (inbound.try_clone().unwrap(), outbound.try_clone().unwrap()),
+                (outbound.try_clone().unwrap(), inbound.try_clone().unwrap()),
+            ] {
+                thread::spawn(move || io::copy(&mut from, &mut to));
+            }
+            shared.lock().unwrap().extend([inbound, outbound]);
+        });
+
+        Self { address, sockets }
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Cierra las dos puntas del link.
+    pub fn cut(&self) {
+        for socket in self.sockets.lock().unwrap().iter() {
+            let _ = socket.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+/// Cliente con lineas escritas a mano y expectativas sobre lo que recibe, con un tiempo maximo.
+/// Las expectativas leen hasta encontrar la linea esperada y descartan las anteriores.
+#[derive(Debug)]
+pub struct TestClient {
+    nickname: String,
+    connection: ClientConnection,
+    timeout: Duration,
+    history: Vec<String>,
+}
+
+impl TestClient {
+    /// Se conecta y se registra con el nick (tambien como usuario), esperando el login.
+    pub fn connect(address: SocketAddr, nickname: &str) -> Self {
+        let connection = ClientConnection::connect(address).expect("Couldn't connect");
+        let mut client = Self::from_connection(connection, nickname);
+        client
+            .connection
+            .register("pass", nickname, nickname, "Test Client")
+            .expect("Couldn't register");
+        client.expect_numeric(RPL_SUCLOGIN);
+        client
+    }
+
+    /// Cliente sobre una conexion sin registrar, para probar el registro.
+    pub fn unregistered(address: SocketAddr, nickname: &str) -> Self {
+        let connection = ClientConnection::connect(address).expect("Couldn't connect");
+        Self::from_connection(connection, nickname)
+    }
+
+    fn from_connection(connection: ClientConnection, nickname: &str) -> Self {
+        Self {
+            nickname: nickname.to_owned(),
+            connection,
+            timeout: DEFAULT_TIMEOUT,
+            history: vec![],
+        }
+    }
+
+    pub fn nickname(&self) -> &str {
+        &self.nickname
+    }
+
+    pub fn send(&mut self, line: &str) -> &mut Self {
+        self.connection.send(line).expect("Couldn't send");
+        self
+    }
+
+    /// Espera una linea exactamente igual a la indicada.
+    pub fn expect(&mut self, line: &str) -> &mut Self {
+        self.expect_matching(&format!("{:?}", line), |l| l == line);
+        self
+    }
+
+    /// Espera una linea que contenga el texto y la devuelve.
+    pub fn expect_containing(&mut self, text: &str) -> String {
+        self.expect_matching(&format!("a line containing {:?}", text), |l| {
+            l.contains(text)
+        })
+    }
+
+    /// Espera la respuesta numerica y devuelve su contenido.
+    pub fn expect_numeric(&mut self, code: usize) -> String {
+        let prefix = format!("{}:", code);
+        let line = self.expect_matching(&format!("numeric {}", code), |l| l.starts_with(&prefix));
+        line[prefix.len()..].trim_start().to_owned()
+    }
+
+    /// Espera una linea que cumpla la condicion y la devuelve.
+    pub fn expect_matching<P>(&mut self, description: &str, predicate: P) -> String
+    where
+        P: Fn(&str) -> bool,
+    {
+        let deadline = Instant::now() + self.timeout;
+        while let Some(line) = self.read_until(deadline) {
+            if predicate(&line) {
+                return line;
+            }
+        }
+        panic!(
+            "{} didn't receive {} in {:?}, last lines:\n{}",
+            self.nickname,
+            description,
+            self.timeout,
+            self.history.join("\n")
+        );
+    }
+
+    /// Falla si en el tiempo indicado llega una linea igual a la indicada.
+    pub fn expect_no(&mut self, line: &str, wait: Duration) -> &mut Self {
+        let deadline = Instant::now() + wait;
+        while let Some(received) = self.read_until(deadline) {
+            assert_ne!(
+                received, line,
+                "{} received an unexpected line",
+                self.nickname
+            );
+        }
+        self
+    }
+
+    /// Espera que el server cierre la conexion.
+    pub fn expect_closed(&mut self) {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                panic!("{}'s connection is still open", self.nickname);
+            }
+            let _ = self.connection.set_read_timeout(Some(left));
+            match self.connection.read_line() {
+                Ok(Some(line)) => self.remember(line),
+                _ => return,
+            }
+        }
+    }
+
+    /// Proxima linea antes del limite, o None si no llego ninguna o se cerro la conexion.
+    fn read_until(&mut self, deadline: Instant) -> Option<String> {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return None;
+        }
+        let _ = self.connection.set_read_timeout(Some(left));
+        let line = self.connection.read_line().ok().flatten()?;
+        self.remember(line.to_owned());
+        Some(line)
+    }
+
+    fn remember(&mut self, line: String) {
+        if self.history.len() == HISTORY_LEN {
+            self.history.remove(0);
+        }
+        self.history.push(line);
+    }
+}
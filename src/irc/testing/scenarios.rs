@@ -0,0 +1,317 @@
+use std::{net::TcpListener, thread};
+
+use crate::irc::constants::{
+    ERR_ALREADYREGISTRED, INFO_PASSWORD, RPL_CHANNELMODEIS, RPL_ENDOFNAMES, RPL_ISON, RPL_LIST,
+    RPL_LISTEND, RPL_NAMREPLY, RPL_NICKIN, RPL_NICKOUT, RPL_TOPIC, RPL_YOUREOPER,
+};
+
+use crate::irc::model::{server::ConnectionLimits, writer::SENDQ_EXCEEDED};
+
+use super::*;
+
+const QUIET: Duration = Duration::from_millis(300);
+
+#[test]
+fn test_registration_and_taken_nicknames() {
+    let mut net = TestNetwork::new();
+    let server = net.start_server();
+
+    let mut first = TestClient::unregistered(net.address(server), "first");
+    first
+        .send("PASS secret")
+        .send("NICK first")
+        .send("USER first first first :First Client");
+    first
+        .expect("1201: New password was set")
+        .expect("1202: first :You have a new nick");
+    first.expect_numeric(RPL_SUCLOGIN);
+
+    let mut second = TestClient::connect(net.address(server), "second");
+    first.expect("2000: second");
+    second.send("ISON first nobody");
+    assert_eq!(second.expect_numeric(RPL_ISON), ":first");
+
+    // Same nick, other account
+    let mut impostor = TestClient::unregistered(net.address(server), "first");
+    impostor
+        .send("PASS secret")
+        .send("NICK first")
+        .send("USER other other other :Impostor");
+    impostor.expect_numeric(ERR_ALREADYREGISTRED);
+}
+
+#[test]
+fn test_channel_messages_topic_and_kick() {
+    let mut net = TestNetwork::new();
+    let server = net.start_server();
+    let mut owner = net.client(server, "owner");
+    let mut guest = net.client(server, "guest");
+
+    owner.send("JOIN #room");
+    owner.expect("331: #room :No topic is set");
+    guest.send("JOIN #room");
+    guest.expect("353: #room owner");
+    owner.expect("353: #room guest");
+
+    guest.send("PRIVMSG #room :hi all");
+    owner.expect("#room guest: hi all");
+
+    owner.send("TOPIC #room :testing");
+    assert_eq!(owner.expect_numeric(RPL_TOPIC), "#room :testing");
+
+    owner.send("KICK #room guest :bye");
+    guest
+        .expect("#room :Kicked from channel")
+        .expect("#room owner :bye");
+    owner.expect("1354: #room guest");
+
+    // The server still answers after the kick
+    owner.send("PRIVMSG #room :anyone?");
+    guest.expect_no("#room owner: anyone?", QUIET);
+    owner.send("MODE #room");
+    owner.expect_numeric(RPL_CHANNELMODEIS);
+}
+
+/// Lee las respuestas numericas con el codigo dado hasta la que cierra la lista.
+fn replies_until(client: &mut TestClient, code: usize, end: usize) -> Vec<String> {
+    let (reply, last) = (format!("{}:", code), format!("{}:", end));
+    let mut replies = vec![];
+    loop {
+        let line = client.expect_matching(&format!("numeric {} or {}", code, end), |l| {
+            l.starts_with(&reply) || l.starts_with(&last)
+        });
+        if line.starts_with(&last) {
+            return replies;
+        }
+        replies.push(line[reply.len()..].trim_start().to_owned());
+    }
+}
+
+#[test]
+fn test_leaving_channels_by_part_and_quit() {
+    let mut net = TestNetwork::new();
+    let server = net.start_server();
+    let mut alice = net.client(server, "alice");
+    let mut bob = net.client(server, "bob");
+
+    alice.send("JOIN #first").send("JOIN #second");
+    bob.send("JOIN #first").send("JOIN #second");
+    alice.expect("353: #second bob");
+
+    bob.send("QUIT :bye");
+    bob.expect_closed();
+    assert_eq!(alice.expect_numeric(RPL_NICKOUT), "bob");
+
+    // The last one out removes the channel
+    alice.send("PART #first");
+    alice.send("LIST");
+    let listed = replies_until(&mut alice, RPL_LIST, RPL_LISTEND);
+    assert_eq!(listed.len(), 1);
+    assert!(listed[0].starts_with("#second"));
+
+    alice.send("NAMES #second");
+    assert_eq!(
+        replies_until(&mut alice, RPL_NAMREPLY, RPL_ENDOFNAMES),
+        vec!["#second alice"]
+    );
+}
+
+#[test]
+fn test_channel_modes() {
+    let mut net = TestNetwork::new();
+    let server = net.start_server();
+    let mut owner = net.client(server, "owner");
+
+    owner.send("JOIN #modes");
+    assert_eq!(
+        owner.expect_numeric(RPL_CHANNELMODEIS),
+        "#modes : +tn -psimlk"
+    );
+
+    owner.send("MODE #modes +m").send("MODE #modes");
+    assert_eq!(
+        owner.expect_numeric(RPL_CHANNELMODEIS),
+        "#modes : +tnm -psilk"
+    );
+}
+
+#[test]
+fn test_delayed_lines_are_all_served_in_order() {
+    let mut net = TestNetwork::new();
+    // 200 lines a second and no room for a burst: more lines queue up than the reactor holds
+    let limits = ConnectionLimits {
+        flood_rate: 200,
+        flood_burst: 1,
+        flood_delay: 60,
+        ..ConnectionLimits::default()
+    };
+    let server = net.start(ServerBuilder::new().limits(limits));
+    let mut sender = net.client(server, "sender");
+    let mut receiver = net.client(server, "receiver");
+
+    for i in 0..500 {
+        sender.send(&format!("PRIVMSG receiver :line {}", i));
+    }
+    for i in 0..500 {
+        receiver.expect(&format!("sender: line {}", i));
+    }
+}
+
+#[test]
+fn test_client_that_never_reads_is_dropped_at_the_sendq_limit() {
+    let mut net = TestNetwork::new();
+    let limits = ConnectionLimits {
+        sendq_client: 16 * 1024,
+        flood_rate: 0,
+        ..ConnectionLimits::default()
+    };
+    let server = net.start(ServerBuilder::new().limits(limits));
+    let mut sender = net.client(server, "sender");
+    let mut slow = net.client(server, "slow");
+    slow.send("JOIN #sendq");
+    slow.expect_numeric(RPL_CHANNELMODEIS);
+    sender.send("JOIN #sendq");
+    sender.expect_numeric(RPL_CHANNELMODEIS);
+
+    // Far more than the socket buffers can hold, the rest piles up in the SendQ
+    let text = "A".repeat(400);
+    for _ in 0..50_000 {
+        sender.send(&format!("PRIVMSG slow :{}", text));
+    }
+
+    sender.expect_containing(SENDQ_EXCEEDED);
+    assert_eq!(sender.expect_numeric(RPL_NICKOUT), "slow");
+    slow.expect_closed();
+}
+
+#[test]
+fn test_netjoin_reaches_both_sides() {
+    let mut net = TestNetwork::new();
+    let a = net.start_server();
+    let b = net.start_server();
+    let mut alice = net.client(a, "alice");
+    let mut carol = net.client(a, "carol");
+    let mut bob = net.client(b, "bob");
+    // Back without channels, only the presence line announces it
+    let _dave = net.client(b, "dave");
+    alice.send("JOIN #net");
+    alice.expect_numeric(RPL_CHANNELMODEIS);
+    carol.send("CAP REQ :batch");
+    carol.expect("CAP carol ACK :batch");
+    bob.send("JOIN #net");
+    bob.expect_numeric(RPL_CHANNELMODEIS);
+    alice.expect_no("2000: bob", QUIET);
+
+    net.link(b, a);
+
+    alice.expect("2000: bob").expect("353: #net bob");
+    bob.expect("2000: alice");
+    let start = carol.expect_containing(" netjoin ");
+    let reference = start
+        .strip_prefix("BATCH +")
+        .and_then(|s| s.split_whitespace().next())
+        .expect("netjoin batch without reference")
+        .to_owned();
+    carol
+        .expect(&format!("@batch={} {}: bob", reference, RPL_NICKIN))
+        .expect(&format!("@batch={} :bob JOIN #net", reference))
+        .expect(&format!("@batch={} {}: dave", reference, RPL_NICKIN))
+        .expect(&format!("BATCH -{}", reference));
+    carol.expect_no(&format!("{}: dave", RPL_NICKIN), QUIET);
+}
+
+#[test]
+fn test_messages_cross_servers_until_the_netsplit() {
+    let mut net = TestNetwork::new();
+    let a = net.start_server();
+    let b = net.start_linked(a);
+    let mut alice = net.client(a, "alice");
+    let mut bob = net.client(b, "bob");
+    assert_eq!(alice.expect_numeric(RPL_NICKIN), "bob");
+
+    alice.send("JOIN #net");
+    alice.expect_numeric(RPL_CHANNELMODEIS);
+    bob.send("JOIN #net");
+    assert_eq!(alice.expect_numeric(RPL_NAMREPLY), "#net bob");
+
+    bob.send("PRIVMSG #net :from b");
+    alice.expect("#net bob: from b");
+    alice.send("PRIVMSG bob :direct");
+    bob.expect("alice: direct");
+
+    alice.send(&format!("OPER alice {}", INFO_PASSWORD));
+    alice.expect_numeric(RPL_YOUREOPER);
+    let name = net.name(b).to_owned();
+    alice.send(&format!("SQUIT {} :maintenance", name));
+    assert_eq!(alice.expect_numeric(RPL_NICKOUT), "bob");
+    assert_eq!(bob.expect_numeric(RPL_NICKOUT), "alice");
+
+    alice.send("ISON bob");
+    alice.expect_no(&format!("{}: :bob", RPL_ISON), QUIET);
+}
+
+#[test]
+fn test_stopping_a_server_quits_its_clients_on_the_rest_of_the_net() {
+    let mut net = TestNetwork::new();
+    let a = net.start_server();
+    let b = net.start_linked(a);
+    let mut alice = net.client(a, "alice");
+    let mut bob = net.client(b, "bob");
+    assert_eq!(alice.expect_numeric(RPL_NICKIN), bob.nickname());
+
+    net.stop_server(b);
+    bob.expect_closed();
+    assert_eq!(alice.expect_numeric(RPL_NICKOUT), bob.nickname());
+
+    // The rest of the net keeps working without the link
+    alice.send("ISON bob alice");
+    assert_eq!(alice.expect_numeric(RPL_ISON), ":alice");
+}
+
+#[test]
+fn test_linking_to_a_peer_that_never_answers_keeps_serving_clients() {
+    let mut net = TestNetwork::new();
+    let link_timeout = Duration::from_secs(2);
+    let limits = ConnectionLimits {
+        link_timeout,
+        ..ConnectionLimits::default()
+    };
+    let server = net.start(ServerBuilder::new().limits(limits));
+    let mut alice = net.client(server, "alice");
+    // The kernel accepts the connection, but nobody ever answers the registration
+    let silent = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = silent.local_addr().unwrap().to_string();
+
+    thread::scope(|s| {
+        let started = Instant::now();
+        let linking = s.spawn(|| net.connect(server, &address));
+        thread::sleep(QUIET);
+        alice.send("ISON alice");
+        assert_eq!(alice.expect_numeric(RPL_ISON), ":alice");
+        assert!(started.elapsed() < link_timeout);
+
+        assert!(linking.join().unwrap().is_err());
+        assert!(started.elapsed() >= link_timeout);
+    });
+}
+
+#[test]
+fn test_link_dropped_without_squit_is_a_netsplit() {
+    let mut net = TestNetwork::new();
+    let a = net.start_server();
+    let proxy = LinkProxy::start(net.address(a));
+    let b = net.start(ServerBuilder::new().uplink(&proxy.address().to_string(), LINK_PASSWORD));
+    let mut alice = net.client(a, "alice");
+    let mut bob = net.client(b, "bob");
+    assert_eq!(alice.expect_numeric(RPL_NICKIN), bob.nickname());
+
+    proxy.cut();
+    assert_eq!(alice.expect_numeric(RPL_NICKOUT), bob.nickname());
+    assert_eq!(bob.expect_numeric(RPL_NICKOUT), alice.nickname());
+
+    // Neither side keeps the other's users as ghosts
+    alice.send("ISON bob");
+    alice.expect_no(&format!("{}: :bob", RPL_ISON), QUIET);
+    bob.send("ISON alice");
+    bob.expect_no(&format!("{}: :alice", RPL_ISON), QUIET);
+}
@@ -0,0 +1,81 @@
+//! Server y cliente IRC como biblioteca: el modelo de mensajes, un server que se puede correr
+//! dentro de otro programa, la conexion o la sesion de un cliente y bots de comandos sobre una
+//! sesion. El resto del modelo es interno. La interfaz GTK requiere la feature gui.
+mod cli;
+mod gui;
+mod irc;
+
+pub use cli::run;
+pub use irc::bot::{
+    command::{BotCommand, Invocation, Permission},
+    harness::BotHarness,
+    Bot, BotAction,
+};
+pub use irc::message::monitor::MonitorAction;
+pub use irc::model::{
+    client_connection::ClientConnection,
+    server::{
+        builder::{ServerBuilder, ServerHandle},
+        ConnectionLimits,
+    },
+    session::{event::Event, Events, Session, SessionError, SessionSender},
+    ConnectionError,
+};
+
+/// Modelo de mensajes: el parser generico, cada comando como mensaje tipado y su serializacion.
+pub mod message {
+    pub use crate::irc::message::{
+        admin::Admin, away::Away, cap::Cap, end_of_burst::EndOfBurst,
+        generic_message::GenericMessage, generic_mode::Mode, info::Info, invite::Invite,
+        ison::Ison, join::Join, kick::Kick, kill::Kill, kline::Kline, knock::Knock, links::Links,
+        list::List, lusers::Lusers, map::Map, monitor::Monitor, motd::Motd, names::Names,
+        nickname::Nickname, notice::Notice, oper::Oper, part::Part, password::Password,
+        private::Private, quit::Quit, rehash::Rehash, reply::Reply, restart::Restart,
+        serializer::MessageSerializer, server::Sv, server_quit::ServerQuit, stats::Stats,
+        testfilter::TestFilter, time::Time, topic::Topic, trace::Trace, unkline::Unkline,
+        user::User, userhost::Userhost, version::Version, wallops::Wallops, who::Who, whois::Whois,
+        whowas::Whowas, Command, FromGeneric, MessageError, Serializable,
+    };
+}
+
+/// Lo que parsean los objetivos de cargo fuzz del protocolo DCC, fuera de la API del crate.
+#[cfg(feature = "fuzzing")]
+pub use irc::ctcp::utils::{to_dcc_command, upgrade_dcc_command};
+
+#[cfg(test)]
+mod message_tests {
+    use crate::message::{
+        Command, FromGeneric, GenericMessage, MessageError, Private, Serializable,
+    };
+
+    #[test]
+    fn test_messages_are_parsed_and_serialized_through_the_public_api() {
+        let generic = GenericMessage::parse("PRIVMSG bob,#chan :hola que tal").unwrap();
+        assert_eq!(generic.command, Command::PrivateMessage);
+
+        let private = Private::from_generic(generic).unwrap();
+        assert_eq!(private.receivers, vec![b"bob".as_slice(), b"#chan"]);
+        assert_eq!(private.serialize(), "PRIVMSG bob,#chan :hola que tal");
+    }
+
+    #[test]
+    fn test_canonical_form_parses_back_to_itself() {
+        let canonical = GenericMessage::parse("JOIN   #a,#b")
+            .and_then(|m| m.canonical())
+            .unwrap();
+        let again = GenericMessage::parse(&canonical).and_then(|m| m.canonical());
+        assert_eq!(again, Ok(canonical));
+    }
+
+    #[test]
+    fn test_invalid_lines_are_errors() {
+        assert_eq!(
+            GenericMessage::parse("").unwrap_err(),
+            MessageError::EmptyMessage
+        );
+        assert_eq!(
+            GenericMessage::parse("NOTACOMMAND x").unwrap_err(),
+            MessageError::InvalidCommand
+        );
+    }
+}